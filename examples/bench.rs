@@ -0,0 +1,78 @@
+// Opens hundreds of anchored portals in a scrollable list and shows the pipeline counters.
+// Run with `cargo run --example bench --features perf`, then scroll the list and compare the counts
+// (e.g. reports and layout passes per portal) before and after a change.
+use dioxus::prelude::*;
+use dioxus_portal::*;
+
+const PORTAL_COUNT: usize = 300;
+
+fn main() {
+    dioxus::launch(app);
+}
+
+fn app() -> Element {
+    rsx! {
+        PortalProvider {
+            style: "width: 100vw; height: 100vh; font-family: system-ui, sans-serif;".to_string(),
+            Bench {}
+        }
+    }
+}
+
+#[component]
+fn Bench() -> Element {
+    let mut open = use_signal(|| false);
+    let mut snapshot = use_signal(PerfSnapshot::default);
+    let counters = use_perf_counters();
+
+    let total = snapshot.read().total();
+    let outlet_reports = snapshot.read().outlet_reports;
+    let portals = snapshot.read().portals.len().max(1) as f64;
+
+    rsx! {
+        div { style: "display: flex; height: 100%;",
+            div { style: "width: 320px; padding: 16px; border-right: 1px solid #e5e7eb; display: flex; flex-direction: column; gap: 8px;",
+                button { onclick: move |_| open.toggle(), if open() { "Close all" } else { "Open all" } }
+                button {
+                    onclick: {
+                        let counters = counters.clone();
+                        move |_| snapshot.set(counters.snapshot())
+                    },
+                    "Read counters"
+                }
+                button {
+                    onclick: {
+                        let counters = counters.clone();
+                        move |_| {
+                            counters.reset();
+                            snapshot.set(counters.snapshot());
+                        }
+                    },
+                    "Reset"
+                }
+                pre { style: "font-size: 12px;",
+                    "portals          {PORTAL_COUNT}\n"
+                    "outlet reports   {outlet_reports}\n"
+                    "anchor reports   {total.anchor_reports}\n"
+                    "content reports  {total.content_reports}\n"
+                    "layout passes    {total.layout_passes}\n"
+                    "layout reuses    {total.layout_reuses}\n"
+                    "renders          {total.renders}\n"
+                    "renders/portal   {total.renders as f64 / portals:.1}\n"
+                }
+            }
+            div { style: "flex: 1; overflow: auto; padding: 16px;",
+                for i in 0..PORTAL_COUNT {
+                    Portal { key: "{i}", open: open(), vertical_alignment: Alignment::End,
+                        PortalAnchor {
+                            div { style: "margin: 0 0 24px; padding: 4px 8px; border: 1px solid #d1d5db;", "Anchor {i}" }
+                        }
+                        PortalContent {
+                            div { style: "padding: 2px 6px; font-size: 11px; background: #111827; color: white;", "Content {i}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
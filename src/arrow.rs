@@ -0,0 +1,58 @@
+//! Arrow pointing from portal content to its anchor.
+//!
+//! - Content wrappers placed beside their anchor carry `data-side` (`top`, `bottom`, `left`, or `right`, after
+//!   flipping) and the anchor's center relative to the content in `--dioxus-portal-arrow-x`/`-y`.
+//! - `PortalArrow` is a rotated square rendered inside the content: its stylesheet moves it to the edge facing
+//!   the anchor and slides it along that edge to the anchor's center, so it follows flips and alignment.
+//! - It inherits the background of its parent, which therefore needs one (and usually `position: relative`
+//!   to keep the arrow in its box). The stylesheet travels with the arrow, so it also applies in top-layer,
+//!   shadow-root, and detached-window content.
+use dioxus_lib::prelude::*;
+
+/// Properties for `PortalArrow`.
+#[derive(Props, Clone, PartialEq)]
+pub struct PortalArrowProps {
+    /// Width and height of the square, half of which sticks out of the content.
+    #[props(default = 8.0)]
+    pub size: f64,
+
+    /// Extra declarations, e.g. a `border` matching the content's.
+    #[props(default)]
+    pub style: String,
+
+    #[props(extends = GlobalAttributes)]
+    attributes: Vec<Attribute>,
+}
+
+/// Arrow placed on the edge of the enclosing portal content that faces the anchor.
+#[component]
+pub fn PortalArrow(props: PortalArrowProps) -> Element {
+    rsx! {
+        style { {ARROW_STYLE} }
+        span {
+            "data-dioxus-portal-arrow" : "",
+            "aria-hidden" : "true",
+            style : "--dioxus-portal-arrow-size: {props.size}px; {props.style}",
+            ..props.attributes,
+        }
+    }
+}
+
+/// Placement of the arrow per side. Sides without an arrow (content over its anchor) hide it.
+const ARROW_STYLE: &str = r#"
+[data-dioxus-portal-arrow] {
+  display: none;
+  position: absolute;
+  width: var(--dioxus-portal-arrow-size);
+  height: var(--dioxus-portal-arrow-size);
+  background: inherit;
+  border: inherit;
+  pointer-events: none;
+  transform: translate(-50%, -50%) rotate(45deg);
+}
+[data-side] [data-dioxus-portal-arrow] { display: block; }
+[data-side="top"] [data-dioxus-portal-arrow] { top: 100%; left: var(--dioxus-portal-arrow-x, 50%); border-top: none; border-left: none; }
+[data-side="bottom"] [data-dioxus-portal-arrow] { top: 0; left: var(--dioxus-portal-arrow-x, 50%); border-bottom: none; border-right: none; }
+[data-side="left"] [data-dioxus-portal-arrow] { left: 100%; top: var(--dioxus-portal-arrow-y, 50%); border-bottom: none; border-left: none; }
+[data-side="right"] [data-dioxus-portal-arrow] { left: 0; top: var(--dioxus-portal-arrow-y, 50%); border-top: none; border-right: none; }
+"#;
@@ -0,0 +1,66 @@
+//! Client-only work under server-side rendering.
+//!
+//! - Effects never run on the server. The first `use_client_mounted` effect that runs marks the thread as a client,
+//!   so `on_client` stays `false` while rendering on the server and before the client's first effects.
+//! - Everything that talks to JS waits for that: tasks await `client_ready`, render-time calls check
+//!   `on_client`, and observers start from effects. The server therefore issues no eval.
+//! - `RectObserver` renders its element id only once mounted on the client. Ids are allocated per process, so
+//!   the ones of the server markup would not match the ones the client allocates while hydrating; without
+//!   them, hydration matches the server markup and every observer registers once, under the client's id.
+use std::{
+    cell::{Cell, RefCell},
+    future::poll_fn,
+    rc::Rc,
+    task::{Poll, Waker},
+};
+
+use dioxus_lib::core::provide_root_context;
+use dioxus_lib::prelude::*;
+
+thread_local! {
+    static CLIENT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Tasks waiting in `client_ready`. Kept in the root scope rather than the thread, so the ones of a server
+/// render are dropped with its `VirtualDom`.
+#[derive(Clone, Default)]
+struct Waiting(Rc<RefCell<Vec<Waker>>>);
+
+fn waiting() -> Waiting {
+    try_consume_context::<Waiting>().unwrap_or_else(|| provide_root_context(Waiting::default()))
+}
+
+/// Whether an effect of this crate ran on this thread, i.e. this is a client past its first render.
+pub(crate) fn on_client() -> bool {
+    CLIENT.with(Cell::get)
+}
+
+/// Completes once this thread is known to be a client. Never completes on the server.
+pub(crate) async fn client_ready() {
+    poll_fn(|cx| {
+        if on_client() {
+            return Poll::Ready(());
+        }
+        let waiting = waiting();
+        let mut wakers = waiting.0.borrow_mut();
+        if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    })
+    .await
+}
+
+/// `true` once the calling component is mounted on the client (never on the server).
+pub(crate) fn use_client_mounted() -> Signal<bool> {
+    let mut mounted = use_signal(|| false);
+    use_effect(move || {
+        if !CLIENT.with(|client| client.replace(true)) {
+            for waker in waiting().0.take() {
+                waker.wake();
+            }
+        }
+        mounted.set(true);
+    });
+    mounted
+}
@@ -0,0 +1,113 @@
+//! Suggestion popup for combobox / autocomplete inputs, built on the portal primitives.
+//!
+//! - `ComboboxPopup` anchors its children (the suggestion list) to `input`, keeping it attached while the input
+//!   scrolls with the page or a scroll container, and hiding it while the input is scrolled out of view.
+//! - The list takes at most `max_height`, shrunk to the room left between the input and the edge of the placement
+//!   bounds on the side it opens to (`--dioxus-portal-available-height`), and flips above the input when there is
+//!   more room there.
+//! - Focus never leaves the input: nothing is focused on open, and presses inside the list have their default
+//!   (moving focus) prevented. Presses on the input do not dismiss the list; Escape and outside clicks do, through
+//!   `on_dismiss`. Highlighting suggestions stays with the caller, typically through `aria-activedescendant`.
+use dioxus_lib::html::GlobalAttributesExtension;
+use dioxus_lib::prelude::*;
+
+use crate::focus::{set_trigger_attributes, use_trigger_state};
+use crate::{
+    alloc_component_id, ContentRole, DismissReason, Layer, PlacementConfig, Portal, PortalAnchor,
+    PortalContent,
+};
+
+/// Properties for `ComboboxPopup`.
+#[derive(Props, Clone, PartialEq)]
+pub struct ComboboxPopupProps {
+    /// Whether the suggestions are shown, typically while the input is focused and has matches.
+    pub open: bool,
+
+    /// The text input the list is attached to.
+    pub input: Element,
+
+    /// Called on Escape and on clicks outside both the input and the list.
+    #[props(default)]
+    pub on_dismiss: Callback<DismissReason>,
+
+    /// Placement against the input.
+    #[props(default = PlacementConfig::bottom().align_start().offset(4.0).flip())]
+    pub placement: PlacementConfig,
+
+    /// Height past which the list scrolls, in CSS pixels. Less room around the input shrinks it further.
+    #[props(default = 320.0)]
+    pub max_height: f64,
+
+    /// Make the list at least as wide as the input.
+    #[props(default = true)]
+    pub match_input_width: bool,
+
+    /// Stacking layer.
+    #[props(default = Layer::Dropdown.base(), into)]
+    pub layer: i32,
+
+    /// Declarations appended to the default style of the list box.
+    #[props(default)]
+    pub style: String,
+
+    /// Suggestions, typically `role="option"` elements.
+    children: Element,
+}
+
+/// Suggestion list attached to an input without taking its focus.
+#[component]
+pub fn ComboboxPopup(props: ComboboxPopupProps) -> Element {
+    let popup_id = use_hook(|| alloc_component_id("dioxus-portal-combobox-"));
+    let input_id = format!("{popup_id}-input");
+    let mut visible = use_signal(|| true);
+    use_trigger_state(&input_id, &popup_id, "listbox", props.open, false);
+
+    {
+        let input_id = input_id.clone();
+        use_effect(move || {
+            set_trigger_attributes(
+                &input_id,
+                &[
+                    ("role", Some("combobox".to_string())),
+                    ("aria-autocomplete", Some("list".to_string())),
+                ],
+            );
+        });
+    }
+
+    let on_dismiss = props.on_dismiss;
+    let hidden_style = if visible() { "" } else { "visibility: hidden;" };
+
+    rsx! {
+        Portal {
+            open : props.open,
+            layer : props.layer,
+            config : props.placement,
+            on_dismiss : move |reason| on_dismiss.call(reason),
+
+            PortalAnchor {
+                style : "display: inline-block;",
+                inside_content : true,
+                on_visibility_changed : move |shown| visible.set(shown),
+                span {
+                    id : input_id,
+                    style : "display: contents;",
+                    {props.input}
+                }
+            }
+
+            PortalContent {
+                id : popup_id,
+                role : ContentRole::Listbox,
+                match_anchor_width : props.match_input_width,
+                style : hidden_style,
+                div {
+                    style : "box-sizing: border-box; max-height: min({props.max_height}px, var(--dioxus-portal-available-height, {props.max_height}px)); overflow-y: auto; padding: 4px; border: 1px solid #e5e7eb; border-radius: 8px; background: white; box-shadow: 0 8px 24px rgba(0,0,0,.12); {props.style}",
+                    // Keeps focus (and the caret) in the input while suggestions are clicked or the list scrolled
+                    onmousedown : move |evt: MouseEvent| evt.prevent_default(),
+                    {props.children}
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Ready-made context menu built on `Portal` and the dropdown menu items.
+//!
+//! - `ContextMenu` wraps its children (the target area) and opens `menu` at the pointer on right-click, on the
+//!   context menu key / Shift+F10, and on a touch long-press. The native menu is suppressed. The menu is placed
+//!   against a zero-size `anchor_rect` at the pointer, below and to the right of it, flipping on either axis near
+//!   the edges of the viewport.
+//! - The menu is a `PortalMenuContent` holding `MenuItem`s like `DropdownMenu`'s, so it gets the same keyboard
+//!   navigation, typeahead, and close-on-select. Closing returns focus to the element focused before it opened.
+//! - A long-press is cancelled when the touch moves or ends early. Its `pointerup` is kept from the provider,
+//!   which would otherwise take it for an outside tap and close the menu it just opened.
+use dioxus_lib::html::geometry::ClientPoint;
+use dioxus_lib::prelude::*;
+
+use crate::dropdown_menu::MenuPanel;
+use crate::focus::{remember_focus, return_focus};
+use crate::timer::sleep;
+use crate::{
+    alloc_component_id, Layer, OverflowPolicy, PlacementConfig, Point, Portal, Rect, RectExt,
+};
+
+/// Default placement of a `ContextMenu`: below and to the right of the pointer, flipping on both axes.
+pub const CONTEXT_MENU_PLACEMENT: PlacementConfig = {
+    let mut config = PlacementConfig::bottom().align_start().flip();
+    config.horizontal.overflow_policy = OverflowPolicy::Flip;
+    config
+};
+
+/// Properties for `ContextMenu`.
+#[derive(Props, Clone, PartialEq)]
+pub struct ContextMenuProps {
+    /// `MenuItem`s, `MenuCheckboxItem`s, and `MenuSeparator`s of the menu.
+    pub menu: Element,
+
+    /// Called when the menu opens or closes.
+    #[props(default)]
+    pub on_open_change: Callback<bool>,
+
+    /// Leave the native context menu alone and never open.
+    #[props(default = false)]
+    pub disabled: bool,
+
+    /// Touch duration that opens the menu.
+    #[props(default = 500)]
+    pub long_press_ms: u64,
+
+    /// Placement against the pointer. Its `anchor_rect` is replaced by the pointer position.
+    #[props(default = CONTEXT_MENU_PLACEMENT)]
+    pub placement: PlacementConfig,
+
+    /// Stacking layer.
+    #[props(default = Layer::Dropdown.base(), into)]
+    pub layer: i32,
+
+    /// Wrap around when moving past the first/last item.
+    #[props(default = true)]
+    pub loop_focus: bool,
+
+    /// Declarations appended to the default style of the menu panel.
+    #[props(default)]
+    pub style: String,
+
+    /// Target area.
+    children: Element,
+}
+
+/// Area opening a menu at the pointer on right-click or long-press.
+#[component]
+pub fn ContextMenu(props: ContextMenuProps) -> Element {
+    let menu_id = use_hook(|| alloc_component_id("dioxus-portal-context-menu-"));
+    let mut position = use_signal(|| None::<Point>);
+    let mut press = use_signal(|| None::<LongPress>);
+    let mut generation = use_signal(|| 0_u64);
+    let on_open_change = props.on_open_change;
+
+    let open_at = {
+        let menu_id = menu_id.clone();
+        move |point: ClientPoint| {
+            if position.peek().is_none() {
+                remember_focus(&menu_id);
+                on_open_change.call(true);
+            }
+            position.set(Some(Point::new(point.x, point.y)));
+        }
+    };
+    let close = {
+        let menu_id = menu_id.clone();
+        move || {
+            if position.peek().is_some() {
+                return_focus(&menu_id, &menu_id);
+                position.set(None);
+                on_open_change.call(false);
+            }
+        }
+    };
+    let mut cancel_press = move || {
+        *generation.write() += 1;
+        press.set(None);
+    };
+
+    let disabled = props.disabled;
+    let long_press_ms = props.long_press_ms;
+    let mut config = props.placement;
+    config.anchor_rect = position().map(|point| Rect::from_xywh(point.x, point.y, 0.0, 0.0));
+
+    rsx! {
+        Portal {
+            open : position().is_some(),
+            layer : props.layer,
+            config : config,
+            on_dismiss : {
+                let mut close = close.clone();
+                move |_| close()
+            },
+
+            div {
+                style : "display: contents; -webkit-touch-callout: none;",
+                oncontextmenu : {
+                    let mut open_at = open_at.clone();
+                    move |evt: MouseEvent| {
+                        if disabled {
+                            return;
+                        }
+                        evt.prevent_default();
+                        // Android fires it for a long-press of its own, which may come before or after ours
+                        let touch = *press.peek();
+                        match touch {
+                            Some(touch) if touch.fired => {}
+                            Some(touch) => {
+                                *generation.write() += 1;
+                                press.set(Some(LongPress { fired: true, ..touch }));
+                                open_at(touch.start);
+                            }
+                            None => open_at(evt.client_coordinates()),
+                        }
+                    }
+                },
+                onpointerdown : move |evt: PointerEvent| {
+                    if disabled || evt.pointer_type() != "touch" {
+                        return;
+                    }
+                    cancel_press();
+                    let started = LongPress { pointer_id: evt.pointer_id(), start: evt.client_coordinates(), fired: false };
+                    press.set(Some(started));
+                    let current = *generation.peek();
+                    let mut open_at = open_at.clone();
+                    spawn(async move {
+                        sleep(long_press_ms).await;
+                        if *generation.peek() == current {
+                            press.set(Some(LongPress { fired: true, ..started }));
+                            open_at(started.start);
+                        }
+                    });
+                },
+                onpointermove : move |evt: PointerEvent| {
+                    let moved = press.peek().is_some_and(|press| !press.fired && press.is_moved(&evt));
+                    if moved {
+                        cancel_press();
+                    }
+                },
+                onpointerup : move |evt: PointerEvent| {
+                    let fired = press.peek().is_some_and(|press| press.fired && press.pointer_id == evt.pointer_id());
+                    if fired {
+                        evt.stop_propagation();
+                    }
+                    cancel_press();
+                },
+                onpointercancel : move |_| cancel_press(),
+                {props.children}
+            }
+
+            MenuPanel {
+                id : menu_id,
+                layer : props.layer,
+                loop_focus : props.loop_focus,
+                style : props.style,
+                on_close : move |_| {
+                    let mut close = close.clone();
+                    close()
+                },
+                {props.menu}
+            }
+        }
+    }
+}
+
+/// Touch that may become a long-press.
+#[derive(Clone, Copy, PartialEq)]
+struct LongPress {
+    pointer_id: i32,
+    start: ClientPoint,
+    fired: bool, // The menu was opened by this press
+}
+
+/// How far a touch may move and still be a long-press, in CSS pixels.
+const LONG_PRESS_SLOP: f64 = 10.0;
+
+impl LongPress {
+    fn is_moved(&self, evt: &PointerEvent) -> bool {
+        evt.pointer_id() == self.pointer_id
+            && (evt.client_coordinates() - self.start).length() > LONG_PRESS_SLOP
+    }
+}
@@ -0,0 +1,108 @@
+//! Placement through CSS anchor positioning (`PortalProviderProps::css_anchor_positioning`).
+//!
+//! - Support for `anchor-name` and `position-try-fallbacks` is checked once per provider, on the client. Until the
+//!   answer arrives, and wherever it is negative, portals keep the measured layout.
+//! - The `PortalAnchor` wrapper is named `--dioxus-portal-anchor-<id>`, and each content that CSS can place is
+//!   `position: fixed` against it through `position-anchor` and `anchor()` insets. The browser keeps it attached
+//!   through scrolls and resizes, so neither the content nor (once every content of the portal is placed this
+//!   way) the anchor is measured.
+//! - CSS can place a content anchored to a `PortalAnchor` of this document, without `anchor_rect`, and with
+//!   `OverflowPolicy::Ignore` or `Flip` on both axes. `Flip` becomes a `flip-block`/`flip-inline` fallback;
+//!   `Clamp` and `Shrink` have no CSS counterpart and keep the measured layout.
+use dioxus_lib::{document, prelude::*};
+
+use crate::{Alignment, AxisParam, OverflowPolicy, PortalId, Spread};
+
+/// `true` once the browser is known to support anchor positioning, when `enabled`.
+pub(crate) fn use_css_anchor_support(enabled: bool) -> Signal<bool> {
+    let mut supported = use_signal(|| false);
+    use_hook(|| {
+        if !enabled || !crate::has_eval() {
+            return;
+        }
+        spawn(async move {
+            crate::client::client_ready().await;
+            let mut eval = document::eval(JS_CODE_OF_SUPPORTS);
+            if let Ok(true) = eval.recv::<bool>().await {
+                supported.set(true);
+            }
+        });
+    });
+    supported
+}
+
+const JS_CODE_OF_SUPPORTS: &str = r#"
+    try {
+      dioxus.send(
+        typeof CSS !== "undefined" &&
+          CSS.supports("anchor-name: --a") &&
+          CSS.supports("position-try-fallbacks: flip-block")
+      );
+    } catch (e) {
+      dioxus.send(false);
+    }
+"#;
+
+/// Anchor name of the `PortalAnchor` of portal `id`.
+pub(crate) fn anchor_name(id: PortalId) -> String {
+    format!("--dioxus-portal-anchor-{}", id.0)
+}
+
+/// Whether CSS can express the placement along both axes.
+pub(crate) fn can_place(param_v: &AxisParam, param_h: &AxisParam) -> bool {
+    [param_v, param_h].iter().all(|param| {
+        matches!(
+            param.overflow_policy,
+            OverflowPolicy::Ignore | OverflowPolicy::Flip
+        )
+    })
+}
+
+/// Declarations placing a content against the anchor of portal `id`.
+pub(crate) fn placement_style(id: PortalId, param_v: &AxisParam, param_h: &AxisParam) -> String {
+    let (inset_v, center_v) = axis_inset(param_v, "top", "bottom");
+    let (inset_h, center_h) = axis_inset(param_h, "left", "right");
+    let translate = if center_v || center_h {
+        format!(
+            " translate: {} {};",
+            if center_h { "-50%" } else { "0" },
+            if center_v { "-50%" } else { "0" }
+        )
+    } else {
+        String::new()
+    };
+    let flips = match (param_v.overflow_policy, param_h.overflow_policy) {
+        (OverflowPolicy::Flip, OverflowPolicy::Flip) => {
+            " position-try-fallbacks: flip-block, flip-inline, flip-block flip-inline;"
+        }
+        (OverflowPolicy::Flip, _) => " position-try-fallbacks: flip-block;",
+        (_, OverflowPolicy::Flip) => " position-try-fallbacks: flip-inline;",
+        _ => "",
+    };
+    format!(
+        "position: fixed; position-anchor: {}; {inset_v} {inset_h}{translate}{flips}",
+        anchor_name(id)
+    )
+}
+
+/// Inset of one axis, whose edges are `start` and `end`, mirroring `calc_content_range`, and whether the content
+/// is centered on the anchor.
+fn axis_inset(param: &AxisParam, start: &str, end: &str) -> (String, bool) {
+    let offset = param.offset;
+    match (param.alignment, param.spread) {
+        (Alignment::Center, _) => (format!("{start}: calc(anchor(center) + {offset}px);"), true),
+        (Alignment::Start, Spread::Inside) => (
+            format!("{start}: calc(anchor({start}) + {offset}px);"),
+            false,
+        ),
+        (Alignment::Start, Spread::Outside) => {
+            (format!("{end}: calc(anchor({start}) + {offset}px);"), false)
+        }
+        (Alignment::End, Spread::Inside) => {
+            (format!("{end}: calc(anchor({end}) + {offset}px);"), false)
+        }
+        (Alignment::End, Spread::Outside) => {
+            (format!("{start}: calc(anchor({end}) + {offset}px);"), false)
+        }
+    }
+}
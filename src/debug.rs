@@ -0,0 +1,117 @@
+//! Visual diagnostics for portal placement (`debug` feature).
+//!
+//! - The outlet and content entries record the rectangles they compute into `DebugRects` held by the provider.
+//! - `PortalDebugLayer` draws those rectangles, plus every anchor, as labeled translucent boxes fixed to the
+//!   viewport (the coordinate system of all placement calculations). It ignores pointer events.
+use std::collections::HashMap;
+
+use dioxus_lib::prelude::*;
+
+use crate::rect_observer::Rect;
+use crate::{PortalId, PortalProviderContext};
+
+/// Rectangles recorded for the debug layer, in viewport coordinates.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub(crate) struct DebugRects {
+    /// Outlet rectangles by target name (`None` is the provider's own outlet).
+    pub outlets: HashMap<Option<String>, Rect>,
+    /// Placement bounds by target name (the outlet shrunk by `bounds_padding`).
+    pub bounds: HashMap<Option<String>, Rect>,
+    /// Resolved content rectangles by portal and content.
+    pub contents: HashMap<(PortalId, u64), Rect>,
+}
+
+// Writes are skipped when nothing changed, so re-renders of the recording components do not notify the layer
+
+/// Records the rectangle and placement bounds of the outlet for `target`.
+pub(crate) fn record_outlet(
+    mut debug_rects: Signal<DebugRects>,
+    target: Option<String>,
+    outlet: Rect,
+    bounds: Rect,
+) {
+    let changed = {
+        let current = debug_rects.peek();
+        current.outlets.get(&target) != Some(&outlet)
+            || current.bounds.get(&target) != Some(&bounds)
+    };
+    if changed {
+        let mut w = debug_rects.write();
+        w.outlets.insert(target.clone(), outlet);
+        w.bounds.insert(target, bounds);
+    }
+}
+
+/// Records the resolved rectangle of a content, or removes it when `rect` is `None`.
+pub(crate) fn record_content(
+    mut debug_rects: Signal<DebugRects>,
+    key: (PortalId, u64),
+    rect: Option<Rect>,
+) {
+    if debug_rects.peek().contents.get(&key).copied() != rect {
+        let mut w = debug_rects.write();
+        match rect {
+            Some(rect) => w.contents.insert(key, rect),
+            None => w.contents.remove(&key),
+        };
+    }
+}
+
+/// Properties for `PortalDebugLayer`.
+#[derive(Props, Clone, PartialEq)]
+pub struct PortalDebugLayerProps {
+    /// z-index of the layer. Defaults to above any portal slot.
+    #[props(default = i32::MAX as i64)]
+    pub z_index: i64,
+}
+
+/// Draws anchor, outlet, bounds, and content rectangles of the enclosing `PortalProvider`.
+/// Place it anywhere inside the provider while diagnosing placement.
+#[component]
+pub fn PortalDebugLayer(props: PortalDebugLayerProps) -> Element {
+    let Some(provider_ctx) = try_use_context::<PortalProviderContext>() else {
+        return rsx! {};
+    };
+    let debug_rects = provider_ctx.debug_rects.read();
+    let entries = provider_ctx.entries.read();
+
+    let mut boxes = Vec::new();
+    for (target, rect) in debug_rects.outlets.iter() {
+        let label = format!("outlet {}", target.as_deref().unwrap_or("(default)"));
+        boxes.push((label, *rect, OUTLET_COLOR));
+    }
+    for (target, rect) in debug_rects.bounds.iter() {
+        let label = format!("bounds {}", target.as_deref().unwrap_or("(default)"));
+        boxes.push((label, *rect, BOUNDS_COLOR));
+    }
+    for data in entries.values().filter(|data| data.is_open()) {
+        if let Some(rect) = crate::anchor_rect_of(&provider_ctx, data) {
+            boxes.push((format!("{} anchor", data.id), rect, ANCHOR_COLOR));
+        }
+    }
+    for ((id, content_id), rect) in debug_rects.contents.iter() {
+        if entries.get(id).is_some_and(|data| data.is_open()) {
+            boxes.push((format!("{id} content {content_id}"), *rect, CONTENT_COLOR));
+        }
+    }
+
+    rsx! {
+        div {
+            style: "position: fixed; top: 0; left: 0; width: 0; height: 0; pointer-events: none; z-index: {props.z_index};",
+            for (label, rect, color) in boxes {
+                div {
+                    style: "position: fixed; box-sizing: border-box; top: {rect.origin.y}px; left: {rect.origin.x}px; width: {rect.size.width}px; height: {rect.size.height}px; border: 1px dashed {color}; background: {color}22;",
+                    span {
+                        style: "position: absolute; top: 0; left: 0; padding: 0 2px; font: 10px monospace; color: white; background: {color}; white-space: nowrap;",
+                        "{label}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+const OUTLET_COLOR: &str = "#2563eb";
+const BOUNDS_COLOR: &str = "#16a34a";
+const ANCHOR_COLOR: &str = "#d97706";
+const CONTENT_COLOR: &str = "#db2777";
@@ -0,0 +1,182 @@
+//! Portal content in its own native window (`desktop` feature, `PortalWindow::Detached`).
+//!
+//! - While a detached portal is open, the `Portal` renders a `DetachedWindow`, which opens a frameless,
+//!   always-on-top window through `dioxus-desktop`. The window's `VirtualDom` renders the component given to
+//!   `PortalWindow::Detached` below a `DetachedRoot`, with the portal's id and the provider as root contexts.
+//!   Elements of the main `VirtualDom` cannot be rendered there, so the `PortalContent`s are not shown.
+//! - The window is placed like a content of the window's size: the anchor's viewport rectangle is shifted by the
+//!   position of the main window's client area on screen (in logical pixels) and laid out with the portal's
+//!   placement within the main window's monitor, so alignments and flips apply. It follows the anchor as long as
+//!   the main window re-measures it, and is resized to fit the component.
+//! - `DetachedWindow` only subscribes to the portal's own entry and to the measured size of the component.
+//! - Closing or unmounting the portal closes the window.
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use dioxus_desktop::{Config, DesktopContext, LogicalPosition, LogicalSize, WindowBuilder};
+use dioxus_lib::core::{spawn_forever, use_drop};
+use dioxus_lib::prelude::*;
+use euclid::{vec2, Size2D};
+
+use crate::model::{calc_content_position, Pixels};
+use crate::geometry::RectExt;
+use crate::rect_observer::{Rect, RectObserver};
+use crate::{
+    anchor_rect_of, use_entry_data, PortalContext, PortalEntryData, PortalId, PortalProviderContext,
+};
+
+#[derive(Props, Clone)]
+pub(crate) struct DetachedWindowProps {
+    pub id: PortalId,
+    pub content: fn() -> Element,
+}
+
+impl PartialEq for DetachedWindowProps {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && std::ptr::fn_addr_eq(self.content, other.content)
+    }
+}
+
+/// Opens the window of portal `id` on mount and closes it on unmount. Changes of `content` while the window is
+/// open are ignored.
+#[component]
+pub(crate) fn DetachedWindow(props: DetachedWindowProps) -> Element {
+    let provider_ctx = use_context::<PortalProviderContext>();
+    let portal_ctx = use_context::<PortalContext>();
+    let id = props.id;
+    let main = use_hook(dioxus_desktop::window);
+    let entry = use_entry_data(provider_ctx.entries, id);
+    // Size of the component, measured in the window
+    let size = use_signal(|| None::<Size2D<f64, Pixels>>);
+    // The window once `dioxus-desktop` created it, and whether the portal unmounted before that
+    let window = use_hook(|| Rc::new(RefCell::new(None::<DesktopContext>)));
+    let dropped = use_hook(|| Rc::new(Cell::new(false)));
+
+    let position = {
+        let main = main.clone();
+        let provider_ctx = provider_ctx.clone();
+        move || {
+            let entry = entry.peek();
+            let data = entry.as_ref()?;
+            let anchor = anchor_rect_of(&provider_ctx, data)?;
+            window_position(&main, data, anchor, size.peek().unwrap_or_default())
+        }
+    };
+
+    use_hook(|| {
+        let dom = VirtualDom::new_with_props(
+            DetachedRoot,
+            DetachedRootProps {
+                content: props.content,
+                size,
+            },
+        )
+        .with_root_context(portal_ctx.clone())
+        .with_root_context(provider_ctx.clone());
+        let mut builder = WindowBuilder::new()
+            .with_title(format!("{id}"))
+            .with_decorations(false)
+            .with_always_on_top(true)
+            .with_resizable(false)
+            .with_visible(true);
+        if let Some(position) = position() {
+            builder = builder.with_position(position);
+        }
+        let pending = main.new_window(dom, Config::new().with_window(builder).with_menu(None));
+        let window = window.clone();
+        let dropped = dropped.clone();
+        let position = position.clone();
+        // Not tied to this scope, so a window created after the portal unmounted is still closed
+        spawn_forever(async move {
+            let created = pending.resolve().await;
+            if dropped.get() {
+                created.close();
+            } else {
+                // The anchor or the size may have changed while the window was being created
+                if let Some(position) = position() {
+                    created.window.set_outer_position(position);
+                }
+                *window.borrow_mut() = Some(created);
+            }
+        });
+    });
+
+    // Follow the anchor and the size of the component
+    let _subscribe = (entry.read(), size.read());
+    if let (Some(window), Some(position)) = (window.borrow().as_ref(), position()) {
+        window.window.set_outer_position(position);
+    }
+
+    use_drop(move || {
+        dropped.set(true);
+        if let Some(window) = window.borrow_mut().take() {
+            window.close();
+        }
+    });
+
+    rsx! {}
+}
+
+/// Screen position of the window of a portal anchored at `anchor`, placed like a content of `size` within the
+/// main window's monitor. `None` while the main window's position or monitor is unknown.
+fn window_position(
+    main: &DesktopContext,
+    data: &PortalEntryData,
+    anchor: Rect,
+    size: Size2D<f64, Pixels>,
+) -> Option<LogicalPosition<f64>> {
+    let scale = main.window.scale_factor();
+    let client = main.window.inner_position().ok()?.to_logical::<f64>(scale);
+    let monitor = main.window.current_monitor()?;
+    let origin = monitor.position().to_logical::<f64>(scale);
+    let extent = monitor.size().to_logical::<f64>(scale);
+    let bounds = Rect::from_xywh(origin.x, origin.y, extent.width, extent.height);
+    let anchor = anchor.translate(vec2(client.x, client.y));
+    let (position, _) = calc_content_position(
+        data.vertical_param,
+        data.horizontal_param,
+        size,
+        Some(anchor),
+        bounds,
+        None,
+    );
+    Some(LogicalPosition::new(position.x, position.y))
+}
+
+#[derive(Props, Clone)]
+struct DetachedRootProps {
+    content: fn() -> Element,
+    size: Signal<Option<Size2D<f64, Pixels>>>,
+}
+
+impl PartialEq for DetachedRootProps {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::fn_addr_eq(self.content, other.content) && self.size == other.size
+    }
+}
+
+/// Root of the detached window: the portal's component, with the window sized to fit it and the size reported
+/// to the `DetachedWindow`.
+#[component]
+fn DetachedRoot(props: DetachedRootProps) -> Element {
+    let window = use_hook(dioxus_desktop::window);
+    let mut size = props.size;
+    #[allow(non_snake_case)]
+    let Content = props.content;
+
+    rsx! {
+        RectObserver {
+            style: "width: fit-content; height: fit-content;",
+            on_rect_changed: move |rect: Rect| {
+                window.window.set_inner_size(LogicalSize::new(rect.width(), rect.height()));
+                // The signal belongs to the `DetachedWindow`, which may unmount before the window closed
+                if let Ok(mut size) = size.try_write() {
+                    *size = Some(rect.size);
+                }
+            },
+            Content {}
+        }
+    }
+}
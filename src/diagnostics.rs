@@ -0,0 +1,56 @@
+//! Development-mode warnings for common misconfigurations.
+//!
+//! - Warnings are written to the browser console with `console.warn`, once per component and kind.
+//! - In release builds (`debug_assertions` off) nothing is logged and the checks cost a hook slot only.
+//! - `error` is logged in every build; it is used when a component falls back to a degraded rendering.
+//! - On the server, without eval, or before the client's first effects, messages go to `tracing` instead of the
+//!   console (with the `tracing` feature; otherwise they are dropped).
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
+
+use dioxus_lib::{document, prelude::*};
+
+/// Per-component record of the warnings already logged.
+#[derive(Clone, Default)]
+pub(crate) struct Diagnostics(Rc<RefCell<HashSet<&'static str>>>);
+
+impl Diagnostics {
+    /// Logs `message` unless a warning of the same `kind` was already logged by this component.
+    pub fn warn(&self, kind: &'static str, message: &str) {
+        if cfg!(debug_assertions) && self.0.borrow_mut().insert(kind) {
+            warn(message);
+        }
+    }
+}
+
+/// Creates the warning record of the calling component.
+pub(crate) fn use_diagnostics() -> Diagnostics {
+    use_hook(Diagnostics::default)
+}
+
+/// Logs `message` to the console in debug builds.
+pub(crate) fn warn(message: &str) {
+    if cfg!(debug_assertions) {
+        log("warn", message);
+    }
+}
+
+/// Logs `message` to the console in every build, for misuse that changes what is rendered.
+pub(crate) fn error(message: &str) {
+    log("error", message);
+}
+
+fn log(level: &str, message: &str) {
+    if !crate::has_eval() || !crate::client::on_client() {
+        #[cfg(feature = "tracing")]
+        match level {
+            "error" => tracing::error!("{message}"),
+            _ => tracing::warn!("{message}"),
+        }
+        return;
+    }
+    let message = message
+        .replace('\\', "\\\\")
+        .replace('`', "\\`")
+        .replace("${", "\\${");
+    document::eval(&format!("console.{level}(`dioxus-portal: {message}`);"));
+}
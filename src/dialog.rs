@@ -0,0 +1,187 @@
+//! Ready-made modal dialog built on the portal primitives.
+//!
+//! - `Dialog` bundles what a modal needs: `ContentRole::Dialog` content centered in the outlet on the
+//!   `Layer::Modal` layer, a scrim (`PortalOverlay`) under it, a focus trap, and a page scroll lock while open.
+//! - Escape and scrim clicks are requests: they go through `on_dismiss_request`, which can refuse them (e.g. to
+//!   confirm discarding unsaved changes), before the dialog closes. The scrim blocks events, so nothing below it
+//!   reacts to the click.
+//! - Opening records the focused element and focuses the first tabbable element of the dialog; closing gives focus
+//!   back to the recorded element (see `focus`).
+use dioxus_lib::html::GlobalAttributesExtension;
+use dioxus_lib::prelude::*;
+
+use crate::disclosure::use_disclosure;
+use crate::focus::{focus_first, remember_focus, return_focus, FocusTrap};
+use crate::scroll_lock::use_scroll_lock;
+use crate::{
+    alloc_component_id, Alignment, ContentRole, DismissReason, Layer, OverlayClick,
+    PlacementConfig, Portal, PortalContent, PortalOverlay, Spread,
+};
+
+/// Placement of a `Dialog`: centered in the outlet.
+pub const DIALOG_PLACEMENT: PlacementConfig = {
+    let mut config = PlacementConfig::bottom();
+    config.vertical.alignment = Alignment::Center;
+    config.vertical.spread = Spread::Inside;
+    config
+};
+
+/// Properties for `Dialog`.
+#[derive(Props, Clone, PartialEq)]
+pub struct DialogProps {
+    /// Element opening the dialog, typically a button. Without it, open the dialog with `open`.
+    #[props(optional)]
+    pub trigger: Option<Element>,
+
+    /// Controlled open state. When unset, the dialog keeps its own.
+    #[props(optional)]
+    pub open: Option<bool>,
+
+    /// Called with the requested open state on trigger clicks and accepted dismissals.
+    #[props(default)]
+    pub on_open_change: Callback<bool>,
+
+    /// Decides whether an Escape press or scrim click closes the dialog. Every request is accepted when unset.
+    #[props(optional)]
+    pub on_dismiss_request: Option<Callback<DismissReason, bool>>,
+
+    /// Heading of the dialog, which also labels it (`aria-labelledby`).
+    #[props(optional, into)]
+    pub title: Option<String>,
+
+    /// Close on Escape.
+    #[props(default = true)]
+    pub close_on_escape: bool,
+
+    /// Close on clicks on the scrim.
+    #[props(default = true)]
+    pub close_on_overlay_click: bool,
+
+    /// Keep Tab and Shift+Tab inside the dialog.
+    #[props(default = true)]
+    pub trap_focus: bool,
+
+    /// Keep the page from scrolling while the dialog is open.
+    #[props(default = true)]
+    pub lock_scroll: bool,
+
+    /// Return focus to the element focused before opening on close.
+    #[props(default = true)]
+    pub restore_focus: bool,
+
+    /// Stacking layer.
+    #[props(default = Layer::Modal.base(), into)]
+    pub layer: i32,
+
+    /// Declarations appended to the default style of the dialog box.
+    #[props(default)]
+    pub style: String,
+
+    /// Declarations appended to the default style of the scrim.
+    #[props(default)]
+    pub overlay_style: String,
+
+    /// Body of the dialog.
+    children: Element,
+}
+
+/// Modal dialog with a scrim, focus trap, and scroll lock.
+#[component]
+pub fn Dialog(props: DialogProps) -> Element {
+    let dialog_id = use_hook(|| alloc_component_id("dioxus-portal-dialog-"));
+    let title_id = format!("{dialog_id}-title");
+    let mut disclosure = use_disclosure(props.open, props.on_open_change);
+    let open = disclosure.open;
+    use_scroll_lock(open && props.lock_scroll);
+
+    // Focus is recorded when the content mounts, right before it moves in, so it is given back on close
+    let restore_focus = props.restore_focus;
+    let mut was_open = use_signal(|| false);
+    {
+        let dialog_id = dialog_id.clone();
+        use_effect(use_reactive!(|open| {
+            if *was_open.peek() && !open && restore_focus {
+                return_focus(&dialog_id, &dialog_id);
+            }
+            was_open.set(open);
+        }));
+    }
+    let onmounted = {
+        let dialog_id = dialog_id.clone();
+        move |_| {
+            remember_focus(&dialog_id);
+            focus_first(&dialog_id);
+        }
+    };
+
+    let on_dismiss_request = props.on_dismiss_request;
+    let mut request_close = move |reason: DismissReason| {
+        let accepted = on_dismiss_request.is_none_or(|request| request.call(reason));
+        if accepted {
+            disclosure.set(false);
+        }
+    };
+    let close_on_overlay_click = props.close_on_overlay_click;
+
+    let body = rsx! {
+        div {
+            style : "box-sizing: border-box; max-width: min(560px, calc(100vw - 32px)); max-height: calc(100vh - 32px); overflow: auto; padding: 24px; border-radius: 12px; background: white; box-shadow: 0 24px 48px rgba(0,0,0,.2); outline: none; {props.style}",
+            if let Some(title) = &props.title {
+                h2 {
+                    id : title_id.clone(),
+                    style : "margin: 0 0 12px; font-size: 1.25em;",
+                    {title.clone()}
+                }
+            }
+            {props.children}
+        }
+    };
+
+    rsx! {
+        Portal {
+            open : open,
+            layer : props.layer,
+            config : DIALOG_PLACEMENT,
+            on_dismiss : request_close,
+            dismiss_on_escape : props.close_on_escape,
+            dismiss_on_outside_click : false,
+
+            if let Some(trigger) = props.trigger {
+                span {
+                    style : "display: contents;",
+                    onpointerdown : move |_| disclosure.press(),
+                    onclick : move |_| disclosure.click(),
+                    {trigger}
+                }
+            }
+
+            // Blocks events so the dismissal stack does not see scrim clicks, which are handled here instead
+            PortalOverlay {
+                overlay_behavior : OverlayClick::Block,
+                div {
+                    style : "position: absolute; inset: 0; background: rgba(0,0,0,.5); {props.overlay_style}",
+                    onclick : move |_| {
+                        if close_on_overlay_click {
+                            request_close(DismissReason::OutsideClick);
+                        }
+                    },
+                }
+            }
+
+            PortalContent {
+                id : dialog_id.clone(),
+                role : ContentRole::Dialog,
+                aria_labelledby : props.title.is_some().then_some(title_id.clone()),
+                div {
+                    style : "display: contents;",
+                    onmounted : onmounted,
+                    if props.trap_focus {
+                        FocusTrap { container_id : dialog_id.clone(), {body} }
+                    } else {
+                        {body}
+                    }
+                }
+            }
+        }
+    }
+}
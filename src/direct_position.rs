@@ -0,0 +1,136 @@
+//! Fast path that moves placed content without re-rendering (`PortalProvider::direct_positioning`).
+//!
+//! - Every placed `PortalContentEntry` registers a writer that holds what its last render used to place the
+//!   content (placement parameters, size, bounds, outlet origin), everything but the anchor rectangle.
+//! - When only the anchor moved, `PortalAnchor` hands the new rectangle to the writers of its portal. They compute
+//!   the new position and write `top`/`left`, and the style variables that follow the anchor (arrow offset and
+//!   available room), straight to the wrapper elements, in one eval (or through `web-sys`).
+//! - A writer whose content would end up on another side or alignment (a flip, or a flip resolving back) declines,
+//!   and the move goes through a render, which also updates `data-side`.
+//! - The rectangle is kept here, so the next render (after a structural change) places content at the same spot.
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use dioxus_lib::document;
+
+use crate::rect_observer::Rect;
+use crate::PortalId;
+
+/// Attribute identifying the positioned wrapper of a content (`"{portal id}-{content id}"`).
+pub(crate) const POSITION_ATTRIBUTE: &str = "data-dioxus-portal-position";
+
+/// Position of a content's wrapper and the style variables that follow the anchor.
+pub(crate) struct Placed {
+    pub top: f64,
+    pub left: f64,
+    /// Custom properties in pixels, such as `--dioxus-portal-arrow-x`.
+    pub properties: Vec<(&'static str, f64)>,
+}
+
+/// Computes the `Placed` of a content for an anchor rectangle, or `None` when the content would change sides.
+type Place = Rc<dyn Fn(Rect) -> Option<Placed>>;
+
+/// Registry of the position writers of a provider.
+#[derive(Clone, Default)]
+pub(crate) struct DirectPositions(Rc<RefCell<Registry>>);
+
+#[derive(Default)]
+struct Registry {
+    writers: HashMap<(PortalId, u64), (String, Place)>,
+    anchors: HashMap<PortalId, Rect>, // Anchor rectangles applied without a render
+}
+
+impl DirectPositions {
+    /// Registers (or replaces) the writer of a placed content. `key` is its `POSITION_ATTRIBUTE` value.
+    pub fn register(
+        &self,
+        id: PortalId,
+        content_id: u64,
+        key: String,
+        place: impl Fn(Rect) -> Option<Placed> + 'static,
+    ) {
+        let place: Place = Rc::new(place);
+        self.0
+            .borrow_mut()
+            .writers
+            .insert((id, content_id), (key, place));
+    }
+
+    /// Removes the writer of a content that is no longer placed.
+    pub fn unregister(&self, id: PortalId, content_id: u64) {
+        self.0.borrow_mut().writers.remove(&(id, content_id));
+    }
+
+    /// Moves the placed contents of portal `id` to follow `anchor`. Returns `false`, leaving everything to the
+    /// next render, when the portal has no placed content or one of them would change sides.
+    pub fn move_anchor(&self, id: PortalId, anchor: Rect) -> bool {
+        let positions = {
+            let mut registry = self.0.borrow_mut();
+            let positions = registry
+                .writers
+                .iter()
+                .filter(|((portal, _), _)| *portal == id)
+                .map(|(_, (key, place))| place(anchor).map(|placed| (key.clone(), placed)))
+                .collect::<Option<Vec<_>>>();
+            let Some(positions) = positions.filter(|positions| !positions.is_empty()) else {
+                return false;
+            };
+            registry.anchors.insert(id, anchor);
+            positions
+        };
+        write_positions(&positions);
+        true
+    }
+
+    /// Anchor rectangle last applied by `move_anchor`, newer than the one in the portal's entry.
+    pub fn anchor(&self, id: PortalId) -> Option<Rect> {
+        self.0.borrow().anchors.get(&id).copied()
+    }
+
+    /// Forgets the anchor rectangle of portal `id`, once its entry holds the latest one again.
+    pub fn clear_anchor(&self, id: PortalId) {
+        self.0.borrow_mut().anchors.remove(&id);
+    }
+}
+
+/// Writes the positions and properties of the wrappers identified by their keys.
+fn write_positions(positions: &[(String, Placed)]) {
+    #[cfg(all(feature = "web", target_arch = "wasm32"))]
+    if crate::web_observer::set_positions(POSITION_ATTRIBUTE, positions) {
+        return;
+    }
+
+    let positions = positions
+        .iter()
+        .map(|(key, placed)| {
+            let properties = placed
+                .properties
+                .iter()
+                .map(|(name, value)| format!(r#"["{name}", {value}]"#))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                r#"["{key}", {}, {}, [{properties}]]"#,
+                placed.top, placed.left
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    document::eval(&format!(
+        r#"
+    try {{
+      for (const [key, top, left, properties] of [{positions}]) {{
+        const el = document.querySelector(`[{POSITION_ATTRIBUTE}="${{key}}"]`);
+        if (el) {{
+          el.style.top = `${{top}}px`;
+          el.style.left = `${{left}}px`;
+          for (const [name, value] of properties) {{
+            el.style.setProperty(name, `${{value}}px`);
+          }}
+        }}
+      }}
+    }} catch (e) {{
+      console.error(`direct position error: ${{e}}`);
+    }}
+"#
+    ));
+}
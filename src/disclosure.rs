@@ -0,0 +1,59 @@
+//! Open state of the ready-made components toggled by a trigger.
+//!
+//! - The state is internal unless the caller passes `open`; every requested change goes to `on_open_change`
+//!   either way, so a component can be controlled or not with the same code.
+//! - A press on the trigger of an open component first dismisses it as an outside click (the provider sees the
+//!   `pointerdown` after the trigger). `press` records the state before that, so the following `click` closes
+//!   instead of reopening. Clicks without a press (Enter/Space on a button) toggle the current state.
+use dioxus_lib::prelude::*;
+
+/// Open state of a component, obtained with `use_disclosure`.
+#[derive(Clone, Copy)]
+pub(crate) struct Disclosure {
+    pub open: bool,
+    internal: Signal<bool>,
+    controlled: bool,
+    on_open_change: Callback<bool>,
+    open_at_press: Signal<Option<bool>>, // State when the current press started
+}
+
+impl Disclosure {
+    /// Requests `open`.
+    pub fn set(&mut self, open: bool) {
+        if !self.controlled {
+            self.internal.set(open);
+        }
+        self.on_open_change.call(open);
+    }
+
+    /// Records a `pointerdown` on the trigger.
+    pub fn press(&mut self) {
+        self.open_at_press.set(Some(self.open));
+    }
+
+    /// Toggles on a `click` of the trigger.
+    pub fn click(&mut self) {
+        match self.open_at_press.take() {
+            // Already closed by the press
+            Some(true) if !self.open => {}
+            Some(was_open) => self.set(!was_open),
+            None => self.set(!self.open),
+        }
+    }
+}
+
+/// Open state following `open` when set, else kept by the component.
+pub(crate) fn use_disclosure(open: Option<bool>, on_open_change: Callback<bool>) -> Disclosure {
+    let internal = use_signal(|| false);
+    let open_at_press = use_signal(|| None);
+    Disclosure {
+        open: match open {
+            Some(open) => open,
+            None => internal(),
+        },
+        internal,
+        controlled: open.is_some(),
+        on_open_change,
+        open_at_press,
+    }
+}
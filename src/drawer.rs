@@ -0,0 +1,353 @@
+//! Ready-made drawer (sheet) sliding in from an edge of the outlet, built on the portal primitives.
+//!
+//! - `Drawer` places its panel flush against `side`, spanning that edge, and slides it in and out. The slide is
+//!   keyed off the `data-state` of the positioned wrapper, and the portal stays mounted for `duration_ms` after
+//!   closing (through the overlay's `transition_ms`) so the slide-out can play.
+//! - A modal drawer (the default) behaves like `Dialog`: scrim, focus trap, scroll lock, focus restoration, and
+//!   Escape/scrim dismissal through `on_dismiss_request`. A non-modal one leaves the page interactive and only
+//!   closes on Escape.
+//! - Under touch, dragging the panel towards its edge follows the finger, and releasing past a quarter of the
+//!   panel closes it (`DismissReason::Swipe`); otherwise it springs back. The panel keeps native scrolling along the
+//!   other axis.
+use std::fmt;
+
+use dioxus_lib::html::geometry::ClientPoint;
+use dioxus_lib::html::GlobalAttributesExtension;
+use dioxus_lib::prelude::*;
+
+use crate::disclosure::use_disclosure;
+use crate::focus::{focus_first, remember_focus, return_focus, FocusTrap};
+use crate::scroll_lock::use_scroll_lock;
+use crate::{
+    alloc_component_id, Alignment, ContentRole, DismissReason, Layer, OverlayClick,
+    PlacementConfig, Portal, PortalContent, PortalOverlay, Spread,
+};
+
+/// Edge of the outlet a `Drawer` slides in from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Edge {
+    #[default]
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl Edge {
+    /// Placement flush against this edge, starting at the top-left corner.
+    pub const fn placement(self) -> PlacementConfig {
+        let mut config = PlacementConfig::bottom().align_start();
+        config.vertical.spread = Spread::Inside;
+        config.vertical.alignment = Alignment::Start;
+        match self {
+            Edge::Left | Edge::Top => {}
+            Edge::Right => config.horizontal.alignment = Alignment::End,
+            Edge::Bottom => config.vertical.alignment = Alignment::End,
+        }
+        config
+    }
+
+    fn is_horizontal(self) -> bool {
+        matches!(self, Edge::Left | Edge::Right)
+    }
+
+    /// Distance of `delta` (from the drag start to the pointer) towards this edge.
+    fn towards(self, delta_x: f64, delta_y: f64) -> f64 {
+        match self {
+            Edge::Left => -delta_x,
+            Edge::Right => delta_x,
+            Edge::Top => -delta_y,
+            Edge::Bottom => delta_y,
+        }
+    }
+}
+
+impl fmt::Display for Edge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Edge::Left => "left",
+            Edge::Right => "right",
+            Edge::Top => "top",
+            Edge::Bottom => "bottom",
+        })
+    }
+}
+
+/// Properties for `Drawer`.
+#[derive(Props, Clone, PartialEq)]
+pub struct DrawerProps {
+    /// Edge the panel slides in from.
+    #[props(default)]
+    pub side: Edge,
+
+    /// Width (for `Left`/`Right`) or height (for `Top`/`Bottom`) of the panel, as a CSS length.
+    #[props(default = "320px".to_string(), into)]
+    pub size: String,
+
+    /// Upper bound of `size`, as a CSS length.
+    #[props(default = "100%".to_string(), into)]
+    pub max_size: String,
+
+    /// Element opening the drawer, typically a button. Without it, open the drawer with `open`.
+    #[props(optional)]
+    pub trigger: Option<Element>,
+
+    /// Controlled open state. When unset, the drawer keeps its own.
+    #[props(optional)]
+    pub open: Option<bool>,
+
+    /// Called with the requested open state on trigger clicks and accepted dismissals.
+    #[props(default)]
+    pub on_open_change: Callback<bool>,
+
+    /// Decides whether an Escape press, scrim click, or swipe closes the drawer. Every request is accepted when
+    /// unset.
+    #[props(optional)]
+    pub on_dismiss_request: Option<Callback<DismissReason, bool>>,
+
+    /// Show a scrim, trap focus, and lock the page scroll while open.
+    #[props(default = true)]
+    pub modal: bool,
+
+    /// Close when the panel is swiped towards its edge under touch.
+    #[props(default = true)]
+    pub swipe_to_close: bool,
+
+    /// Close on Escape.
+    #[props(default = true)]
+    pub close_on_escape: bool,
+
+    /// Close on clicks on the scrim (modal only).
+    #[props(default = true)]
+    pub close_on_overlay_click: bool,
+
+    /// Duration of the slide in and out.
+    #[props(default = 250)]
+    pub duration_ms: u64,
+
+    /// Stacking layer.
+    #[props(default = Layer::Modal.base(), into)]
+    pub layer: i32,
+
+    /// Declarations appended to the default style of the panel.
+    #[props(default)]
+    pub style: String,
+
+    /// Declarations appended to the default style of the scrim.
+    #[props(default)]
+    pub overlay_style: String,
+
+    /// Body of the drawer.
+    children: Element,
+}
+
+/// Panel sliding in from an edge.
+#[component]
+pub fn Drawer(props: DrawerProps) -> Element {
+    let drawer_id = use_hook(|| alloc_component_id("dioxus-portal-drawer-"));
+    let mut disclosure = use_disclosure(props.open, props.on_open_change);
+    let open = disclosure.open;
+    let modal = props.modal;
+    let side = props.side;
+    use_scroll_lock(open && modal);
+
+    let mut was_open = use_signal(|| false);
+    {
+        let drawer_id = drawer_id.clone();
+        use_effect(use_reactive!(|open| {
+            if *was_open.peek() && !open && modal {
+                return_focus(&drawer_id, &drawer_id);
+            }
+            was_open.set(open);
+        }));
+    }
+    let onmounted = {
+        let drawer_id = drawer_id.clone();
+        move |_| {
+            if modal {
+                remember_focus(&drawer_id);
+                focus_first(&drawer_id);
+            }
+        }
+    };
+
+    let on_dismiss_request = props.on_dismiss_request;
+    let mut request_close = move |reason: DismissReason| {
+        let accepted = on_dismiss_request.is_none_or(|request| request.call(reason));
+        if accepted {
+            disclosure.set(false);
+        }
+    };
+    let close_on_overlay_click = props.close_on_overlay_click;
+
+    // Touch drag towards the edge: where it started, and how far the panel follows it
+    let mut swipe = use_signal(|| None::<Swipe>);
+    let mut panel_size = use_signal(|| 0.0_f64);
+    let swipe_to_close = props.swipe_to_close;
+    let mut end_swipe = move |release: bool| {
+        let Some(ended) = swipe.take() else { return };
+        if release && ended.distance > (*panel_size.peek() / 4.0).max(SWIPE_MIN_DISTANCE) {
+            request_close(DismissReason::Swipe);
+        }
+    };
+    let drag = swipe().map_or(0.0, |swipe| swipe.distance);
+
+    let (extent, touch_action) = if side.is_horizontal() {
+        (
+            format!(
+                "width: {}; max-width: {}; height: 100dvh;",
+                props.size, props.max_size
+            ),
+            "pan-y",
+        )
+    } else {
+        (
+            format!(
+                "width: 100vw; height: {}; max-height: {};",
+                props.size, props.max_size
+            ),
+            "pan-x",
+        )
+    };
+    let drag_style = if drag > 0.0 {
+        let (x, y) = match side {
+            Edge::Left => (-drag, 0.0),
+            Edge::Right => (drag, 0.0),
+            Edge::Top => (0.0, -drag),
+            Edge::Bottom => (0.0, drag),
+        };
+        format!(" transform: translate({x}px, {y}px); transition: none;")
+    } else {
+        String::new()
+    };
+    let panel_style = format!(
+        "--dioxus-portal-drawer-ms: {}ms; box-sizing: border-box; {extent} overflow: auto; background: white; box-shadow: 0 0 32px rgba(0,0,0,.2); outline: none; touch-action: {touch_action}; {}{drag_style}",
+        props.duration_ms, props.style
+    );
+
+    let panel = rsx! {
+        div {
+            "data-dioxus-portal-drawer" : side.to_string(),
+            style : panel_style,
+            onresize : move |evt: ResizeEvent| {
+                if let Ok(size) = evt.get_border_box_size() {
+                    panel_size.set(if side.is_horizontal() { size.width } else { size.height });
+                }
+            },
+            onpointerdown : move |evt: PointerEvent| {
+                if swipe_to_close && evt.pointer_type() == "touch" {
+                    swipe.set(Some(Swipe::start(&evt)));
+                }
+            },
+            onpointermove : move |evt: PointerEvent| {
+                let moved = swipe
+                    .peek()
+                    .filter(|swipe| swipe.pointer_id == evt.pointer_id())
+                    .map(|swipe| swipe.moved(side, &evt));
+                if let Some(moved) = moved {
+                    swipe.set(Some(moved));
+                }
+            },
+            onpointerup : move |_| end_swipe(true),
+            onpointercancel : move |_| end_swipe(false),
+            {props.children}
+        }
+    };
+
+    rsx! {
+        Portal {
+            open : open,
+            layer : props.layer,
+            config : side.placement(),
+            on_dismiss : request_close,
+            dismiss_on_escape : props.close_on_escape,
+            dismiss_on_outside_click : false,
+
+            if let Some(trigger) = props.trigger {
+                span {
+                    style : "display: contents;",
+                    onpointerdown : move |_| disclosure.press(),
+                    onclick : move |_| disclosure.click(),
+                    {trigger}
+                }
+            }
+
+            // Carries the slide duration for both modes; only the modal one is visible and takes clicks
+            if modal {
+                PortalOverlay {
+                    overlay_behavior : OverlayClick::Block,
+                    transition_ms : props.duration_ms,
+                    div {
+                        style : "position: absolute; inset: 0; background: rgba(0,0,0,.5); {props.overlay_style}",
+                        onclick : move |_| {
+                            if close_on_overlay_click {
+                                request_close(DismissReason::OutsideClick);
+                            }
+                        },
+                    }
+                }
+            } else {
+                PortalOverlay {
+                    overlay_behavior : OverlayClick::PassThrough,
+                    transition_ms : props.duration_ms,
+                }
+            }
+
+            PortalContent {
+                id : drawer_id.clone(),
+                role : modal.then_some(ContentRole::Dialog),
+                div {
+                    style : "display: contents;",
+                    onmounted : onmounted,
+                    style { {DRAWER_STYLE} }
+                    if modal {
+                        FocusTrap { container_id : drawer_id.clone(), {panel} }
+                    } else {
+                        {panel}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Touch drag on the panel.
+#[derive(Clone, Copy, PartialEq)]
+struct Swipe {
+    pointer_id: i32,
+    start: ClientPoint,
+    distance: f64, // Towards the edge, never negative
+}
+
+/// Distance a swipe must cover to close the drawer, however small the panel.
+const SWIPE_MIN_DISTANCE: f64 = 40.0;
+
+impl Swipe {
+    fn start(evt: &PointerEvent) -> Self {
+        Swipe {
+            pointer_id: evt.pointer_id(),
+            start: evt.client_coordinates(),
+            distance: 0.0,
+        }
+    }
+
+    fn moved(self, side: Edge, evt: &PointerEvent) -> Self {
+        let delta = evt.client_coordinates() - self.start;
+        Swipe {
+            distance: side.towards(delta.x, delta.y).max(0.0),
+            ..self
+        }
+    }
+}
+
+/// Slide in on mount, and out while the wrapper is `data-state="closed"`.
+const DRAWER_STYLE: &str = r#"
+@keyframes dioxus-portal-drawer-in { from { transform: var(--dioxus-portal-drawer-hidden); } }
+[data-dioxus-portal-drawer] { animation: dioxus-portal-drawer-in var(--dioxus-portal-drawer-ms) ease-out; transition: transform var(--dioxus-portal-drawer-ms) ease-in; }
+[data-dioxus-portal-drawer="left"] { --dioxus-portal-drawer-hidden: translateX(-100%); }
+[data-dioxus-portal-drawer="right"] { --dioxus-portal-drawer-hidden: translateX(100%); }
+[data-dioxus-portal-drawer="top"] { --dioxus-portal-drawer-hidden: translateY(-100%); }
+[data-dioxus-portal-drawer="bottom"] { --dioxus-portal-drawer-hidden: translateY(100%); }
+[data-state="closed"] [data-dioxus-portal-drawer] { transform: var(--dioxus-portal-drawer-hidden); }
+@media (prefers-reduced-motion: reduce) { [data-dioxus-portal-drawer] { animation: none; transition: none; } }
+"#;
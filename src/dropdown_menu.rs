@@ -0,0 +1,364 @@
+//! Ready-made dropdown menu built on `Portal` and `PortalMenuContent`.
+//!
+//! - `DropdownMenu` toggles a `PortalMenuContent` anchored to its `trigger` (click, Enter/Space, or ArrowDown to
+//!   open), so items get arrow-key navigation, Home/End, typeahead, and Escape from it. Outside clicks close it
+//!   through the dismissal stack, and closing returns focus to the trigger.
+//! - Items find their menu through `MenuContext`, provided by a component rendered inside the content: the
+//!   content is mounted under the provider's outlet, where the `DropdownMenu` scope is not an ancestor.
+//! - `MenuItem` and `MenuCheckboxItem` are focusable on hover and activated by click, Enter, or Space. Disabled
+//!   items (`aria-disabled`) are skipped by the keyboard and ignore clicks. Activation closes the menu unless
+//!   `close_on_select` is off (the default for checkbox items).
+//! - `MenuContext` also tracks the `Submenu` open in each menu and whether the pointer is over it, and its `close`
+//!   closes the whole chain from the root menu, so an item selected in a submenu closes every level.
+use dioxus_lib::core::use_drop;
+use dioxus_lib::html::{GlobalAttributesExtension, Key};
+use dioxus_lib::prelude::*;
+
+use crate::disclosure::use_disclosure;
+use crate::focus::use_trigger_state;
+use crate::{alloc_component_id, Layer, PlacementConfig, Portal, PortalAnchor, PortalMenuContent};
+
+/// Properties for `DropdownMenu`.
+#[derive(Props, Clone, PartialEq)]
+pub struct DropdownMenuProps {
+    /// Element opening the menu, typically a button.
+    pub trigger: Element,
+
+    /// Controlled open state. When unset, the menu keeps its own.
+    #[props(optional)]
+    pub open: Option<bool>,
+
+    /// Called with the requested open state on trigger clicks, selections, and dismissals.
+    #[props(default)]
+    pub on_open_change: Callback<bool>,
+
+    /// Placement against the trigger.
+    #[props(default = PlacementConfig::bottom().align_start().offset(4.0).flip())]
+    pub placement: PlacementConfig,
+
+    /// Stacking layer.
+    #[props(default = Layer::Dropdown.base(), into)]
+    pub layer: i32,
+
+    /// Wrap around when moving past the first/last item.
+    #[props(default = true)]
+    pub loop_focus: bool,
+
+    /// Declarations appended to the default style of the menu panel.
+    #[props(default)]
+    pub style: String,
+
+    /// `MenuItem`s, `MenuCheckboxItem`s, and `MenuSeparator`s.
+    children: Element,
+}
+
+/// Trigger opening a menu of items.
+#[component]
+pub fn DropdownMenu(props: DropdownMenuProps) -> Element {
+    let menu_id = use_hook(|| alloc_component_id("dioxus-portal-dropdown-"));
+    let trigger_id = format!("{menu_id}-trigger");
+    let mut disclosure = use_disclosure(props.open, props.on_open_change);
+    let open = disclosure.open;
+    use_trigger_state(&trigger_id, &menu_id, "menu", open, true);
+
+    rsx! {
+        Portal {
+            open : open,
+            layer : props.layer,
+            config : props.placement,
+            on_dismiss : move |_| disclosure.set(false),
+
+            PortalAnchor {
+                style : "display: inline-block;",
+                span {
+                    id : trigger_id,
+                    style : "display: contents;",
+                    onpointerdown : move |_| disclosure.press(),
+                    onclick : move |_| disclosure.click(),
+                    onkeydown : move |evt: KeyboardEvent| {
+                        if evt.key() == Key::ArrowDown && !open {
+                            evt.prevent_default();
+                            disclosure.set(true);
+                        }
+                    },
+                    {props.trigger}
+                }
+            }
+
+            MenuPanel {
+                id : menu_id,
+                layer : props.layer,
+                loop_focus : props.loop_focus,
+                style : props.style,
+                on_close : move |_| disclosure.set(false),
+                {props.children}
+            }
+        }
+    }
+}
+
+#[derive(Props, Clone, PartialEq)]
+pub(crate) struct MenuPanelProps {
+    id: String,
+    layer: i32,
+    loop_focus: bool,
+    #[props(default = true)]
+    auto_focus: bool,
+    style: String,
+    /// Closes this menu (Escape).
+    on_close: Callback<()>,
+    /// Closes the whole chain this menu belongs to, `on_close` for a root menu.
+    #[props(optional)]
+    close_all: Option<Callback<()>>,
+    children: Element,
+}
+
+/// `PortalMenuContent` styled as a menu panel, providing `MenuContext` to its items.
+#[component]
+pub(crate) fn MenuPanel(props: MenuPanelProps) -> Element {
+    let open_sub = use_signal(|| None);
+    let mut pointer_inside = use_signal(|| false);
+    let mut layer = use_signal(|| props.layer);
+    if *layer.peek() != props.layer {
+        layer.set(props.layer);
+    }
+    let context = MenuContext {
+        close: props.close_all.unwrap_or(props.on_close),
+        open_sub,
+        pointer_inside,
+        layer,
+    };
+
+    rsx! {
+        PortalMenuContent {
+            id : props.id,
+            item_selector : ITEM_SELECTOR,
+            loop_focus : props.loop_focus,
+            auto_focus : props.auto_focus,
+            on_close : props.on_close,
+            div {
+                "data-dioxus-portal-menu" : "",
+                style : "min-width: 160px; padding: 4px; border: 1px solid #e5e7eb; border-radius: 8px; background: white; box-shadow: 0 8px 24px rgba(0,0,0,.12); {props.style}",
+                onpointerenter : move |_| pointer_inside.set(true),
+                onpointerleave : move |_| pointer_inside.set(false),
+                style { {MENU_STYLE} }
+                MenuScope {
+                    context : context,
+                    {props.children}
+                }
+            }
+        }
+    }
+}
+
+/// Menu the items below belong to.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) struct MenuContext {
+    pub close: Callback<()>,           // Closes the whole menu chain
+    pub open_sub: Signal<Option<u64>>, // `Submenu` open in this menu
+    pub pointer_inside: Signal<bool>,  // Whether the pointer is over this menu's panel
+    pub layer: Signal<i32>,
+}
+
+#[derive(Props, Clone, PartialEq)]
+struct MenuScopeProps {
+    context: MenuContext,
+    children: Element,
+}
+
+/// Provides `MenuContext` from inside the content, where the items are mounted.
+#[component]
+fn MenuScope(props: MenuScopeProps) -> Element {
+    // Callback props are updated in place, so the copy provided here stays current
+    let mut context = use_context_provider(|| props.context);
+    // The panel's state outlives the content, so reopening starts without a submenu
+    use_drop(move || {
+        context.open_sub.set(None);
+        context.pointer_inside.set(false);
+    });
+    rsx! {
+        {props.children}
+    }
+}
+
+/// Properties for `MenuItem`.
+#[derive(Props, Clone, PartialEq)]
+pub struct MenuItemProps {
+    /// Called when the item is activated.
+    #[props(default)]
+    pub on_select: Callback<()>,
+
+    /// Skipped by the keyboard and inert to clicks.
+    #[props(default = false)]
+    pub disabled: bool,
+
+    /// Close the menu once the item is activated.
+    #[props(default = true)]
+    pub close_on_select: bool,
+
+    /// Text matched by typeahead, when the item's text content does not fit (e.g. it has an icon or shortcut).
+    #[props(optional)]
+    pub text_value: Option<String>,
+
+    #[props(default)]
+    pub style: String,
+
+    children: Element,
+}
+
+/// Actionable entry of a menu.
+#[component]
+pub fn MenuItem(props: MenuItemProps) -> Element {
+    let menu = try_use_context::<MenuContext>();
+    let disabled = props.disabled;
+    let close_on_select = props.close_on_select;
+    let on_select = props.on_select;
+    let activate = move || {
+        if disabled {
+            return;
+        }
+        on_select.call(());
+        if let Some(menu) = menu.filter(|_| close_on_select) {
+            menu.close.call(());
+        }
+    };
+    item(
+        "menuitem",
+        None,
+        disabled,
+        props.text_value,
+        props.style,
+        activate,
+        props.children,
+    )
+}
+
+/// Properties for `MenuCheckboxItem`.
+#[derive(Props, Clone, PartialEq)]
+pub struct MenuCheckboxItemProps {
+    pub checked: bool,
+
+    /// Called with the toggled state when the item is activated.
+    #[props(default)]
+    pub on_checked_change: Callback<bool>,
+
+    /// Skipped by the keyboard and inert to clicks.
+    #[props(default = false)]
+    pub disabled: bool,
+
+    /// Close the menu once the item is activated.
+    #[props(default = false)]
+    pub close_on_select: bool,
+
+    /// Text matched by typeahead, when the item's text content does not fit.
+    #[props(optional)]
+    pub text_value: Option<String>,
+
+    #[props(default)]
+    pub style: String,
+
+    children: Element,
+}
+
+/// Menu entry toggling a boolean, shown with a check mark when `checked`.
+#[component]
+pub fn MenuCheckboxItem(props: MenuCheckboxItemProps) -> Element {
+    let menu = try_use_context::<MenuContext>();
+    let disabled = props.disabled;
+    let close_on_select = props.close_on_select;
+    let checked = props.checked;
+    let on_checked_change = props.on_checked_change;
+    let activate = move || {
+        if disabled {
+            return;
+        }
+        on_checked_change.call(!checked);
+        if let Some(menu) = menu.filter(|_| close_on_select) {
+            menu.close.call(());
+        }
+    };
+    item(
+        "menuitemcheckbox",
+        Some(checked),
+        disabled,
+        props.text_value,
+        props.style,
+        activate,
+        props.children,
+    )
+}
+
+/// Properties for `MenuSeparator`.
+#[derive(Props, Clone, PartialEq)]
+pub struct MenuSeparatorProps {
+    #[props(default)]
+    pub style: String,
+}
+
+/// Line between groups of items.
+#[component]
+pub fn MenuSeparator(props: MenuSeparatorProps) -> Element {
+    rsx! {
+        div {
+            role : "separator",
+            style : "height: 1px; margin: 4px 0; background: #e5e7eb; {props.style}",
+        }
+    }
+}
+
+/// Item element with `role`, activated by click, Enter (through `PortalMenuContent`), or Space.
+fn item(
+    role: &'static str,
+    checked: Option<bool>,
+    disabled: bool,
+    text_value: Option<String>,
+    style: String,
+    activate: impl Fn() + Copy + 'static,
+    children: Element,
+) -> Element {
+    let mut mounted = use_signal(|| None::<std::rc::Rc<MountedData>>);
+    rsx! {
+        div {
+            role : role,
+            tabindex : "-1",
+            "aria-checked" : checked.map(|checked| checked.to_string()),
+            "aria-disabled" : disabled.then_some("true"),
+            "data-disabled" : disabled.then_some(""),
+            "data-text-value" : text_value,
+            style : "display: flex; align-items: center; gap: 8px; padding: 6px 8px; border-radius: 4px; cursor: default; user-select: none; outline: none; {style}",
+            onmounted : move |evt: MountedEvent| mounted.set(Some(evt.data())),
+            // Follow the pointer like native menus, so the keyboard continues from the hovered item
+            onpointermove : move |_| {
+                if let (false, Some(mounted)) = (disabled, mounted()) {
+                    spawn(async move {
+                        let _ = mounted.set_focus(true).await;
+                    });
+                }
+            },
+            onclick : move |_| activate(),
+            onkeydown : move |evt: KeyboardEvent| {
+                if evt.key() == Key::Character(" ".to_string()) {
+                    evt.prevent_default();
+                    activate();
+                }
+            },
+            if let Some(checked) = checked {
+                span {
+                    "aria-hidden" : "true",
+                    style : "width: 1em; text-align: center;",
+                    if checked { "✓" }
+                }
+            }
+            {children}
+        }
+    }
+}
+
+/// Items navigated by the keyboard.
+pub(crate) const ITEM_SELECTOR: &str = r#"[role="menuitem"], [role="menuitemcheckbox"]"#;
+
+/// Highlight of the focused item and dimming of disabled ones.
+const MENU_STYLE: &str = r#"
+[data-dioxus-portal-menu] [role^="menuitem"]:focus { background: #f3f4f6; }
+[data-dioxus-portal-menu] [role^="menuitem"][data-disabled] { opacity: 0.5; }
+"#;
@@ -0,0 +1,200 @@
+//! End-to-end positioning harness for headless browsers (`e2e` feature, wasm32 only).
+//!
+//! - Meant for `wasm-bindgen-test` suites run with `wasm-pack test --headless`, in this crate or in component
+//!   libraries built on it: `E2eHarness::mount` launches an app through `dioxus-web` in a fresh container and
+//!   waits until it settled, so assertions read the real DOM.
+//! - Elements are looked up by CSS selector in the whole document, since teleported content leaves the
+//!   container. Give anchors and `PortalContent`s an `id` or a `data-testid` to find them.
+//! - `assert_placed` compares the measured rectangle of a content with the position `calc_content_position`
+//!   gives for the measured anchor, so a test states the placement it expects rather than pixel values.
+//! - Interactions (`click`, `pointer_down`, `key_down`) dispatch bubbling DOM events and wait for the app to
+//!   settle again.
+//! - Dropping the harness removes its container. The app itself keeps running detached, as `dioxus-web` has
+//!   no way to stop it, so every test should mount its own.
+use std::cell::Cell;
+
+use dioxus_lib::prelude::*;
+use euclid::Point2D;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, KeyboardEvent, KeyboardEventInit, PointerEvent, PointerEventInit};
+
+use crate::model::{calc_content_position, AxisParam, Pixels, Rect};
+use crate::geometry::RectExt;
+
+/// Animation frames waited for the app to settle: one to render, one to measure, and one to place.
+const SETTLE_FRAMES: usize = 3;
+
+thread_local! {
+    static NEXT_CONTAINER: Cell<u64> = const { Cell::new(0) };
+}
+
+/// An app mounted in its own container of the test page.
+pub struct E2eHarness {
+    container: HtmlElement,
+}
+
+impl E2eHarness {
+    /// Mounts `app` in a new container filling the viewport, and waits until it settled.
+    pub async fn mount(app: fn() -> Element) -> Self {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let id = NEXT_CONTAINER.with(|next| next.replace(next.get() + 1));
+        let id = format!("dioxus-portal-e2e-{id}");
+        let container = document
+            .create_element("div")
+            .unwrap()
+            .dyn_into::<HtmlElement>()
+            .unwrap();
+        container.set_id(&id);
+        let _ = container.style().set_property("position", "fixed");
+        let _ = container.style().set_property("inset", "0");
+        document.body().unwrap().append_child(&container).unwrap();
+
+        let dom = VirtualDom::new(app);
+        wasm_bindgen_futures::spawn_local(async move {
+            dioxus_web::run(dom, dioxus_web::Config::new().rootname(id)).await;
+        });
+        let harness = E2eHarness { container };
+        harness.settle().await;
+        harness
+    }
+
+    /// Element the app is mounted in.
+    pub fn container(&self) -> &HtmlElement {
+        &self.container
+    }
+
+    /// Waits until renders, measurements, and placements caused by the last change are done.
+    pub async fn settle(&self) {
+        for _ in 0..SETTLE_FRAMES {
+            next_frame().await;
+        }
+    }
+
+    /// First element of the document matching `selector`.
+    pub fn query(&self, selector: &str) -> Option<web_sys::Element> {
+        let document = web_sys::window()?.document()?;
+        document.query_selector(selector).ok().flatten()
+    }
+
+    /// Viewport rectangle of the element matching `selector`.
+    pub fn rect(&self, selector: &str) -> Option<Rect> {
+        Some(Rect::from_dom_rect(
+            &self.query(selector)?.get_bounding_client_rect(),
+        ))
+    }
+
+    /// Clicks the element matching `selector`, then waits until the app settled. Panics when there is none.
+    pub async fn click(&self, selector: &str) {
+        self.html_element(selector).click();
+        self.settle().await;
+    }
+
+    /// Dispatches a mouse `pointerdown` on the element matching `selector` (e.g. to click outside a portal),
+    /// then waits until the app settled. Panics when there is none.
+    pub async fn pointer_down(&self, selector: &str) {
+        let init = PointerEventInit::new();
+        init.set_bubbles(true);
+        init.set_pointer_type("mouse");
+        let event = PointerEvent::new_with_event_init_dict("pointerdown", &init).unwrap();
+        self.html_element(selector).dispatch_event(&event).unwrap();
+        self.settle().await;
+    }
+
+    /// Dispatches a `keydown` of `key` (e.g. `"Escape"`) on the element matching `selector`, then waits until
+    /// the app settled. Panics when there is none.
+    pub async fn key_down(&self, selector: &str, key: &str) {
+        let init = KeyboardEventInit::new();
+        init.set_bubbles(true);
+        init.set_key(key);
+        let event = KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init).unwrap();
+        self.html_element(selector).dispatch_event(&event).unwrap();
+        self.settle().await;
+    }
+
+    /// Position the layout gives the content matching `content` against the anchor matching `anchor`, with
+    /// its measured size and `bounds` (the outlet rectangle, or `viewport()` for a full-page provider).
+    pub fn expected_position(
+        &self,
+        anchor: &str,
+        content: &str,
+        vertical: AxisParam,
+        horizontal: AxisParam,
+        bounds: Rect,
+    ) -> Option<Point2D<f64, Pixels>> {
+        let anchor = self.rect(anchor)?;
+        let content = self.rect(content)?;
+        let (position, _) = calc_content_position(
+            vertical,
+            horizontal,
+            content.size,
+            Some(anchor),
+            bounds,
+            None,
+        );
+        Some(position)
+    }
+
+    /// Asserts that the content matching `content` is placed against the anchor matching `anchor` as
+    /// `vertical` and `horizontal` say, within `tolerance` pixels on each axis.
+    pub fn assert_placed(
+        &self,
+        anchor: &str,
+        content: &str,
+        vertical: AxisParam,
+        horizontal: AxisParam,
+        bounds: Rect,
+        tolerance: f64,
+    ) {
+        let actual = self
+            .rect(content)
+            .unwrap_or_else(|| panic!("no element matches `{content}`"));
+        let expected = self
+            .expected_position(anchor, content, vertical, horizontal, bounds)
+            .unwrap_or_else(|| panic!("no element matches `{anchor}`"));
+        assert!(
+            (actual.origin.x - expected.x).abs() <= tolerance
+                && (actual.origin.y - expected.y).abs() <= tolerance,
+            "`{content}` is at ({}, {}), expected ({}, {}) against `{anchor}`",
+            actual.origin.x,
+            actual.origin.y,
+            expected.x,
+            expected.y,
+        );
+    }
+
+    fn html_element(&self, selector: &str) -> HtmlElement {
+        self.query(selector)
+            .and_then(|el| el.dyn_into::<HtmlElement>().ok())
+            .unwrap_or_else(|| panic!("no element matches `{selector}`"))
+    }
+}
+
+impl Drop for E2eHarness {
+    fn drop(&mut self) {
+        self.container.remove();
+    }
+}
+
+/// Viewport rectangle of the test page.
+pub fn viewport() -> Rect {
+    let window = web_sys::window().unwrap();
+    let width = window
+        .inner_width()
+        .ok()
+        .and_then(|w| w.as_f64())
+        .unwrap_or(0.0);
+    let height = window
+        .inner_height()
+        .ok()
+        .and_then(|h| h.as_f64())
+        .unwrap_or(0.0);
+    Rect::from_xywh(0.0, 0.0, width, height)
+}
+
+/// Completes on the next animation frame.
+pub async fn next_frame() {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let _ = web_sys::window().unwrap().request_animation_frame(&resolve);
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
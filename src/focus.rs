@@ -0,0 +1,264 @@
+//! Focus movement and trigger wiring shared by the ready-made components.
+//!
+//! - Triggers are wrapped in a `display: contents` element with an id, so the components never need a handle on
+//!   the caller's element. ARIA state (`aria-expanded`, `aria-controls`, ...) is mirrored onto the wrapper's
+//!   first element child, which is the element assistive technologies and focus actually see.
+//! - Opening moves focus into the content (its first tabbable element, else the content itself), and closing
+//!   gives it back to the trigger when it was left inside the content or dropped to `<body>`, but not when the
+//!   user moved it elsewhere (e.g. by clicking another control). Components opened without a trigger record the
+//!   focused element instead (`remember_focus`) and give focus back to it the same way.
+//! - `FocusTrap` keeps Tab inside modal content with a focusable guard on each side, which sends focus back to the
+//!   other end when reached.
+//! - Elements are looked up by id in the document and in the outlets' shadow roots (`PortalProvider::shadow_root`),
+//!   and the focused element is followed into open shadow roots, as `document` sees only their hosts.
+//! - Everything goes through eval and is skipped without it (`PortalProvider::eval` off).
+use dioxus_lib::{document, prelude::*};
+
+const FOCUS_REG_KEY: &str = "dioxus-portal-focus-return";
+
+/// Elements reachable with Tab.
+const TABBABLE_SELECTOR: &str = r#"a[href], area[href], button:not([disabled]), input:not([disabled]):not([type="hidden"]), select:not([disabled]), textarea:not([disabled]), iframe, [contenteditable="true"], [tabindex]:not([tabindex="-1"])"#;
+
+/// Attribute of the focus guards of a `FocusTrap`, which are never focus targets themselves.
+const FOCUS_GUARD_ATTRIBUTE: &str = "data-dioxus-portal-focus-guard";
+
+/// Sets (or removes, for `None`) attributes on the first element child of the trigger wrapper `wrapper_id`.
+pub(crate) fn set_trigger_attributes(wrapper_id: &str, attributes: &[(&str, Option<String>)]) {
+    if !crate::has_eval() {
+        return;
+    }
+    let updates = attributes
+        .iter()
+        .map(|(name, value)| match value {
+            Some(value) => format!(r#"trigger.setAttribute("{name}", {});"#, js_string(value)),
+            None => format!(r#"trigger.removeAttribute("{name}");"#),
+        })
+        .collect::<String>();
+    let wrapper = js_element_by_id(wrapper_id);
+    document::eval(&format!(
+        r#"
+    try {{
+      const wrapper = {wrapper};
+      const trigger = wrapper && wrapper.firstElementChild;
+      if (!trigger) return;
+      {updates}
+    }} catch (e) {{
+      console.error(`trigger attributes error: ${{e}}`);
+    }}
+"#
+    ));
+}
+
+/// Mirrors `open` onto the trigger of `wrapper_id` (`aria-haspopup`, `aria-expanded`, `aria-controls` pointing at
+/// `content_id`, and `data-state`), and gives focus back to it on close when `restore` is set.
+pub(crate) fn use_trigger_state(
+    wrapper_id: &str,
+    content_id: &str,
+    haspopup: &'static str,
+    open: bool,
+    restore: bool,
+) {
+    let wrapper_id = wrapper_id.to_string();
+    let content_id = content_id.to_string();
+    let mut was_open = use_signal(|| false);
+    use_effect(use_reactive!(|open, restore| {
+        set_trigger_attributes(
+            &wrapper_id,
+            &[
+                ("aria-haspopup", Some(haspopup.to_string())),
+                ("aria-expanded", Some(open.to_string())),
+                ("aria-controls", open.then(|| content_id.clone())),
+                (
+                    "data-state",
+                    Some(if open { "open" } else { "closed" }.to_string()),
+                ),
+            ],
+        );
+        if *was_open.peek() && !open && restore {
+            restore_focus(&wrapper_id, &content_id);
+        }
+        was_open.set(open);
+    }));
+}
+
+/// Focuses the first tabbable element inside `container_id`, else the container itself.
+pub(crate) fn focus_first(container_id: &str) {
+    if !crate::has_eval() {
+        return;
+    }
+    let container = js_element_by_id(container_id);
+    document::eval(&format!(
+        r#"
+    try {{
+      const container = {container};
+      if (!container || container.contains({JS_ACTIVE_ELEMENT})) return;
+      const first = Array.from(container.querySelectorAll(`{TABBABLE_SELECTOR}`)).find(
+        (el) => !el.hasAttribute("{FOCUS_GUARD_ATTRIBUTE}")
+      );
+      if (first) {{
+        first.focus();
+      }} else {{
+        if (!container.hasAttribute("tabindex")) container.tabIndex = -1;
+        container.focus();
+      }}
+    }} catch (e) {{
+      console.error(`focus error: ${{e}}`);
+    }}
+"#
+    ));
+}
+
+/// Moves focus to the first (or `last`) tabbable element inside `container_id`, else the container itself, wherever
+/// focus currently is.
+fn wrap_focus(container_id: &str, last: bool) {
+    if !crate::has_eval() {
+        return;
+    }
+    let container = js_element_by_id(container_id);
+    document::eval(&format!(
+        r#"
+    try {{
+      const container = {container};
+      if (!container) return;
+      const tabbables = Array.from(container.querySelectorAll(`{TABBABLE_SELECTOR}`)).filter(
+        (el) => !el.hasAttribute("{FOCUS_GUARD_ATTRIBUTE}")
+      );
+      const target = {last} ? tabbables[tabbables.length - 1] : tabbables[0];
+      if (target) {{
+        target.focus();
+      }} else {{
+        if (!container.hasAttribute("tabindex")) container.tabIndex = -1;
+        container.focus();
+      }}
+    }} catch (e) {{
+      console.error(`focus error: ${{e}}`);
+    }}
+"#
+    ));
+}
+
+#[derive(Props, Clone, PartialEq)]
+pub(crate) struct FocusTrapProps {
+    /// Element whose tabbable descendants focus cycles through.
+    container_id: String,
+    children: Element,
+}
+
+/// Keeps Tab and Shift+Tab inside `container_id` by surrounding the children with two focusable guards: reaching
+/// the one after the children moves focus to the first tabbable element, and the one before to the last.
+#[component]
+pub(crate) fn FocusTrap(props: FocusTrapProps) -> Element {
+    let start = props.container_id.clone();
+    let end = props.container_id;
+    rsx! {
+        span {
+            tabindex : "0",
+            "data-dioxus-portal-focus-guard" : "",
+            "aria-hidden" : "true",
+            style : GUARD_STYLE,
+            onfocus : move |_| wrap_focus(&start, true),
+        }
+        {props.children}
+        span {
+            tabindex : "0",
+            "data-dioxus-portal-focus-guard" : "",
+            "aria-hidden" : "true",
+            style : GUARD_STYLE,
+            onfocus : move |_| wrap_focus(&end, false),
+        }
+    }
+}
+
+/// Invisible but focusable.
+const GUARD_STYLE: &str = "position: fixed; width: 1px; height: 1px; padding: 0; margin: -1px; overflow: hidden; clip: rect(0 0 0 0); white-space: nowrap; border: 0; outline: none;";
+
+/// Gives focus back to the trigger of `wrapper_id` if it is inside `content_id` or on `<body>`.
+pub(crate) fn restore_focus(wrapper_id: &str, content_id: &str) {
+    if !crate::has_eval() {
+        return;
+    }
+    let wrapper = js_element_by_id(wrapper_id);
+    let content = js_element_by_id(content_id);
+    document::eval(&format!(
+        r#"
+    try {{
+      const wrapper = {wrapper};
+      const trigger = wrapper && wrapper.firstElementChild;
+      if (!trigger) return;
+      const content = {content};
+      const active = {JS_ACTIVE_ELEMENT};
+      if (!active || active === document.body || (content && content.contains(active))) {{
+        trigger.focus();
+      }}
+    }} catch (e) {{
+      console.error(`focus error: ${{e}}`);
+    }}
+"#
+    ));
+}
+
+/// Records the focused element under `key`, for `return_focus` to give focus back to when a component opened
+/// without a trigger of its own (e.g. a context menu) closes.
+pub(crate) fn remember_focus(key: &str) {
+    if !crate::has_eval() {
+        return;
+    }
+    document::eval(&format!(
+        r#"
+    try {{
+      const REG_KEY = Symbol.for("{FOCUS_REG_KEY}");
+      if (!globalThis[REG_KEY]) {{
+        globalThis[REG_KEY] = new Map();
+      }}
+      globalThis[REG_KEY].set("{key}", {JS_ACTIVE_ELEMENT});
+    }} catch (e) {{
+      console.error(`focus error: ${{e}}`);
+    }}
+"#
+    ));
+}
+
+/// Gives focus back to the element recorded under `key` if focus is inside `content_id` or on `<body>`, and
+/// forgets it.
+pub(crate) fn return_focus(key: &str, content_id: &str) {
+    if !crate::has_eval() {
+        return;
+    }
+    let content = js_element_by_id(content_id);
+    document::eval(&format!(
+        r#"
+    try {{
+      const reg = globalThis[Symbol.for("{FOCUS_REG_KEY}")];
+      const previous = reg && reg.get("{key}");
+      if (reg) reg.delete("{key}");
+      if (!previous || !previous.isConnected) return;
+      const content = {content};
+      const active = {JS_ACTIVE_ELEMENT};
+      if (!active || active === document.body || (content && content.contains(active))) {{
+        previous.focus();
+      }}
+    }} catch (e) {{
+      console.error(`focus error: ${{e}}`);
+    }}
+"#
+    ));
+}
+
+/// JS expression evaluating to the element with id `id`, also inside the outlets' shadow roots.
+fn js_element_by_id(id: &str) -> String {
+    crate::js_deep_query(&format!(r#"[id="{id}"]"#))
+}
+
+/// JS expression evaluating to the focused element, followed into open shadow roots.
+const JS_ACTIVE_ELEMENT: &str = "((el) => { while (el && el.shadowRoot && el.shadowRoot.activeElement) el = el.shadowRoot.activeElement; return el; })(document.activeElement)";
+
+/// `value` as a JS string literal.
+fn js_string(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
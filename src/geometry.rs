@@ -0,0 +1,42 @@
+//! Geometry types used in props, and conversions to them.
+//!
+//! - Every rectangle and point is in CSS pixels (`Pixels`), in viewport coordinates unless stated otherwise, so
+//!   `Rect`, `Point`, and `Size` are the only types callers need; `Point2D`, `Size2D`, and `Pixels` are re-exported
+//!   for spelling the generic forms without depending on `euclid`.
+//! - `Rect` is an alias of `euclid::Rect`, so the constructors live in the `RectExt` extension trait (which is also
+//!   why there is no `From<(f64, f64, f64, f64)>`): bring it in scope to call `Rect::from_xywh` or
+//!   `Rect::from_dom_rect`.
+//! - Provider coordinates are relative to the provider's default outlet, the box portal contents are laid out in.
+//!   `PortalProviderHandle::viewport_to_provider` and `provider_to_viewport` convert between the two.
+pub use euclid::{Point2D, Size2D};
+
+pub use crate::model::{Pixels, Rect};
+
+/// Point in CSS pixels.
+pub type Point = Point2D<f64, Pixels>;
+
+/// Size in CSS pixels.
+pub type Size = Size2D<f64, Pixels>;
+
+/// Constructors of `Rect`.
+pub trait RectExt {
+    /// Rectangle at (`x`, `y`) of `width` by `height`.
+    fn from_xywh(x: f64, y: f64, width: f64, height: f64) -> Rect;
+
+    /// Rectangle of `(x, y, width, height)`.
+    fn from_tuple((x, y, width, height): (f64, f64, f64, f64)) -> Rect {
+        Self::from_xywh(x, y, width, height)
+    }
+
+    /// Rectangle of a `DOMRect`, e.g. from `getBoundingClientRect()` (`web` feature, wasm32 only).
+    #[cfg(all(feature = "web", target_arch = "wasm32"))]
+    fn from_dom_rect(rect: &web_sys::DomRect) -> Rect {
+        Self::from_xywh(rect.x(), rect.y(), rect.width(), rect.height())
+    }
+}
+
+impl RectExt for Rect {
+    fn from_xywh(x: f64, y: f64, width: f64, height: f64) -> Rect {
+        Rect::new(Point2D::new(x, y), Size2D::new(width, height))
+    }
+}
@@ -0,0 +1,123 @@
+//! Coordination of related portals (e.g. the tooltips of a toolbar).
+//!
+//! - `PortalGroup` gives every descendant `Portal` a shared singleton group, so opening one closes the others.
+//! - The first portal opened in a cold group waits for `open_delay_ms`. While a group portal is open, and for
+//!   `skip_delay_ms` after the last one closed, the group is warm and portals open immediately.
+//! - `on_active_change` reports the portal currently shown in the group.
+use dioxus_lib::hooks::use_context_provider;
+use dioxus_lib::prelude::*;
+
+use crate::timer::sleep;
+use crate::{alloc_component_id, PortalId};
+
+/// Properties for `PortalGroup`.
+#[derive(Props, Clone, PartialEq)]
+pub struct PortalGroupProps {
+    /// Delay before the first portal of a cold group is shown.
+    #[props(default = 0)]
+    pub open_delay_ms: u64,
+
+    /// How long the group stays warm after its last portal closed.
+    #[props(default = 300)]
+    pub skip_delay_ms: u64,
+
+    /// Called with the shown portal whenever it changes, or `None` when every portal of the group is closed.
+    #[props(default)]
+    pub on_active_change: Callback<Option<PortalId>>,
+
+    children: Element,
+}
+
+/// Component providing shared open state to descendant `Portal`s.
+#[component]
+pub fn PortalGroup(props: PortalGroupProps) -> Element {
+    let name = use_memo(|| alloc_component_id("dioxus-portal-group-"));
+    let active = use_signal(|| None);
+    let warm = use_signal(|| false);
+    let generation = use_signal(|| 0);
+
+    let settings = GroupSettings {
+        open_delay_ms: props.open_delay_ms,
+        skip_delay_ms: props.skip_delay_ms,
+        on_active_change: props.on_active_change,
+    };
+    let mut settings_signal = use_signal(|| settings.clone());
+    if *settings_signal.peek() != settings {
+        settings_signal.set(settings);
+    }
+
+    use_context_provider(|| PortalGroupContext {
+        name: name(),
+        settings: settings_signal,
+        active,
+        warm,
+        generation,
+    });
+
+    rsx! {
+        {props.children}
+    }
+}
+
+/// Latest `PortalGroup` props shared with the portals.
+#[derive(Clone, PartialEq)]
+pub(crate) struct GroupSettings {
+    pub open_delay_ms: u64,
+    pub skip_delay_ms: u64,
+    pub on_active_change: Callback<Option<PortalId>>,
+}
+
+/// Context shared by the portals of a `PortalGroup`.
+#[derive(Clone)]
+pub(crate) struct PortalGroupContext {
+    pub name: String,
+    pub settings: Signal<GroupSettings>,
+    pub active: Signal<Option<PortalId>>, // Portal currently shown in the group
+    pub warm: Signal<bool>,               // Whether the next portal opens without delay
+    pub generation: Signal<u64>,          // Invalidates pending cool-down timers
+}
+
+impl PortalGroupContext {
+    /// Delay to apply to a portal that has just been opened.
+    pub fn open_delay(&self) -> u64 {
+        if *self.warm.peek() {
+            0
+        } else {
+            self.settings.peek().open_delay_ms
+        }
+    }
+
+    /// Records that `id` became visible.
+    pub fn activate(&mut self, id: PortalId) {
+        *self.generation.write() += 1;
+        self.warm.set(true);
+        if *self.active.peek() != Some(id) {
+            self.active.set(Some(id));
+            let on_active_change = self.settings.peek().on_active_change;
+            on_active_change.call(Some(id));
+        }
+    }
+
+    /// Records that `id` is no longer visible, and cools the group down after `skip_delay_ms`.
+    pub fn deactivate(&mut self, id: PortalId) {
+        if *self.active.peek() != Some(id) {
+            return;
+        }
+        self.active.set(None);
+        let GroupSettings {
+            skip_delay_ms,
+            on_active_change,
+            ..
+        } = self.settings.peek().clone();
+        on_active_change.call(None);
+
+        let generation = *self.generation.peek();
+        let mut ctx = self.clone();
+        spawn(async move {
+            sleep(skip_delay_ms).await;
+            if *ctx.generation.peek() == generation {
+                ctx.warm.set(false);
+            }
+        });
+    }
+}
@@ -0,0 +1,218 @@
+//! Ready-made hover card built on the portal primitives.
+//!
+//! - `HoverCard` wraps its children (the trigger) in a `PortalAnchor` and shows a card after `open_delay_ms` of
+//!   hover or keyboard focus, hiding it `close_delay_ms` after both the trigger and the card are left. Touch
+//!   pointers are ignored, like the hover they lack.
+//! - Leaving the trigger towards the card does not start the close delay: while the pointer stays inside the
+//!   safe polygon (the convex hull of the exit point and the card), it can cross the gap or graze other elements
+//!   on its way. The polygon is tracked by a one-shot `pointermove` listener that reports whether the pointer
+//!   reached the card or left the polygon.
+//! - `content` is a callback called by a component inside the card, so the body is only built while the card is
+//!   shown. Like the tooltip, the card stays out of the dismissal stack and handles Escape itself.
+use dioxus_lib::html::{GlobalAttributesExtension, Key};
+use dioxus_lib::{document, prelude::*};
+
+use crate::timer::use_delayed_toggle;
+use crate::{
+    alloc_component_id, Layer, PlacementConfig, Portal, PortalAnchor, PortalArrow, PortalContent,
+};
+
+/// Properties for `HoverCard`.
+#[derive(Props, Clone, PartialEq)]
+pub struct HoverCardProps {
+    /// Builds the body of the card. Only called while the card is shown.
+    pub content: Callback<(), Element>,
+
+    /// Hover (or focus) time before the card shows.
+    #[props(default = 500)]
+    pub open_delay_ms: u64,
+
+    /// Time the card stays after the pointer (or focus) left both the trigger and the card.
+    #[props(default = 300)]
+    pub close_delay_ms: u64,
+
+    /// Keep the card open while the pointer moves from the trigger towards it.
+    #[props(default = true)]
+    pub safe_polygon: bool,
+
+    /// Called when the card shows or hides.
+    #[props(default)]
+    pub on_open_change: Callback<bool>,
+
+    /// Placement against the trigger.
+    #[props(default = PlacementConfig::bottom().offset(8.0).flip())]
+    pub placement: PlacementConfig,
+
+    /// Stacking layer.
+    #[props(default = Layer::Popover.base(), into)]
+    pub layer: i32,
+
+    /// Render a `PortalArrow` pointing at the trigger.
+    #[props(default = false)]
+    pub arrow: bool,
+
+    /// Never show the card.
+    #[props(default = false)]
+    pub disabled: bool,
+
+    /// Declarations appended to the default style of the card box.
+    #[props(default)]
+    pub style: String,
+
+    children: Element,
+}
+
+/// Trigger with a preview card shown on hover and focus.
+#[component]
+pub fn HoverCard(props: HoverCardProps) -> Element {
+    let card_id = use_hook(|| alloc_component_id("dioxus-portal-hover-card-"));
+    let trigger_id = format!("{card_id}-trigger");
+    let mut open = use_signal(|| false);
+    let on_open_change = props.on_open_change;
+    let mut toggle = use_delayed_toggle(move |value| {
+        if *open.peek() != value {
+            open.set(value);
+            on_open_change.call(value);
+        }
+    });
+    let shown = open() && !props.disabled;
+
+    let open_delay_ms = props.open_delay_ms;
+    let close_delay_ms = props.close_delay_ms;
+    let safe_polygon = props.safe_polygon && crate::has_eval();
+
+    let onpointerleave = {
+        let card_id = card_id.clone();
+        move |evt: PointerEvent| {
+            if evt.pointer_type() == "touch" {
+                return;
+            }
+            if !(shown && safe_polygon) {
+                toggle.schedule(false, close_delay_ms);
+                return;
+            }
+            // Hold the card until the pointer leaves the polygon; reaching the card cancels through its own
+            // `pointerenter`, and re-entering the trigger through the toggle
+            let current = toggle.cancel();
+            let exit = evt.client_coordinates();
+            let mut eval = document::eval(&js_code_of_safe_polygon(&card_id, exit.x, exit.y));
+            spawn(async move {
+                let reached = eval.recv::<bool>().await.unwrap_or(false);
+                if !reached && toggle.is_current(current) {
+                    toggle.schedule(false, close_delay_ms);
+                }
+            });
+        }
+    };
+
+    let close_on_escape = move |evt: KeyboardEvent| {
+        if shown && evt.key() == Key::Escape {
+            evt.stop_propagation();
+            toggle.schedule(false, 0);
+        }
+    };
+
+    rsx! {
+        Portal {
+            open : shown,
+            layer : props.layer,
+            config : props.placement,
+
+            PortalAnchor {
+                style : "display: inline-block;",
+                span {
+                    id : trigger_id,
+                    style : "display: contents;",
+                    onpointerenter : move |evt: PointerEvent| {
+                        if evt.pointer_type() != "touch" {
+                            toggle.schedule(true, if shown { 0 } else { open_delay_ms });
+                        }
+                    },
+                    onpointerleave : onpointerleave,
+                    onfocusin : move |_| toggle.schedule(true, open_delay_ms),
+                    onfocusout : move |_| toggle.schedule(false, close_delay_ms),
+                    onkeydown : close_on_escape,
+                    {props.children}
+                }
+            }
+
+            PortalContent {
+                id : card_id,
+                div {
+                    "data-state" : if shown { "open" } else { "closed" },
+                    style : "position: relative; max-width: 320px; padding: 12px; border: 1px solid #e5e7eb; border-radius: 8px; background: white; box-shadow: 0 8px 24px rgba(0,0,0,.12); {props.style}",
+                    onpointerenter : move |_| toggle.schedule(true, 0),
+                    onpointerleave : move |evt: PointerEvent| {
+                        if evt.pointer_type() != "touch" {
+                            toggle.schedule(false, close_delay_ms);
+                        }
+                    },
+                    onfocusin : move |_| toggle.schedule(true, 0),
+                    onfocusout : move |_| toggle.schedule(false, close_delay_ms),
+                    onkeydown : close_on_escape,
+                    CardBody { content : props.content }
+                    if props.arrow {
+                        PortalArrow {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Props, Clone, PartialEq)]
+struct CardBodyProps {
+    content: Callback<(), Element>,
+}
+
+/// Calls `content` when mounted, i.e. only while the card is shown.
+#[component]
+fn CardBody(props: CardBodyProps) -> Element {
+    props.content.call(())
+}
+
+/// Generates JS code watching the pointer after it left the trigger at (`x`, `y`). Sends `true` once it enters
+/// the card, or `false` once it leaves the convex hull of the exit point and the card (immediately if the card is
+/// not found). Also used by `Submenu`, with the submenu as the card.
+pub(crate) fn js_code_of_safe_polygon(card_id: &str, x: f64, y: f64) -> String {
+    format!(
+        r#"
+    try {{
+      const card = document.getElementById("{card_id}");
+      if (!card) {{
+        dioxus.send(false);
+        return;
+      }}
+      const exit = {{ x: {x}, y: {y} }};
+      const cross = (o, a, b) => (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x);
+      const inTriangle = (p, a, b, c) => {{
+        const d1 = cross(p, a, b), d2 = cross(p, b, c), d3 = cross(p, c, a);
+        return !((d1 < 0 || d2 < 0 || d3 < 0) && (d1 > 0 || d2 > 0 || d3 > 0));
+      }};
+      const onMove = (e) => {{
+        const r = card.getBoundingClientRect();
+        const p = {{ x: e.clientX, y: e.clientY }};
+        if (p.x >= r.left && p.x <= r.right && p.y >= r.top && p.y <= r.bottom) {{
+          document.removeEventListener("pointermove", onMove, true);
+          dioxus.send(true);
+          return;
+        }}
+        // The hull is covered by the triangles fanning from the exit point to each edge of the card
+        const corners = [
+          {{ x: r.left, y: r.top }}, {{ x: r.right, y: r.top }},
+          {{ x: r.right, y: r.bottom }}, {{ x: r.left, y: r.bottom }},
+        ];
+        const inside = corners.some((a, i) => inTriangle(p, exit, a, corners[(i + 1) % 4]));
+        if (!inside) {{
+          document.removeEventListener("pointermove", onMove, true);
+          dioxus.send(false);
+        }}
+      }};
+      document.addEventListener("pointermove", onMove, true);
+    }} catch (e) {{
+      console.error(`hover card safe polygon error: ${{e}}`);
+      dioxus.send(false);
+    }}
+"#
+    )
+}
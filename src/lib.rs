@@ -5,83 +5,541 @@
 //! - `PortalAnchor`: Anchor area used as the reference for alignment. When registered, the rectangle of this component is used as the anchor
 //! - `PortalContent`: Registers the content to display
 //! - `PortalOverlay`: Registers the overlay element
+//! - `PortalMenuContent`: `PortalContent` with keyboard navigation for menu items
+//! - `PortalTarget`: Named render region that portals can be routed into with `target`
+//! - `PortalGroup`: Coordinates descendant portals (mutual exclusivity, shared open delay)
 //!
 //! Placement is controlled by the combination of `Alignment`, `Spread`, and `OverflowPolicy`.
 
+mod arrow;
+#[cfg(feature = "debug")]
+mod debug;
+mod client;
+mod combobox_popup;
+mod context_menu;
+mod css_anchor;
+#[cfg(all(feature = "desktop", not(target_arch = "wasm32")))]
+mod desktop_window;
+mod dialog;
+mod diagnostics;
+pub mod geometry;
+#[cfg(all(feature = "e2e", target_arch = "wasm32"))]
+pub mod e2e;
+mod direct_position;
+mod disclosure;
+mod drawer;
+mod dropdown_menu;
+mod focus;
+mod group;
+mod hover_card;
+mod menu;
+mod menubar;
+pub mod model;
+mod motion;
+#[cfg(all(feature = "native", not(target_arch = "wasm32")))]
+mod native_observer;
+#[cfg(feature = "perf")]
+mod perf;
+mod popconfirm;
+mod popover;
+mod rect_history;
 mod rect_observer;
+mod scroll_lock;
+mod select;
+mod submenu;
+#[cfg(feature = "test-utils")]
+mod test_measure;
+mod timer;
+mod toast;
+mod tooltip;
+mod tour;
+#[cfg(all(feature = "web", target_arch = "wasm32"))]
+mod web_observer;
 
 use dioxus_core::use_drop;
 use dioxus_lib::hooks::use_context_provider;
-use dioxus_lib::{html::geometry::Pixels, prelude::*};
+use dioxus_lib::html::Key;
+use dioxus_lib::{document, html::geometry::ClientPoint, prelude::*};
 use euclid::{Point2D, Size2D};
-use std::{collections::HashMap, fmt::Display, ops::Range};
+use std::{
+    cell::Cell,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    ops::Deref,
+    rc::Rc,
+};
+
+use crate::rect_observer::{
+    use_frame_rect, use_rect_hub, use_registry_teardown, use_root_rect, ObserverNamespace,
+    DEFAULT_MIN_DELTA, DEFAULT_POLL_MS,
+};
+use crate::motion::{use_prefers_reduced_motion, MotionDefaults, Transition};
+use crate::timer::sleep;
+use crate::model::{
+    available_size, calc_content_position, dismissal_target, placement_bounds, stack_order, Dismissal, Pixels, StackPosition,
+};
+
+pub use crate::arrow::{PortalArrow, PortalArrowProps};
+#[cfg(feature = "debug")]
+pub use crate::debug::{PortalDebugLayer, PortalDebugLayerProps};
+pub use crate::geometry::{Point, RectExt, Size};
+pub use crate::model::{
+    Alignment, AxisParam, Layer, OverflowPolicy, PlacementConfig, ResolvedPlacement, Spread, LAYER_RANGE,
+};
+pub use crate::group::{PortalGroup, PortalGroupProps};
+pub use crate::menu::{PortalMenuContent, PortalMenuContentProps};
+pub use crate::motion::ReducedMotion;
+#[cfg(feature = "perf")]
+pub use crate::perf::{use_perf_counters, PerfCounters, PerfSnapshot, PortalCounters};
+pub use crate::rect_history::{RectHistory, RectSample};
+#[cfg(feature = "test-utils")]
+pub use crate::test_measure::TestMeasure;
+pub use crate::combobox_popup::{ComboboxPopup, ComboboxPopupProps};
+pub use crate::context_menu::{ContextMenu, ContextMenuProps, CONTEXT_MENU_PLACEMENT};
+pub use crate::dialog::{Dialog, DialogProps, DIALOG_PLACEMENT};
+pub use crate::drawer::{Drawer, DrawerProps, Edge};
+pub use crate::dropdown_menu::{
+    DropdownMenu, DropdownMenuProps, MenuCheckboxItem, MenuCheckboxItemProps, MenuItem, MenuItemProps,
+    MenuSeparator, MenuSeparatorProps,
+};
+pub use crate::hover_card::{HoverCard, HoverCardProps};
+pub use crate::menubar::{Menubar, MenubarMenu, MenubarMenuProps, MenubarProps};
+pub use crate::popconfirm::{ConfirmFuture, Popconfirm, PopconfirmProps};
+pub use crate::popover::{Popover, PopoverProps};
+pub use crate::select::{Select, SelectOption, SelectProps};
+pub use crate::submenu::{Submenu, SubmenuProps};
+pub use crate::toast::{
+    use_toast, Toast, ToastHandle, ToastId, ToastKind, ToastProvider, ToastProviderProps,
+    ToastRegion,
+};
+pub use crate::tooltip::{Tooltip, TooltipProps};
+pub use crate::tour::{Tour, TourProps, TourStep, TourTarget, TourTargetProps};
+pub use crate::rect_observer::{
+    use_element_rect, MeasureBackend, MeasureFrequency, ObserveTarget, ObservedBox, ObserverError, Rect,
+    RectObserver, RectObserverProps, ReportOptions, SharedMeasureBackend, ThrottleMode,
+};
+
+#[cfg(all(feature = "desktop", not(target_arch = "wasm32")))]
+use crate::desktop_window::DetachedWindow;
+use crate::diagnostics::use_diagnostics;
+
+// Whether the renderer runs the JS of `document::eval`, as set by the enclosing provider's `eval`
+#[derive(Clone, Copy)]
+struct EvalSupport(bool);
+
+// Whether the renderer runs eval, for the calling component. Without it, components get the behavior that needs
+// none: measurement through `MountedData` and native timers (`native` feature), content kept in the outlet, and
+// positions applied by re-rendering. Components outside any provider assume eval
+pub(crate) fn has_eval() -> bool {
+    try_consume_context::<EvalSupport>().is_none_or(|eval| eval.0)
+}
+use crate::direct_position::{DirectPositions, Placed, POSITION_ATTRIBUTE};
+use crate::group::PortalGroupContext;
 
-use crate::rect_observer::{Rect, RectObserver};
+// Existing element used as the positioning root of a `PortalProvider`
+#[derive(Clone)]
+pub enum PortalRoot {
+    Selector(String),          // CSS selector, resolved once when the root is set
+    Mounted(Rc<MountedData>),  // Element obtained from an `onmounted` event
+}
 
-// ------ Types for placement control --------------------------------------------------------------------------------------------------------------
+impl std::fmt::Debug for PortalRoot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PortalRoot::Selector(selector) => f.debug_tuple("Selector").field(selector).finish(),
+            PortalRoot::Mounted(_) => f.debug_tuple("Mounted").finish_non_exhaustive(),
+        }
+    }
+}
 
-#[derive(Clone, Copy, PartialEq)]
-pub enum Alignment {
-    Start,
-    Center,
-    End,
+impl PartialEq for PortalRoot {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (PortalRoot::Selector(a), PortalRoot::Selector(b)) => a == b,
+            (PortalRoot::Mounted(a), PortalRoot::Mounted(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
 }
 
-#[derive(Clone, Copy, PartialEq)]
-pub enum Spread {
-    Inside,
-    Outside,
+// ------ Types for dismissal --------------------------------------------------------------------------------------------------------------------
+
+// What caused a dismissal request passed to `Portal::on_dismiss`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DismissReason {
+    EscapeKey,
+    OutsideClick,
+    Programmatic, // `PortalProviderHandle::close_all` / `close_layer`
+    GroupSwitch,  // Another portal in the same `group` was opened
+    Timeout,      // `auto_close_ms` elapsed
+    LightDismiss, // The browser closed a `light_dismiss` popover (outside click, Escape, or another popover opening)
+    Swipe,        // A touch swipe towards the edge of a `Drawer`
 }
 
-#[derive(Clone, Copy, PartialEq)]
-pub enum OverflowPolicy {
-    Ignore,
-    Shrink,
-    Clamp,
-    Flip,
+impl Display for DismissReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            DismissReason::EscapeKey => "escape key",
+            DismissReason::OutsideClick => "outside click",
+            DismissReason::Programmatic => "programmatic",
+            DismissReason::GroupSwitch => "group switch",
+            DismissReason::Timeout => "timeout",
+            DismissReason::LightDismiss => "light dismiss",
+            DismissReason::Swipe => "swipe",
+        })
+    }
+}
+
+// Where a portal's overlay is stacked in the outlet
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum OverlayStacking {
+    Owner,        // Directly under the portal that owns the overlay
+    TopmostModal, // Directly under the topmost open modal when that is above the owner, dimming the modals below it
+}
+
+// Behavior of clicks on a `PortalOverlay`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum OverlayClick {
+    Dismiss,     // Close the owning portal
+    Block,       // Swallow the events so nothing below (including the dismissal stack) reacts
+    PassThrough, // `pointer-events: none`, keeping the page interactive under a visual scrim
+}
+
+// ------ Types for accessibility ----------------------------------------------------------------------------------------------------------------
+
+// Where the content of a portal is shown
+#[derive(Clone, Copy, Debug, Default)]
+pub enum PortalWindow {
+    #[default]
+    Main, // In the provider's outlet (or the portal's `target`)
+    // This component instead of the `PortalContent`s, in a frameless, always-on-top native window placed against
+    // the anchor on screen. Requires the `desktop` feature; without it the `PortalContent`s stay in the outlet
+    Detached(fn() -> Element),
+}
+
+// Function pointers are compared by address, the only comparison they have
+impl PartialEq for PortalWindow {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (PortalWindow::Main, PortalWindow::Main) => true,
+            (PortalWindow::Detached(a), PortalWindow::Detached(b)) => std::ptr::fn_addr_eq(*a, *b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for PortalWindow {}
+
+// Semantic role applied to the positioned content wrapper
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ContentRole {
+    Dialog,
+    Menu,
+    Tooltip,
+    Listbox,
+}
+
+impl ContentRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentRole::Dialog => "dialog",
+            ContentRole::Menu => "menu",
+            ContentRole::Tooltip => "tooltip",
+            ContentRole::Listbox => "listbox",
+        }
+    }
+
+    // `role` plus the companion attributes expected for that role
+    fn attributes(&self) -> Vec<Attribute> {
+        let mut attrs = vec![Attribute::new("role", self.as_str(), None, false)];
+        if *self == ContentRole::Dialog {
+            attrs.push(Attribute::new("aria-modal", "true", None, false));
+            attrs.push(Attribute::new("tabindex", "-1", None, false));
+        }
+        attrs
+    }
+}
+
+impl Display for ContentRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 // ------ Public Props -------------------------------------------------------------------------------------------------------------------
 
+// Props that choose what the provider sets up when it mounts (contexts, observers, and DOM structure) are read
+// only then: `eval`, `shadow_root`, `shadow_root_style`, `bubble`, `isolated`, `namespace`, `observer_poll_ms`,
+// `observer_min_delta`, `measure_frequency`, `measure_backend`, `js_rect_hook`, `direct_positioning`,
+// `css_anchor_positioning`, and `on_observer_error`. Remount the provider (e.g. under a new `key`) to change
+// them. The other props apply on every render
 #[derive(Props, Clone, PartialEq)]
 pub struct PortalProviderProps {
     #[props(default)]
     pub style: String,
+    // Whether the renderer runs the JS of `document::eval`. Set it to `false` on renderers without a JS runtime
+    // (Blitz / dioxus-native): no eval is issued below the provider, elements are measured through `MountedData`
+    // and timers run natively (both need the `native` feature), content always renders in the outlet, and
+    // `direct_positioning`, `shadow_root`, focus management, and scroll locking are skipped
+    #[props(default = true)]
+    pub eval: bool,
+    // Render an ARIA live region that announces portal open/close messages
+    #[props(default = false)]
+    pub live_region: bool,
+    // Render portal content in the browser top layer so it escapes ancestor `overflow: hidden`, `filter`,
+    // and stacking contexts. Can be overridden per portal with `Portal::teleport`
+    #[props(default = false)]
+    pub teleport: bool,
+    // Render each outlet's portals in an open shadow root attached to that outlet, so the host page's stylesheets
+    // do not apply to them (e.g. a widget embedded in arbitrary pages). Inherited properties such as `font` and
+    // `color` still inherit from the outlet
+    #[props(default = false)]
+    pub shadow_root: bool,
+    // Stylesheet added to each outlet's shadow root with `shadow_root`, for the content's own CSS
+    #[props(optional)]
+    pub shadow_root_style: Option<String>,
+    // When nested in another provider, forward portals to the outermost provider's outlet instead of rendering
+    // them here (where they would be clipped)
+    #[props(default = false)]
+    pub bubble: bool,
+    // Keep portals of this subtree inside this provider: nested `bubble` providers stop here
+    #[props(default = false)]
+    pub isolated: bool,
+    // Do not wrap the children in a layout box: the wrapper uses `display: contents` (so flex/grid parents see
+    // the children directly) and the outlet is fixed to the viewport. `style` has no layout effect in this mode
+    #[props(default = false)]
+    pub fragment: bool,
+    // Existing element whose box is used as the positioning root instead of the injected wrapper. Implies
+    // `fragment`; the outlet is fixed over the element's rectangle, so the element needs no `position` of its own
+    #[props(optional)]
+    pub root: Option<PortalRoot>,
+    // Give the outlet `overflow: hidden` so portal content is clipped to the provider's box. By default content
+    // may extend past the provider (and is only clipped by its ancestors). Teleported content is not affected
+    #[props(default = false)]
+    pub clip_to_provider: bool,
+    // Margin kept between portal content and the provider's edges when placing content (applies to every portal)
+    #[props(default = 0.0)]
+    pub bounds_padding: f64,
+    // Called after a portal of this provider opens or closes (including unmounting while open and closing by
+    // dismissal). Not called on a `bubble` provider, whose portals are reported by the outermost one
+    #[props(default)]
+    pub on_portal_opened: Callback<OpenPortal>,
+    #[props(default)]
+    pub on_portal_closed: Callback<OpenPortal>,
+    // Namespace of this provider's JS observer registry and element ids. Each provider instance gets its own
+    // by default; ids also carry a per-app random part, so this is only needed to pick a recognizable name
+    #[props(optional)]
+    pub namespace: Option<String>,
+    // z-index of the outlet. Portal slots start from this value so stacking fits an existing z-index scheme
+    #[props(default = 1)]
+    pub z_index_base: i64,
+    // z-index distance between consecutive portals (each uses two slots: overlay and content)
+    #[props(default = 2)]
+    pub z_index_stride: i64,
+    // Backdrop rendered for modal portals (`ContentRole::Dialog` content) that have no `PortalOverlay`
+    #[props(optional)]
+    pub default_overlay: Option<Element>,
+    // Render a single scrim for all open modal portals, placed just below the topmost one, instead of one
+    // overlay per modal. The scrim stays mounted while any modal is open, so it does not flash between modals
+    #[props(default = false)]
+    pub shared_overlay: bool,
+    // Where the rendered overlay is stacked
+    #[props(default = OverlayStacking::Owner)]
+    pub overlay_stacking: OverlayStacking,
+    // Placement inherited by portals that do not set the corresponding props
+    #[props(default = AxisParam::VERTICAL)]
+    pub default_vertical: AxisParam,
+    #[props(default = AxisParam::HORIZONTAL)]
+    pub default_horizontal: AxisParam,
+    // Fade in/out durations and easing inherited by every portal. `PortalOverlay::transition_ms` and
+    // `transition_easing` override them per portal
+    #[props(default = 0)]
+    pub enter_ms: u64,
+    #[props(default = 0)]
+    pub exit_ms: u64,
+    #[props(default = "ease".to_string())]
+    pub easing: String,
+    // Whether `prefers-reduced-motion` disables the transitions
+    #[props(default = ReducedMotion::System)]
+    pub reduced_motion: ReducedMotion,
+    // Interval at which element sizes are polled where `ResizeObserver` is unavailable (some embedded webviews
+    // and older engines). Unused otherwise
+    #[props(default = DEFAULT_POLL_MS)]
+    pub observer_poll_ms: u64,
+    // Rectangle changes where every edge moved by less than this many pixels are ignored, so sub-pixel jitter
+    // does not re-layout portals
+    #[props(default = DEFAULT_MIN_DELTA)]
+    pub observer_min_delta: f64,
+    // How often anchors, contents, and outlets are measured. `MeasureFrequency::Low` skips scrolls for renderers
+    // where eval is a network hop (LiveView), and is the default with the `liveview` feature
+    #[props(default)]
+    pub measure_frequency: MeasureFrequency,
+    // Measures the anchors, contents, and outlets below this provider instead of the built-in backends (e.g.
+    // fixed rectangles in tests, or a host without a DOM)
+    #[props(optional)]
+    pub measure_backend: Option<SharedMeasureBackend>,
+    // Source of a JS function `(rect, element) => rect` that adjusts every rectangle measured below this provider
+    // before it is sent, e.g. to undo a host page's zoom layer or canvas transform. `rect` has `x`, `y`, `width`,
+    // and `height` in viewport pixels; returning nothing keeps it. Runs in the eval observer only (so not with
+    // `eval` off)
+    #[props(optional)]
+    pub js_rect_hook: Option<String>,
+    // Write positions that follow a moving anchor straight to the content wrappers' styles instead of
+    // re-rendering the portal entries. Structural changes (open/close, content, size) and moves that change a
+    // content's side still re-render
+    #[props(default = false)]
+    pub direct_positioning: bool,
+    // Place contents with CSS anchor positioning where the browser supports it, so they follow their anchors
+    // without being measured. Portals whose placement CSS cannot express (`anchor_rect`, `PortalFrameAnchor`,
+    // `Clamp` or `Shrink` overflow) keep the measured layout
+    #[props(default = false)]
+    pub css_anchor_positioning: bool,
+    // Receives failures of the rectangle observers below this provider (anchors, contents, outlets), whose
+    // positions stop updating
+    #[props(optional)]
+    pub on_observer_error: Option<Callback<ObserverError>>,
     #[props(extends=GlobalAttributes)]
     pub attribute: Vec<Attribute>,
     children: Element,
 }
 
+// `portal_key`, `throttle`, and `track_continuously` are read when the portal mounts, like the provider's setup
+// props. The other props apply on every render
 #[derive(Props, Clone, PartialEq)]
 pub struct PortalProps {
     #[props(default = false)]
     pub open: bool,
-    #[props(default = 0)]
+    // Applies a change of `open` only once it held for this long, so rapid toggles (e.g. hover jitter) coalesce
+    // instead of mounting and tearing down the content each time. Closing is delayed as well. The initial value
+    // is applied immediately
+    #[props(optional)]
+    pub open_debounce_ms: Option<u64>,
+    // Stacking layer: an integer or a `Layer`
+    #[props(default = 0, into)]
     pub layer: i32,
 
+    // Name of the `PortalTarget` to render into. `None` renders into the provider's default outlet
+    #[props(optional)]
+    pub target: Option<String>,
+
+    // Overrides `teleport` of the `PortalProvider` for this portal
+    #[props(optional)]
+    pub teleport: Option<bool>,
+
+    // Shows the primary content as an `auto` popover in the top layer (implies `teleport`), which the browser
+    // light-dismisses on outside clicks, Escape, and when an unrelated popover opens. The portal is then closed
+    // like by `close_all`, with `DismissReason::LightDismiss`. Placement still comes from this crate's layout
+    #[props(default = false)]
+    pub light_dismiss: bool,
+
+    // Renders `ContentRole::Dialog` contents inside a `<dialog>` opened with `showModal()`, so the platform traps
+    // focus, makes the rest of the page inert, and keeps the content above everything. Escape and clicks outside
+    // the content still go through `on_dismiss`, and placement still comes from this crate's layout. The dialog
+    // has its own outlet, where the portals opened from the content render (unless they set `target`), so they
+    // are not made inert with the page
+    #[props(default = false)]
+    pub native_dialog: bool,
+
+    // Show a component in its own native window instead of the contents in the outlet (`PortalWindow::Detached`,
+    // `desktop` feature). The component runs in the window's own `VirtualDom`, where `use_portal_id` and
+    // `use_portal_provider` work but the app's other contexts are missing, so it reads shared state from global
+    // signals. Portals opened inside it are not supported
+    #[props(default)]
+    pub window: PortalWindow,
+
+    // Append the content to the existing DOM node matching this CSS selector (e.g. a host-page container)
+    // Takes precedence over `teleport`. The renderer delegates bubbling events to its root element, so handlers
+    // inside the content only receive them when the node lies within the Dioxus root
+    #[props(optional)]
+    pub mount_selector: Option<String>,
+
+    // Identity of the portal kept across unmount/remount (named `portal_key` since `key` is reserved by `rsx!`)
+    // Must be unique among mounted portals of the provider
+    #[props(optional)]
+    pub portal_key: Option<String>,
+
     // Use this when specifying the anchor rectangle directly
     // This property takes precedence over the rectangle from `PortalAnchor`
     // Note: The position is relative to the viewport
     #[props(optional)]
     pub anchor_rect : Option<Rect>, 
 
-    #[props(default=Alignment::End)]
-    pub vertical_alignment: Alignment,
-    #[props(default=Spread::Outside)]
-    pub vertical_spread: Spread,
-    #[props(default = 0.0)]
-    pub vertical_offset: f64,
-    #[props(default=OverflowPolicy::Clamp)]
-    pub vertical_overflow_policy: OverflowPolicy,
-
-    #[props(default=Alignment::Center)]
-    pub horizontal_alignment: Alignment,
-    #[props(default=Spread::Inside)]
-    pub horizontal_spread: Spread,
-    #[props(default = 0.0)]
-    pub horizontal_offset: f64,
-    #[props(default=OverflowPolicy::Clamp)]
-    pub horizontal_overflow_policy: OverflowPolicy,
+    // Messages announced through the provider's live region when the portal opens/closes
+    // Ignored unless `live_region` is enabled on `PortalProvider`
+    #[props(optional)]
+    pub open_announcement: Option<String>,
+    #[props(optional)]
+    pub close_announcement: Option<String>,
+
+    // Opening a portal closes the other open portals with the same group name
+    #[props(optional)]
+    pub group: Option<String>,
+
+    // Placement of both axes and an optional `anchor_rect` as one value, e.g.
+    // `PlacementConfig::bottom().align_start().offset(8.0).flip()`. Replaces the `PortalProvider` defaults; the
+    // individual parameters below and `anchor_rect` still take precedence over it
+    #[props(optional)]
+    pub config: Option<PlacementConfig>,
+
+    // Placement parameters. Unset values inherit `config`, else the `PortalProvider` defaults
+    #[props(optional)]
+    pub vertical_alignment: Option<Alignment>,
+    #[props(optional)]
+    pub vertical_spread: Option<Spread>,
+    #[props(optional)]
+    pub vertical_offset: Option<f64>,
+    #[props(optional)]
+    pub vertical_overflow_policy: Option<OverflowPolicy>,
+
+    #[props(optional)]
+    pub horizontal_alignment: Option<Alignment>,
+    #[props(optional)]
+    pub horizontal_spread: Option<Spread>,
+    #[props(optional)]
+    pub horizontal_offset: Option<f64>,
+    #[props(optional)]
+    pub horizontal_overflow_policy: Option<OverflowPolicy>,
+
+    // Reuse the last resolved placement (flipped side, content size) on the next open to avoid a visible jump
+    // Kept across remounts when `portal_key` is set
+    #[props(default = false)]
+    pub remember_placement: bool,
+
+    // Expected top-left corner of the content, relative to the viewport (e.g. computed on the server or saved
+    // from a previous session). The hidden first pass is placed there instead of at the outlet's origin, so it does
+    // not make layout-affecting ancestors scroll or flash. A placement remembered by `remember_placement` is used
+    // instead when there is one
+    #[props(optional)]
+    pub initial_position: Option<Point2D<f64, Pixels>>,
+
+    // Clicking inside the content brings the portal above the other portals of its layer
+    #[props(default = false)]
+    pub raise_on_interact: bool,
+
+    // How often the anchor and content rectangles are re-measured
+    #[props(default = ThrottleMode::AnimationFrame)]
+    pub throttle: ThrottleMode,
+
+    // Measures the anchor every animation frame while the portal is open, for anchors moved by a CSS
+    // transition/animation on an ancestor, which no resize or scroll event reports
+    #[props(default = false)]
+    pub track_continuously: bool,
+
+    // Closes the portal after this delay (e.g. toasts). The timer is paused while the content is hovered
+    #[props(optional)]
+    pub auto_close_ms: Option<u64>,
+    // Called when `auto_close_ms` elapsed
+    #[props(default)]
+    pub on_auto_close: Callback<()>,
+
+    // Called when the provider's dismissal stack asks this portal to close
+    // Only the topmost open portal with this callback receives Escape / outside clicks
+    #[props(optional)]
+    pub on_dismiss: Option<Callback<DismissReason>>,
+    #[props(default = true)]
+    pub dismiss_on_escape: bool,
+    #[props(default = true)]
+    pub dismiss_on_outside_click: bool,
 
     children: Element,
 }
@@ -90,24 +548,114 @@ pub struct PortalProps {
 pub struct PortalAnchorProps {
     #[props(default)]
     pub style: String,
+    // Receives whether the anchor is visible in the viewport and its scroll containers (when the portal opens and on
+    // change while it is open), e.g. to close a tooltip whose anchor scrolled out of view
+    #[props(optional)]
+    pub on_visibility_changed: Option<Callback<bool>>,
+    // Box of the anchor element that content is placed against, e.g. `MarginBox` to keep clear of its margins
+    #[props(default = ObservedBox::BorderBox)]
+    pub observed_box: ObservedBox,
+    // Presses on the anchor count as inside the portal's content, so they do not dismiss it as outside clicks
+    // (e.g. the input of a combobox, which keeps its suggestions open while clicked)
+    #[props(default = false)]
+    pub inside_content: bool,
     #[props(extends=GlobalAttributes)]
     attributes: Vec<Attribute>,
     children: Element,
 }
 
+#[derive(Props, Clone, PartialEq)]
+pub struct PortalFrameAnchorProps {
+    // Selector of the same-origin iframe in this document
+    pub frame: String,
+    // Selector of the anchor element in the iframe's document, looked up again after each load of the iframe
+    pub selector: String,
+}
+
 #[derive(Props, Clone, PartialEq)]
 pub struct PortalContentProps {
     #[props(default)]
     pub style: String,
+    // Role applied to the positioned wrapper, e.g. `ContentRole::Dialog`
+    #[props(optional)]
+    pub role: Option<ContentRole>,
+    // Overrides the `target` of the owning `Portal`
+    #[props(optional)]
+    pub target: Option<String>,
+
+    // Per-content placement. Unset values follow the owning `Portal`, so several contents (e.g. a panel and
+    // an arrow) can share one anchor while being positioned independently
+    #[props(optional)]
+    pub vertical_alignment: Option<Alignment>,
+    #[props(optional)]
+    pub vertical_spread: Option<Spread>,
+    #[props(optional)]
+    pub vertical_offset: Option<f64>,
+    #[props(optional)]
+    pub vertical_overflow_policy: Option<OverflowPolicy>,
+
+    #[props(optional)]
+    pub horizontal_alignment: Option<Alignment>,
+    #[props(optional)]
+    pub horizontal_spread: Option<Spread>,
+    #[props(optional)]
+    pub horizontal_offset: Option<f64>,
+    #[props(optional)]
+    pub horizontal_overflow_policy: Option<OverflowPolicy>,
+
+    // Make the content at least as wide as the anchor (`min-width`, which `style` can override), e.g. for the
+    // listbox of a select
+    #[props(default = false)]
+    pub match_anchor_width: bool,
+
     #[props(extends=GlobalAttributes)]
     attributes: Vec<Attribute>,
     children: Element,
 }
 
+impl PortalContentProps {
+    // Placement of this content: the portal's parameters with this content's overrides applied
+    fn axis_params(&self, data: &PortalEntryData) -> (AxisParam, AxisParam) {
+        (
+            data.vertical_param.with_overrides(
+                self.vertical_alignment,
+                self.vertical_spread,
+                self.vertical_offset,
+                self.vertical_overflow_policy,
+            ),
+            data.horizontal_param.with_overrides(
+                self.horizontal_alignment,
+                self.horizontal_spread,
+                self.horizontal_offset,
+                self.horizontal_overflow_policy,
+            ),
+        )
+    }
+}
+
+#[derive(Props, Clone, PartialEq)]
+pub struct PortalTargetProps {
+    pub name: String,
+    #[props(default)]
+    pub style: String,
+    #[props(extends=GlobalAttributes)]
+    attributes: Vec<Attribute>,
+}
+
 #[derive(Props, Clone, PartialEq)]
 pub struct PortalOverlayProps {
     #[props(default)]
     pub style: String,
+    // When unset, events reach the overlay children and bubble to the provider as usual
+    #[props(optional)]
+    pub overlay_behavior: Option<OverlayClick>,
+    // Duration of the fade in/out, overriding the provider's `enter_ms`/`exit_ms`. When non-zero, the portal
+    // (content included) stays mounted with `data-state="closed"` until the fade-out ends
+    #[props(optional)]
+    pub transition_ms: Option<u64>,
+    // Overrides the provider's `easing`
+    #[props(optional)]
+    pub transition_easing: Option<String>,
     #[props(extends=GlobalAttributes)]
     attributes: Vec<Attribute>,
     children: Element,
@@ -117,168 +665,1064 @@ pub struct PortalOverlayProps {
 
 #[component]
 pub fn PortalAnchor(props: PortalAnchorProps) -> Element {
-    let mut provider_ctx = use_context::<PortalProviderContext>();
-    let portal_ctx = use_context::<PortalContext>();
-    let id = portal_ctx.id;
+    // Outside a `Portal` (or a provider) the anchor only renders its children
+    let provider_ctx = try_use_context::<PortalProviderContext>();
+    let entries = provider_ctx.as_ref().map(|provider_ctx| provider_ctx.entries);
+    let css_anchor = provider_ctx.as_ref().map(|provider_ctx| provider_ctx.css_anchor);
+    let pointer_target = provider_ctx.as_ref().map(|provider_ctx| provider_ctx.pointer_target);
+    let direct = provider_ctx.and_then(|provider_ctx| provider_ctx.direct_positions);
+    let portal_ctx = try_use_context::<PortalContext>();
+    let id = portal_ctx.as_ref().map(|portal_ctx| portal_ctx.id);
+    let owner = portal_ctx.map_or(0, |portal_ctx| portal_ctx.owner);
+    let diagnostics = use_diagnostics();
 
     // When the anchor rectangle changes, update via this signal instead of
     // directly mutating entry.anchor_rect so the rectangle persists across rerenders
     let mut rect = use_signal(|| None);
+    let mut throttle = ThrottleMode::AnimationFrame;
+    let mut track_continuously = false;
+
+    // Observation is suspended while the portal is closed (and not fading out), and measures again on open.
+    // It is not needed either while CSS anchor positioning places every content
+    let paused = use_memo(move || {
+        let Some((entries, id)) = entries.zip(id) else { return false };
+        let css_anchor = css_anchor.is_some_and(|css_anchor| css_anchor());
+        let paused = entries
+            .read()
+            .get(&id)
+            .is_some_and(|data| (!data.is_open() && !data.closing) || (css_anchor && data.css_anchored()));
+        paused
+    });
 
-    {
-        let mut entries = provider_ctx.entries.write();
-        let entry = entries.get_mut(&id).unwrap();
-        entry.has_anchor_component = true;
-        entry.measured_anchor_rect = rect();
+    match entries.zip(id) {
+        Some((mut entries, id)) => {
+            let mut entries = entries.write();
+            match entries.get_mut(&id) {
+                Some(entry) => {
+                    throttle = entry.throttle;
+                    track_continuously = entry.track_continuously;
+                    entry.has_anchor_component = true;
+                    entry.measured_anchor_rect = rect();
+                    if entry.custom_anchor_rect.is_some() {
+                        diagnostics.warn("anchor-with-anchor-rect", &format!("{id} has both `anchor_rect` and a `PortalAnchor`; the `PortalAnchor` is ignored"));
+                    }
+                }
+                None => log_missing_entry(id, "PortalAnchor register"),
+            }
+        }
+        None if id.is_none() => diagnostics.warn("anchor-outside-portal", "`PortalAnchor` is used outside a `Portal` and has no effect"),
+        None => {}
     }
 
+    #[cfg(feature = "perf")]
+    let perf = try_use_context::<PortalProviderContext>().map(|provider_ctx| provider_ctx.perf);
+    let on_rect_changed = {
+        let direct = direct.clone();
+        move |r: Rect| {
+            #[cfg(feature = "perf")]
+            if let Some((perf, id)) = perf.as_ref().zip(id) {
+                perf.count(id, |counters| counters.anchor_reports += 1);
+            }
+            // With direct positioning an anchor move only rewrites the placed contents' positions
+            if let Some((direct, id)) = direct.as_ref().zip(id) {
+                if direct.move_anchor(id, r) {
+                    return;
+                }
+                direct.clear_anchor(id);
+            }
+            rect.set(Some(r))
+        }
+    };
+
     use_drop(move || {
         // Discard rectangle info on unmount
-        let mut entries = provider_ctx.entries.write();
-        let entry = entries.get_mut(&id).unwrap();
-        entry.has_anchor_component = false;
-        entry.measured_anchor_rect = None;
+        if let Some((direct, id)) = direct.zip(id) {
+            direct.clear_anchor(id);
+        }
+        let Some((mut entries, id)) = entries.zip(id) else { return };
+        let mut entries = entries.write();
+        // The portal may already be gone when it unmounts before its children, or replaced by another instance
+        // with the same `portal_key`
+        if let Some(entry) = entries.get_mut(&id).filter(|entry| entry.owner == owner) {
+            entry.has_anchor_component = false;
+            entry.measured_anchor_rect = None;
+        }
     });
 
-    let style = format!("{} width: fit-content; height: fit-content;", props.style);
+    // The name stays while support is enabled, so contents placed by CSS find the anchor from their first frame
+    let anchor_name = match id {
+        Some(id) if css_anchor.is_some_and(|css_anchor| css_anchor()) => format!(" anchor-name: {};", css_anchor::anchor_name(id)),
+        _ => String::new(),
+    };
+    let style = format!("{} width: fit-content; height: fit-content;{anchor_name}", props.style);
+
+    let mut attributes = props.attributes;
+    if let Some(id) = id {
+        attributes.push(Attribute::new(ANCHOR_ATTRIBUTE, id.to_string(), None, false));
+    }
 
     rsx! {
         RectObserver {
-            on_rect_changed : move |r : Rect| { rect.set(Some(r)) },
-            attributes : props.attributes,
+            on_rect_changed : on_rect_changed,
+            attributes : attributes,
             style : style,
-            {props.children}
+            throttle : throttle,
+            track_continuously : track_continuously,
+            on_visibility_changed : props.on_visibility_changed,
+            observed_box : props.observed_box,
+            paused : paused(),
+            measure_key : id.map(|id| format!("anchor:{id}")),
+            if props.inside_content {
+                div {
+                    style : "display: contents;",
+                    // Recorded like a press on the content, before the event bubbles up to the provider
+                    onpointerdown : move |_| {
+                        if let Some((mut pointer_target, id)) = pointer_target.zip(id) {
+                            pointer_target.set(Some(id));
+                        }
+                    },
+                    {props.children}
+                }
+            } else {
+                {props.children}
+            }
         }
     }
 }
 
+// Anchor inside a same-origin iframe, e.g. a control of embedded legacy content. The element is measured in the
+// frame, translated by the frame's offset in this document, and followed through the scrolls of both documents
 #[component]
-pub fn PortalContent(props: PortalContentProps) -> Element {
-    let mut provider_ctx = use_context::<PortalProviderContext>();
-    let portal_ctx = use_context::<PortalContext>();
-    let id = portal_ctx.id;
-
-    {
-        // Register content
-        let mut entries = provider_ctx.entries.write();
-        let entry = entries.get_mut(&id).unwrap();
-        entry.content = Some(props);
+pub fn PortalFrameAnchor(props: PortalFrameAnchorProps) -> Element {
+    let entries = try_use_context::<PortalProviderContext>().map(|provider_ctx| provider_ctx.entries);
+    let portal_ctx = try_use_context::<PortalContext>();
+    let id = portal_ctx.as_ref().map(|portal_ctx| portal_ctx.id);
+    let owner = portal_ctx.map_or(0, |portal_ctx| portal_ctx.owner);
+    let diagnostics = use_diagnostics();
+    let rect = use_frame_rect(props.frame, props.selector);
+
+    match entries.zip(id) {
+        Some((mut entries, id)) => {
+            let mut entries = entries.write();
+            match entries.get_mut(&id) {
+                Some(entry) => {
+                    entry.has_anchor_component = true;
+                    entry.frame_anchor = true;
+                    entry.measured_anchor_rect = rect();
+                }
+                None => log_missing_entry(id, "PortalFrameAnchor register"),
+            }
+        }
+        None if id.is_none() => diagnostics.warn("anchor-outside-portal", "`PortalFrameAnchor` is used outside a `Portal` and has no effect"),
+        None => {}
     }
 
     use_drop(move || {
-        let mut entries = provider_ctx.entries.write();
-        let entry = entries.get_mut(&id).unwrap();
-        entry.content = None;
+        let Some((mut entries, id)) = entries.zip(id) else { return };
+        let mut entries = entries.write();
+        if let Some(entry) = entries.get_mut(&id).filter(|entry| entry.owner == owner) {
+            entry.has_anchor_component = false;
+            entry.frame_anchor = false;
+            entry.measured_anchor_rect = None;
+        }
     });
 
     rsx! {}
 }
 
 #[component]
-pub fn PortalOverlay(props: PortalOverlayProps) -> Element {
-    let mut provider_ctx = use_context::<PortalProviderContext>();
-    let portal_ctx = use_context::<PortalContext>();
+pub fn PortalContent(props: PortalContentProps) -> Element {
+    let diagnostics = use_diagnostics();
+    let portal_ctx = try_use_context::<PortalContext>();
+    let Some(mut provider_ctx) = try_use_context::<PortalProviderContext>() else {
+        // Without a provider the content is rendered in place while the portal is open
+        return match portal_ctx.and_then(|portal_ctx| portal_ctx.inline_open) {
+            Some(open) if open() => rsx! {
+                div {
+                    style : props.style,
+                    ..props.attributes,
+                    {props.children}
+                }
+            },
+            _ => rsx! {},
+        };
+    };
+    let Some(portal_ctx) = portal_ctx else {
+        diagnostics.warn("content-outside-portal", "`PortalContent` is used outside a `Portal` and is not shown");
+        return rsx! {};
+    };
     let id = portal_ctx.id;
+    let content_id = use_hook(alloc_content_id);
 
     {
-        // Register overlay
-        let mut entries = provider_ctx.entries.write();
-        let entry = entries.get_mut(&id).unwrap();
-        entry.overlay = Some(props);
+        // Register content. A portal may have several; they keep their mount order
+        // Unchanged props are not written, so a re-render of the content does not notify the registry
+        let registered = provider_ctx.entries.peek().get(&id).map(|entry| {
+            entry.contents.iter().find(|(other, _)| *other == content_id).map(|(_, content)| **content == props)
+        });
+        match registered {
+            Some(Some(true)) => {}
+            Some(_) => {
+                let mut entries = provider_ctx.entries.write();
+                if let Some(entry) = entries.get_mut(&id) {
+                    match entry.contents.iter_mut().find(|(other, _)| *other == content_id) {
+                        Some((_, content)) => *content = Registered::new(props),
+                        None => entry.contents.push((content_id, Registered::new(props))),
+                    }
+                }
+            }
+            None => {
+                log_missing_entry(id, "PortalContent register");
+                diagnostics.warn("content-after-drop", &format!("`PortalContent` rendered after {id} was dropped; the content is not shown"));
+            }
+        }
     }
 
     use_drop(move || {
         let mut entries = provider_ctx.entries.write();
-        let entry = entries.get_mut(&id).unwrap();
-        entry.content = None;
+        if let Some(entry) = entries.get_mut(&id) {
+            entry.contents.retain(|(other, _)| *other != content_id);
+        }
     });
 
     rsx! {}
 }
 
 #[component]
-pub fn Portal(props: PortalProps) -> Element {
-    let mut provider_ctx = use_context::<PortalProviderContext>();
-    let id = use_memo(|| alloc_id());
-    let id = id(); 
-
-    // Share the portal ID with children
-    use_context_provider(|| PortalContext { id });
-
-    let entry_data = {
-        let param_v = AxisParam {
-            alignment: props.vertical_alignment,
-            spread: props.vertical_spread,
-            offset: props.vertical_offset,
-            overflow_policy: props.vertical_overflow_policy,
-        };
-
-        let param_h = AxisParam {
-            alignment: props.horizontal_alignment,
-            spread: props.horizontal_spread,
-            offset: props.horizontal_offset,
-            overflow_policy: props.horizontal_overflow_policy,
-        };
-
-        PortalEntryData {
-            id: id,
-            open: props.open,
-            layer: props.layer,
-            vertical_param: param_v,
-            horizontal_param: param_h,
-            has_anchor_component: false, // If an anchor exists, becomes true when `PortalAnchor` is rendered
-            measured_anchor_rect: None,
-            custom_anchor_rect: props.anchor_rect,
-            content: None,
-            overlay: None,
-        }
+pub fn PortalOverlay(props: PortalOverlayProps) -> Element {
+    // Overlays are not rendered without a provider
+    let (Some(mut provider_ctx), Some(portal_ctx)) =
+        (try_use_context::<PortalProviderContext>(), try_use_context::<PortalContext>())
+    else {
+        return rsx! {};
     };
+    let id = portal_ctx.id;
 
     {
-        // Register portal
-        let mut entries = provider_ctx.entries.write();
-        entries.insert(id, entry_data);
+        // Register overlay, unless the registered props are unchanged
+        let registered = provider_ctx.entries.peek().get(&id).map(|entry| entry.overlay.as_deref() == Some(&props));
+        match registered {
+            Some(true) => {}
+            Some(false) => {
+                let mut entries = provider_ctx.entries.write();
+                if let Some(entry) = entries.get_mut(&id) {
+                    entry.overlay = Some(Registered::new(props));
+                }
+            }
+            None => log_missing_entry(id, "PortalOverlay register"),
+        }
     }
 
+    let owner = portal_ctx.owner;
     use_drop(move || {
         let mut entries = provider_ctx.entries.write();
-        entries.remove(&id);
+        if let Some(entry) = entries.get_mut(&id).filter(|entry| entry.owner == owner) {
+            entry.overlay = None;
+        }
     });
 
-    rsx! {
-        {props.children}
-    }
+    rsx! {}
 }
 
+// Region rendering the portals whose `target` matches `name`. Must be placed inside the `PortalProvider`
+// The region is positioned by `style`; portal content is laid out within its bounds
 #[component]
-pub fn PortalProvider(props: PortalProviderProps) -> Element {
-    let entries = use_signal(|| HashMap::new());
-
-    use_context_provider(|| PortalProviderContext { entries });
+pub fn PortalTarget(props: PortalTargetProps) -> Element {
+    if try_use_context::<PortalProviderContext>().is_none() {
+        use_hook(|| diagnostics::error("`PortalTarget` is used outside a `PortalProvider` and renders nothing"));
+        return rsx! {};
+    }
 
     rsx! {
-        div {
-            style : format!("{} position: relative;", props.style),
-            ..props.attribute,
+        PortalOutlet {
+            target : props.name,
+            style : format!("position: relative; pointer-events: none; {}", props.style),
+            attributes : props.attributes,
+        }
+    }
+}
 
-            div {
-                style : "position: absolute; top: 0; left: 0; width: 100%; height: 100%; z-index: 0;",
+#[component]
+pub fn Portal(props: PortalProps) -> Element {
+    // Without a provider there is no outlet: fall back to rendering the content in place
+    let Some(mut provider_ctx) = try_use_context::<PortalProviderContext>() else {
+        return rsx! {
+            InlinePortal {
+                open : props.open,
                 {props.children}
             }
+        };
+    };
+    let id = use_hook(|| match &props.portal_key {
+        Some(key) => keyed_id(provider_ctx.keyed_ids, key),
+        None => alloc_id(),
+    });
+    // Portals sharing a `portal_key` share the id, so the entry records which instance registered it last
+    let owner = use_hook(alloc_component_key);
 
-            PortalOutlet {}
+    // Share the portal ID with children
+    use_context_provider(|| PortalContext { id, owner, inline_open: None });
+    let dialog_target = try_use_context::<DialogTarget>();
+
+    let group_ctx = try_use_context::<PortalGroupContext>();
+    let group = props
+        .group
+        .clone()
+        .or_else(|| group_ctx.as_ref().map(|ctx| ctx.name.clone()));
+
+    // Coalesce `open` toggles within `open_debounce_ms`: only a value that held for the whole window is applied
+    let mut settled_open = use_signal(|| props.open);
+    let mut requested_open = use_signal(|| props.open);
+    let mut debounce_generation = use_signal(|| 0_u64);
+    if *requested_open.peek() != props.open {
+        requested_open.set(props.open);
+        *debounce_generation.write() += 1;
+        match props.open_debounce_ms {
+            Some(ms) if ms > 0 => {
+                let generation = *debounce_generation.peek();
+                let requested = props.open;
+                spawn(async move {
+                    sleep(ms).await;
+                    if *debounce_generation.peek() == generation && *settled_open.peek() != requested {
+                        settled_open.set(requested);
+                    }
+                });
+            }
+            _ => settled_open.set(props.open),
         }
     }
-}
+    let requested = settled_open();
 
-// ------ Internal Types -------------------------------------------------------------------------------------------------------------------
+    // Inside a `PortalGroup`, showing the portal may be delayed until the group is warm
+    let mut delay_done = use_signal(|| true);
+    let mut delay_generation = use_signal(|| 0_u64);
 
-// Unique identifier for a portal
-#[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
-struct PortalId(u64);
+    // Handle open/close transitions. Read with `peek` so this component does not subscribe to its own write
+    let mut was_open = use_signal(|| false);
+    if *was_open.peek() != requested {
+        was_open.set(requested);
 
-impl Display for PortalId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let delay = match (&group_ctx, requested) {
+            (Some(ctx), true) => ctx.open_delay(),
+            _ => 0,
+        };
+        *delay_generation.write() += 1;
+        if delay > 0 {
+            delay_done.set(false);
+            let generation = *delay_generation.peek();
+            spawn(async move {
+                sleep(delay).await;
+                if *delay_generation.peek() == generation {
+                    delay_done.set(true);
+                }
+            });
+        } else {
+            delay_done.set(true);
+        }
+
+        if let (true, Some(group)) = (requested, &group) {
+            close_entries(
+                provider_ctx.entries,
+                |data| data.id != id && data.group.as_ref() == Some(group),
+                DismissReason::GroupSwitch,
+            );
+        }
+
+        let message = if requested {
+            props.open_announcement.clone()
+        } else {
+            props.close_announcement.clone()
+        };
+        if let (true, Some(message)) = (provider_ctx.settings.peek().live_region, message) {
+            provider_ctx.announcement.set(message);
+        }
+    }
+
+    let open = requested && delay_done();
+
+    // Keeps the portal rendered while the overlay fades out
+    let mut closing = use_signal(|| false);
+    let mut closing_generation = use_signal(|| 0_u64);
+
+    let mut was_visible = use_signal(|| false);
+
+    // Warn about an open portal without content, once its children have registered
+    let diagnostics = use_diagnostics();
+    {
+        let entries = provider_ctx.entries;
+        let diagnostics = diagnostics.clone();
+        use_effect(move || {
+            let open = was_visible();
+            if open && entries.peek().get(&id).is_some_and(|data| data.contents.is_empty()) {
+                diagnostics.warn("no-content", &format!("{id} is open but has no `PortalContent`"));
+            }
+        });
+    }
+
+    // Warn when another mounted portal holds the same `portal_key`. Checked after the first render, once a portal
+    // this one replaced in the same render has unmounted
+    {
+        let keyed_ids = provider_ctx.keyed_ids;
+        let diagnostics = diagnostics.clone();
+        let portal_key = props.portal_key.clone();
+        use_effect(move || {
+            let Some(key) = &portal_key else { return };
+            if keyed_ids.peek().get(key).is_some_and(|(_, mounted)| *mounted > 1) {
+                diagnostics.warn("duplicate-portal-key", &format!("`portal_key` \"{key}\" is used by several mounted portals; they share {id} and overwrite each other"));
+            }
+        });
+    }
+
+    // Report visibility changes to the group
+    if *was_visible.peek() != open {
+        was_visible.set(open);
+        if !open {
+            // The content unmounts without a `pointerleave`
+            provider_ctx.hovered.write().remove(&id);
+        }
+
+        *closing_generation.write() += 1;
+        let exit_ms = {
+            let entries = provider_ctx.entries.peek();
+            let overlay = entries.get(&id).and_then(|data| data.overlay.as_deref());
+            provider_ctx
+                .motion
+                .peek()
+                .resolve(overlay, *provider_ctx.prefers_reduced_motion.peek())
+                .exit_ms
+        };
+        if !open && exit_ms > 0 {
+            closing.set(true);
+            let generation = *closing_generation.peek();
+            spawn(async move {
+                sleep(exit_ms).await;
+                if *closing_generation.peek() == generation {
+                    closing.set(false);
+                }
+            });
+        } else {
+            closing.set(false);
+        }
+
+        if let Some(mut ctx) = group_ctx.clone() {
+            if open {
+                ctx.activate(id);
+            } else {
+                ctx.deactivate(id);
+            }
+        }
+    }
+
+    // Auto-close timer, restarted whenever the content stops being hovered
+    let hovered = props.auto_close_ms.is_some() && provider_ctx.hovered.read().contains(&id);
+    let timer_running = open && !hovered && props.auto_close_ms.is_some();
+    let mut was_timer_running = use_signal(|| false);
+    let mut auto_close_generation = use_signal(|| 0_u64);
+    if *was_timer_running.peek() != timer_running {
+        was_timer_running.set(timer_running);
+        *auto_close_generation.write() += 1;
+        if let (true, Some(delay)) = (timer_running, props.auto_close_ms) {
+            let generation = *auto_close_generation.peek();
+            let entries = provider_ctx.entries;
+            let on_auto_close = props.on_auto_close;
+            spawn(async move {
+                sleep(delay).await;
+                if *auto_close_generation.peek() == generation {
+                    close_entries(entries, |data| data.id == id, DismissReason::Timeout);
+                    on_auto_close.call(());
+                }
+            });
+        }
+    }
+
+    let entry_data = {
+        let (default_v, default_h) = match props.config {
+            Some(config) => (config.vertical, config.horizontal),
+            None => *provider_ctx.placement_defaults.read(),
+        };
+
+        let param_v = default_v.with_overrides(
+            props.vertical_alignment,
+            props.vertical_spread,
+            props.vertical_offset,
+            props.vertical_overflow_policy,
+        );
+
+        let param_h = default_h.with_overrides(
+            props.horizontal_alignment,
+            props.horizontal_spread,
+            props.horizontal_offset,
+            props.horizontal_overflow_policy,
+        );
+
+        PortalEntryData {
+            id: id,
+            owner,
+            open,
+            closing: closing(),
+            suppressed: false,
+            stack_key: 0,
+            open_seq: 0,
+            key: props.portal_key.clone(),
+            target: props.target.clone().or_else(|| dialog_target.clone().map(|target| target.0)),
+            teleport: props.teleport,
+            light_dismiss: props.light_dismiss,
+            native_dialog: props.native_dialog,
+            mount_selector: props.mount_selector.clone(),
+            group,
+            remember_placement: props.remember_placement,
+            initial_position: props.initial_position,
+            raise_on_interact: props.raise_on_interact,
+            throttle: props.throttle,
+            track_continuously: props.track_continuously,
+            window: props.window,
+            layer: props.layer,
+            vertical_param: param_v,
+            horizontal_param: param_h,
+            has_anchor_component: false, // If an anchor exists, becomes true when `PortalAnchor` is rendered
+            frame_anchor: false,
+            measured_anchor_rect: None,
+            custom_anchor_rect: props.anchor_rect.or(props.config.and_then(|config| config.anchor_rect)),
+            dismiss: props.on_dismiss.map(|on_dismiss| DismissParam {
+                on_dismiss,
+                on_escape: props.dismiss_on_escape,
+                on_outside_click: props.dismiss_on_outside_click,
+            }),
+            contents: Vec::new(),
+            overlay: None,
+        }
+    };
+
+    {
+        // Register portal. A portal closed by the provider stays hidden until `open` is turned off
+        let mut entries = provider_ctx.entries.write();
+        let suppressed = open && entries.get(&id).is_some_and(|data| data.suppressed);
+        let stack_key = entries.get(&id).map_or(0, |data| data.stack_key);
+        let open_seq = match entries.get(&id) {
+            Some(data) if data.open || !open => data.open_seq,
+            _ if open => alloc_open_seq(),
+            _ => 0,
+        };
+        // Contents and the overlay are registered by child components, which may not re-render with the portal.
+        // Those of another instance with the same `portal_key` (about to unmount) are not taken over
+        let (contents, overlay) = entries
+            .remove(&id)
+            .filter(|data| data.owner == owner)
+            .map_or((Vec::new(), None), |data| (data.contents, data.overlay));
+        entries.insert(
+            id,
+            PortalEntryData {
+                suppressed,
+                stack_key,
+                open_seq,
+                contents,
+                overlay,
+                ..entry_data
+            },
+        );
+    }
+
+    {
+        let group_ctx = group_ctx.clone();
+        let portal_key = props.portal_key.clone();
+        use_drop(move || {
+            if let Some(key) = &portal_key {
+                release_keyed_id(provider_ctx.keyed_ids, key);
+            }
+            // A portal with the same `portal_key` mounted in the same render registered the entry under this id
+            let mut entries = provider_ctx.entries.write();
+            if entries.get(&id).is_some_and(|data| data.owner != owner) {
+                return;
+            }
+            entries.remove(&id);
+            provider_ctx.hovered.write().remove(&id);
+            if portal_key.is_none() {
+                provider_ctx.placements.write().remove(&id);
+            }
+            if let Some(mut ctx) = group_ctx {
+                ctx.deactivate(id);
+            }
+        });
+    }
+
+    #[cfg(not(all(feature = "desktop", not(target_arch = "wasm32"))))]
+    if matches!(props.window, PortalWindow::Detached(_)) {
+        diagnostics.warn("detached-without-desktop", &format!("{id} uses `PortalWindow::Detached` without the `desktop` feature; the content is shown in the outlet"));
+    }
+    #[cfg(all(feature = "desktop", not(target_arch = "wasm32")))]
+    if let (true, PortalWindow::Detached(content)) = (open, props.window) {
+        return rsx! {
+            {props.children}
+            DetachedWindow { id, content }
+        };
+    }
+
+    rsx! {
+        {props.children}
+    }
+}
+
+#[derive(Props, Clone, PartialEq)]
+struct InlinePortalProps {
+    pub open: bool,
+    children: Element,
+}
+
+// Fallback of `Portal` without a provider: `PortalContent` renders in place while open, overlays are dropped
+#[component]
+fn InlinePortal(props: InlinePortalProps) -> Element {
+    use_hook(|| diagnostics::error("`Portal` is used outside a `PortalProvider`; its content is rendered in place"));
+    let id = use_hook(alloc_id);
+
+    let mut open = use_signal(|| props.open);
+    if *open.peek() != props.open {
+        open.set(props.open);
+    }
+    use_context_provider(|| PortalContext { id, owner: 0, inline_open: Some(open) });
+
+    rsx! {
+        {props.children}
+    }
+}
+
+#[component]
+pub fn PortalProvider(props: PortalProviderProps) -> Element {
+    let eval = use_context_provider(|| EvalSupport(props.eval)).0;
+    let entries = use_signal(|| HashMap::new());
+    let keyed_ids = use_signal(HashMap::new);
+    let hovered = use_signal(HashSet::new);
+    let placements = use_signal(HashMap::new);
+    let reposition = use_signal(|| 0);
+    let outlet_rect = use_signal(|| None);
+    let layouts = use_hook(|| CopyValue::new(HashMap::new()));
+    #[cfg(feature = "debug")]
+    let debug_rects = use_signal(Default::default);
+    let announcement = use_signal(String::new);
+    let mut pointer_target = use_signal(|| None);
+    let mut pending_tap = use_signal(|| None::<PendingTap>);
+    let shadow_root = (props.shadow_root && eval).then(|| props.shadow_root_style.clone().unwrap_or_default());
+    let direct_positions = use_hook(|| (props.direct_positioning && eval).then(DirectPositions::default));
+
+    let placement = (props.default_vertical, props.default_horizontal);
+    let mut placement_defaults = use_signal(|| placement);
+    if *placement_defaults.peek() != placement {
+        placement_defaults.set(placement);
+    }
+
+    let motion_value = MotionDefaults {
+        enter_ms: props.enter_ms,
+        exit_ms: props.exit_ms,
+        easing: props.easing.clone(),
+        reduced_motion: props.reduced_motion,
+    };
+    let mut motion = use_signal(|| motion_value.clone());
+    if *motion.peek() != motion_value {
+        motion.set(motion_value);
+    }
+    let prefers_reduced_motion = use_prefers_reduced_motion();
+    let css_anchor = css_anchor::use_css_anchor_support(props.css_anchor_positioning);
+
+    let mut default_overlay = use_signal(|| props.default_overlay.clone());
+    if *default_overlay.peek() != props.default_overlay {
+        default_overlay.set(props.default_overlay.clone());
+    }
+
+    let settings_value = ProviderSettings {
+        live_region: props.live_region,
+        teleport: props.teleport,
+        z_index_base: props.z_index_base,
+        z_index_stride: props.z_index_stride.max(2),
+        bounds_padding: props.bounds_padding.max(0.0),
+        shared_overlay: props.shared_overlay,
+        overlay_stacking: props.overlay_stacking,
+    };
+    let mut settings = use_signal(|| settings_value);
+    if *settings.peek() != settings_value {
+        settings.set(settings_value);
+    }
+
+    // Report open/close transitions after the render that caused them, once content roles are registered
+    let open_portals = use_memo(move || PortalProviderHandle { entries, reposition, outlet_rect, layouts }.open_portals());
+    let mut reported_portals = use_signal(Vec::<OpenPortal>::new);
+    let on_portal_opened = props.on_portal_opened;
+    let on_portal_closed = props.on_portal_closed;
+    use_effect(move || {
+        let current = open_portals();
+        let previous = std::mem::replace(&mut *reported_portals.write(), current.clone());
+        for portal in previous.iter().filter(|p| !current.iter().any(|c| c.id == p.id)) {
+            on_portal_closed.call(portal.clone());
+        }
+        for portal in current.iter().filter(|c| !previous.iter().any(|p| p.id == c.id)) {
+            on_portal_opened.call(portal.clone());
+        }
+    });
+
+    // A bubbling provider shares the context of the enclosing provider, which already points to the outermost one
+    let outer_ctx = try_use_context::<PortalProviderContext>();
+    let bubbling = use_hook(|| props.bubble && !props.isolated && outer_ctx.is_some());
+
+    // Each provider instance observes through its own JS registry
+    use_hook(|| {
+        let namespace = props.namespace.clone().unwrap_or_else(alloc_namespace);
+        provide_context(ObserverNamespace(namespace));
+        if let Some(backend) = props.measure_backend.clone() {
+            provide_context(backend);
+        }
+    });
+    use_registry_teardown();
+    // Nothing below talks to JS until the provider is mounted on the client
+    client::use_client_mounted();
+
+    // Rectangles of the observers below this provider are reported in one batch per frame
+    let hub = use_rect_hub(
+        props.observer_poll_ms,
+        props.observer_min_delta,
+        props.measure_frequency,
+        props.on_observer_error,
+        props.js_rect_hook.clone(),
+    );
+
+    let provider_ctx = use_context_provider(|| match (bubbling, outer_ctx) {
+        (true, Some(outer_ctx)) => outer_ctx,
+        _ => PortalProviderContext {
+            entries,
+            keyed_ids,
+            hovered,
+            placements,
+            settings,
+            shadow_root: shadow_root.clone(),
+            default_overlay,
+            placement_defaults,
+            motion,
+            prefers_reduced_motion,
+            announcement,
+            pointer_target,
+            reposition,
+            outlet_rect,
+            layouts,
+            direct_positions: direct_positions.clone(),
+            css_anchor,
+            #[cfg(feature = "debug")]
+            debug_rects,
+            #[cfg(feature = "perf")]
+            perf: Default::default(),
+        },
+    });
+
+    // Measure everything again on `PortalProviderHandle::reposition`. A bubbling provider follows the outer one's
+    {
+        let reposition = provider_ctx.reposition;
+        use_effect(move || {
+            if reposition() > 0 {
+                hub.remeasure();
+            }
+        });
+    }
+
+    let root_rect = use_root_rect(props.root.clone());
+    let fragment = props.fragment || props.root.is_some();
+
+    // In fragment mode the wrapper generates no box but still receives bubbling events for dismissal
+    let root_style = if fragment {
+        format!("{} display: contents;", props.style)
+    } else {
+        format!("{} position: relative;", props.style)
+    };
+
+    if bubbling {
+        // Dismissal and rendering are handled by the outermost provider
+        return rsx! {
+            div {
+                style : root_style,
+                ..props.attribute,
+                {props.children}
+            }
+        };
+    }
+
+    let outlet_style = match (&props.root, root_rect()) {
+        // Not rendered until the root is measured
+        (Some(_), None) => None,
+        (Some(_), Some(r)) => Some(format!("position: fixed; top: {}px; left: {}px; width: {}px; height: {}px;", r.origin.y, r.origin.x, r.size.width, r.size.height)),
+        (None, _) if fragment => Some("position: fixed; top: 0; left: 0; width: 100%; height: 100%;".to_string()),
+        (None, _) => Some("position: absolute; top: 0; left: 0; width: 100%; height: 100%;".to_string()),
+    };
+
+    rsx! {
+        div {
+            style : root_style,
+            onkeydown : move |evt: KeyboardEvent| {
+                if evt.key() == Key::Escape {
+                    dismiss_topmost(entries, DismissReason::EscapeKey, None);
+                }
+            },
+            onpointerdown : move |evt: PointerEvent| {
+                // `PortalEntry` records the portal under the pointer before the event bubbles up here
+                let inside = *pointer_target.peek();
+                pointer_target.set(None);
+                match PendingTap::start(&evt, inside) {
+                    Some(tap) => pending_tap.set(Some(tap)),
+                    None => dismiss_topmost(entries, DismissReason::OutsideClick, inside),
+                }
+            },
+            onpointerup : move |evt: PointerEvent| {
+                if let Some(tap) = pending_tap.take().filter(|tap| tap.is_tap(&evt)) {
+                    dismiss_topmost(entries, DismissReason::OutsideClick, tap.inside);
+                }
+            },
+            // The browser took the touch over for scrolling or zooming
+            onpointercancel : move |_| pending_tap.set(None),
+            ..props.attribute,
+
+            if fragment {
+                {props.children}
+            } else {
+                div {
+                    style : "position: absolute; top: 0; left: 0; width: 100%; height: 100%; z-index: 0;",
+                    {props.children}
+                }
+            }
+
+            style { "@keyframes {FADE_IN_KEYFRAMES} {{ from {{ opacity: 0; }} to {{ opacity: 1; }} }}" }
+
+            if let Some(outlet_style) = outlet_style {
+                PortalOutlet {
+                    style : format!("{outlet_style} z-index: {}; pointer-events: none;{}", props.z_index_base, if props.clip_to_provider { " overflow: hidden;" } else { "" }),
+                }
+            }
+
+            if props.live_region {
+                div {
+                    role : "status",
+                    "aria-live" : "polite",
+                    "aria-atomic" : "true",
+                    style : "position: absolute; width: 1px; height: 1px; margin: -1px; padding: 0; overflow: hidden; clip: rect(0 0 0 0); white-space: nowrap; border: 0;",
+                    {announcement()}
+                }
+            }
+        }
+    }
+}
+
+// ------ Provider handle -------------------------------------------------------------------------------------------------------------------
+
+// Handle to the nearest `PortalProvider`, obtained with `use_portal_provider`
+#[derive(Clone, Copy, PartialEq)]
+pub struct PortalProviderHandle {
+    entries: Signal<HashMap<PortalId, PortalEntryData>>,
+    reposition: Signal<u64>,
+    outlet_rect: Signal<Option<Rect>>,
+    layouts: CopyValue<HashMap<(PortalId, u64), LayoutCache>>,
+}
+
+impl std::fmt::Debug for PortalProviderHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PortalProviderHandle").finish_non_exhaustive()
+    }
+}
+
+impl PortalProviderHandle {
+    // Open portals from bottom to top. Subscribes the caller to registry changes
+    pub fn open_portals(&self) -> Vec<OpenPortal> {
+        let entries = self.entries.read();
+        open_stack_order(&entries)
+            .into_iter()
+            .filter_map(|id| entries.get(&id))
+            .map(|data| OpenPortal {
+                id: data.id,
+                key: data.key.clone(),
+                layer: data.layer,
+                role: data.content().and_then(|content| content.role),
+            })
+            .collect()
+    }
+
+    // Whether an open portal has `ContentRole::Dialog` content
+    pub fn any_modal_open(&self) -> bool {
+        let entries = self.entries.read();
+        entries.values().any(|data| data.is_open() && data.is_modal())
+    }
+
+    // Moves the portal above the other portals of its layer
+    pub fn bring_to_front(&self, id: PortalId) {
+        self.restack(id, true);
+    }
+
+    // Moves the portal below the other portals of its layer
+    pub fn send_to_back(&self, id: PortalId) {
+        self.restack(id, false);
+    }
+
+    fn restack(&self, id: PortalId, to_front: bool) {
+        let mut entries = self.entries;
+        let key = {
+            let entries = entries.peek();
+            let Some(data) = entries.get(&id) else {
+                return;
+            };
+            let siblings = entries
+                .values()
+                .filter(|other| other.id != id && other.layer == data.layer)
+                .map(|other| other.stack_key);
+            // Skip the write (and the outlet re-render) when the portal is already in place
+            match (to_front, siblings.clone().max(), siblings.min()) {
+                (true, Some(max), _) if max < data.stack_key => return,
+                (true, max, _) => max.map_or(0, |key| key + 1),
+                (false, _, Some(min)) if data.stack_key < min => return,
+                (false, _, min) => min.map_or(0, |key| key - 1),
+            }
+        };
+        let mut entries = entries.write();
+        if let Some(data) = entries.get_mut(&id) {
+            data.stack_key = key;
+        }
+    }
+
+    // Measures every anchor, content, and outlet again, e.g. after scrolling with `MeasureFrequency::Low`,
+    // which does not follow scrolls
+    pub fn reposition(&self) {
+        let mut reposition = self.reposition;
+        *reposition.write() += 1;
+    }
+
+    // Viewport rectangle of the provider's default outlet, `None` until it is measured. Subscribes the caller
+    pub fn outlet_rect(&self) -> Option<Rect> {
+        (self.outlet_rect)()
+    }
+
+    // Converts a viewport point (e.g. `ClientPoint` of a mouse event, cast to `Pixels`) to provider coordinates,
+    // relative to the default outlet. `None` until the outlet is measured
+    pub fn viewport_to_provider(&self, point: Point) -> Option<Point> {
+        self.outlet_rect().map(|outlet| point - outlet.origin.to_vector())
+    }
+
+    // Converts a point relative to the default outlet to viewport coordinates. `None` until the outlet is measured
+    pub fn provider_to_viewport(&self, point: Point) -> Option<Point> {
+        self.outlet_rect().map(|outlet| point + outlet.origin.to_vector())
+    }
+
+    // Open portals from bottom to top with their placement parameters and the rectangles of their last layout,
+    // for bug reports and logs (`{:#?}`). Does not subscribe the caller
+    pub fn debug_snapshot(&self) -> ProviderSnapshot {
+        let entries = self.entries.peek();
+        let layouts = self.layouts.read();
+        let portals = open_stack_order(&entries)
+            .into_iter()
+            .filter_map(|id| entries.get(&id))
+            .map(|data| PortalSnapshot {
+                id: data.id,
+                key: data.key.clone(),
+                layer: data.layer,
+                target: data.target.clone(),
+                vertical: data.vertical_param,
+                horizontal: data.horizontal_param,
+                anchor_rect: data.custom_anchor_rect.or(data.measured_anchor_rect),
+                contents: data
+                    .contents
+                    .iter()
+                    .map(|(content_id, content)| {
+                        let (vertical, horizontal) = content.axis_params(data);
+                        let layout = layouts.get(&(data.id, *content_id)).and_then(|cache| cache.0.get());
+                        ContentSnapshot {
+                            role: content.role,
+                            vertical,
+                            horizontal,
+                            anchor_rect: layout.and_then(|(inputs, _)| inputs.anchor),
+                            rect: layout.map(|(inputs, (position, _))| Rect::new(position, inputs.size)),
+                            placement: layout.map(|(_, (_, resolved))| resolved),
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+        ProviderSnapshot {
+            outlet_rect: *self.outlet_rect.peek(),
+            portals,
+        }
+    }
+
+    // Closes every open portal
+    pub fn close_all(&self) {
+        close_entries(self.entries, |_| true, DismissReason::Programmatic);
+    }
+
+    // Closes every open portal on the given layer
+    pub fn close_layer(&self, layer: impl Into<i32>) {
+        let layer = layer.into();
+        close_entries(self.entries, |data| data.layer == layer, DismissReason::Programmatic);
+    }
+}
+
+// Panics outside a `PortalProvider`; see `try_use_portal_provider`
+pub fn use_portal_provider() -> PortalProviderHandle {
+    use_context::<PortalProviderContext>().handle()
+}
+
+// Handle to the nearest `PortalProvider`, or `None` when there is none (e.g. in a library component that
+// should also work without one)
+pub fn try_use_portal_provider() -> Option<PortalProviderHandle> {
+    try_use_context::<PortalProviderContext>().map(|provider_ctx| provider_ctx.handle())
+}
+
+// Summary of an open portal returned by `use_open_portals`
+#[derive(Clone, PartialEq, Debug)]
+pub struct OpenPortal {
+    pub id: PortalId,
+    pub key: Option<String>, // `portal_key` of the portal
+    pub layer: i32,
+    pub role: Option<ContentRole>,
+}
+
+// State of a provider returned by `PortalProviderHandle::debug_snapshot`
+#[derive(Clone, PartialEq, Debug)]
+pub struct ProviderSnapshot {
+    pub outlet_rect: Option<Rect>, // Viewport rectangle of the default outlet, once measured
+    pub portals: Vec<PortalSnapshot>, // Open portals from bottom to top
+}
+
+// An open portal in a `ProviderSnapshot`
+#[derive(Clone, PartialEq, Debug)]
+pub struct PortalSnapshot {
+    pub id: PortalId,
+    pub key: Option<String>,
+    pub layer: i32,
+    pub target: Option<String>,
+    pub vertical: AxisParam,          // Placement of the portal, with the provider defaults applied
+    pub horizontal: AxisParam,
+    pub anchor_rect: Option<Rect>,    // `anchor_rect`, else the measured `PortalAnchor` rectangle
+    pub contents: Vec<ContentSnapshot>, // Registered `PortalContent`s in mount order
+}
+
+// A `PortalContent` in a `PortalSnapshot`
+#[derive(Clone, PartialEq, Debug)]
+pub struct ContentSnapshot {
+    pub role: Option<ContentRole>,
+    pub vertical: AxisParam,          // Placement of the content, with its overrides applied
+    pub horizontal: AxisParam,
+    pub anchor_rect: Option<Rect>,    // Anchor rectangle of its last layout
+    pub rect: Option<Rect>,           // Viewport rectangle of its last layout, `None` until it is placed
+    pub placement: Option<ResolvedPlacement>, // Placement of its last layout, after flipping
+}
+
+// Open portals from bottom to top, recomputed when the registry changes
+pub fn use_open_portals() -> Memo<Vec<OpenPortal>> {
+    let handle = use_portal_provider();
+    use_memo(move || handle.open_portals())
+}
+
+// Id of the enclosing `Portal`
+pub fn use_portal_id() -> PortalId {
+    use_context::<PortalContext>().id
+}
+
+// ------ Internal Types -------------------------------------------------------------------------------------------------------------------
+
+// Unique identifier for a portal
+#[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct PortalId(u64);
+
+impl Display for PortalId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Portal-{}", self.0)
     }
 }
@@ -293,115 +1737,646 @@ fn alloc_id() -> PortalId {
     PortalId(n)
 }
 
+static NEXT_OPEN_SEQ: GlobalSignal<u64> = Signal::global(|| 0);
+fn alloc_open_seq() -> u64 {
+    let mut w = NEXT_OPEN_SEQ.write();
+    *w += 1;
+    *w
+}
+
+// Reports an access to a portal that is no longer registered, which happens when mount/unmount ordering races
+// (e.g. a child outliving its `Portal` during suspense). The access is skipped
+fn log_missing_entry(id: PortalId, during: &str) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(portal = %id, "{during}: portal is not registered, skipped");
+    #[cfg(not(feature = "tracing"))]
+    let _ = (id, during);
+}
+
+static NEXT_CONTENT_ID: GlobalSignal<u64> = Signal::global(|| 0);
+fn alloc_content_id() -> u64 {
+    let mut w = NEXT_CONTENT_ID.write();
+    *w += 1;
+    *w
+}
+
+static NEXT_NAMESPACE: GlobalSignal<u64> = Signal::global(|| 0);
+fn alloc_namespace() -> String {
+    let n = {
+        let mut w = NEXT_NAMESPACE.write();
+        *w += 1;
+        *w
+    };
+    format!("provider-{}", n)
+}
+
+static NEXT_COMPONENT_KEY: GlobalSignal<u64> = Signal::global(|| 0);
+// Number unique among the instances of the ready-made components
+pub(crate) fn alloc_component_key() -> u64 {
+    let mut w = NEXT_COMPONENT_KEY.write();
+    *w += 1;
+    *w
+}
+
+// DOM id of a ready-made component instance: `prefix` (e.g. `dioxus-portal-tooltip-`) and a unique number
+pub(crate) fn alloc_component_id(prefix: &str) -> String {
+    format!("{prefix}{}", alloc_component_key())
+}
+
+// Returns the id previously assigned to `key`, allocating one on first use, and counts the portal as mounted
+fn keyed_id(mut keyed_ids: Signal<HashMap<String, (PortalId, usize)>>, key: &str) -> PortalId {
+    let mut keyed_ids = keyed_ids.write();
+    let (id, mounted) = keyed_ids.entry(key.to_string()).or_insert_with(|| (alloc_id(), 0));
+    *mounted += 1;
+    *id
+}
+
+// Counts a portal using `key` as unmounted. The id stays assigned, so a later portal with the key gets it back
+fn release_keyed_id(mut keyed_ids: Signal<HashMap<String, (PortalId, usize)>>, key: &str) {
+    if let Some((_, mounted)) = keyed_ids.write().get_mut(key) {
+        *mounted = mounted.saturating_sub(1);
+    }
+}
+
 // Context provided at the portal root and shared globally
 #[derive(Clone)]
 struct PortalProviderContext {
     pub entries: Signal<HashMap<PortalId, PortalEntryData>>,
+    pub keyed_ids: Signal<HashMap<String, (PortalId, usize)>>, // Ids assigned to `portal_key`s and their mounted portals
+    pub hovered: Signal<HashSet<PortalId>>,           // Portals whose content is under the pointer
+    pub placements: Signal<HashMap<PortalId, ResolvedPlacement>>, // Placement memory for `remember_placement`
+    pub settings: Signal<ProviderSettings>, // Settings from `PortalProviderProps`
+    pub shadow_root: Option<String>,       // Stylesheet of the outlets' shadow roots when `shadow_root` is enabled
+    pub default_overlay: Signal<Option<Element>>, // `default_overlay` from `PortalProviderProps`
+    pub placement_defaults: Signal<(AxisParam, AxisParam)>, // Vertical and horizontal defaults
+    pub motion: Signal<MotionDefaults>,       // Animation defaults from `PortalProviderProps`
+    pub prefers_reduced_motion: Signal<bool>, // Current value of the `prefers-reduced-motion` media query
+    pub announcement: Signal<String>,      // Latest message shown in the live region
+    pub pointer_target: Signal<Option<PortalId>>, // Portal whose content received the current pointerdown
+    pub reposition: Signal<u64>,           // Bumped by `PortalProviderHandle::reposition`
+    pub outlet_rect: Signal<Option<Rect>>, // Latest rectangle of the default outlet
+    pub layouts: CopyValue<HashMap<(PortalId, u64), LayoutCache>>, // Layouts of the mounted contents, for `debug_snapshot`
+    pub direct_positions: Option<DirectPositions>, // Position writers when `direct_positioning` is enabled
+    pub css_anchor: Signal<bool>,          // Whether `css_anchor_positioning` is enabled and supported
+    #[cfg(feature = "debug")]
+    pub debug_rects: Signal<crate::debug::DebugRects>, // Rectangles drawn by `PortalDebugLayer`
+    #[cfg(feature = "perf")]
+    pub perf: crate::perf::PerfCounters, // Pipeline counters read by `use_perf_counters`
+}
+
+// Settings from `PortalProviderProps` read by the portals and outlets, kept in a signal so changes re-render them
+#[derive(Clone, Copy, PartialEq)]
+struct ProviderSettings {
+    pub live_region: bool,    // Whether the live region is rendered
+    pub teleport: bool,       // Default of `Portal::teleport`
+    pub z_index_base: i64,
+    pub z_index_stride: i64,
+    pub bounds_padding: f64,  // Inset of the outlet rect used as placement bounds
+    pub shared_overlay: bool,
+    pub overlay_stacking: OverlayStacking,
+}
+
+impl PortalProviderContext {
+    fn handle(&self) -> PortalProviderHandle {
+        PortalProviderHandle {
+            entries: self.entries,
+            reposition: self.reposition,
+            outlet_rect: self.outlet_rect,
+            layouts: self.layouts,
+        }
+    }
 }
 
 // Context to share information for each portal
 #[derive(Clone)]
 struct PortalContext {
     pub id: PortalId, 
+    pub owner: u64,                        // Instance of the `Portal`, which `portal_key` alone does not tell apart
+    pub inline_open: Option<Signal<bool>>, // `open` of a portal rendered without a provider
 }
 
+// Outlet inside a native `<dialog>` (`PortalProps::native_dialog`), the default target of the portals below it
+#[derive(Clone)]
+struct DialogTarget(String);
+
 // Portal registration data
 #[derive(Clone, PartialEq)]
 struct PortalEntryData {
     pub id: PortalId,
+    pub owner: u64,                         // `PortalContext::owner` of the instance that registered the entry
     pub open: bool,
+    pub closing: bool,                      // Closed but still rendered during the exit transition
+    pub suppressed: bool,                   // Closed by the provider while `open` is still true
+    pub stack_key: i64,                     // Order within the layer, changed by `bring_to_front` / `send_to_back`
+    pub open_seq: u64,                      // When the portal was last opened; breaks ties so the last opened is on top
+    pub key: Option<String>,                // `portal_key` from `PortalProps`
+    pub target: Option<String>,             // `PortalTarget` name from `PortalProps`
+    pub teleport: Option<bool>,             // Per-portal override of the provider's `teleport`
+    pub light_dismiss: bool,                // Primary content shown as an `auto` popover
+    pub native_dialog: bool,                // Dialog contents shown in a modal `<dialog>`
+    pub mount_selector: Option<String>,     // DOM node the content is appended to
+    pub group: Option<String>,              // Singleton group name
+    pub remember_placement: bool,
+    pub initial_position: Option<Point2D<f64, Pixels>>, // Position of the primary content until it is measured
+    pub raise_on_interact: bool,
+    pub throttle: ThrottleMode,
+    pub track_continuously: bool,
+    pub window: PortalWindow,
     pub layer: i32,
     pub has_anchor_component: bool,         // Whether a `PortalAnchor` component exists in the portal's children 
+    pub frame_anchor: bool,                 // Whether that component is a `PortalFrameAnchor`
     pub measured_anchor_rect: Option<Rect>, // Rectangle of the `PortalAnchor` component
     pub custom_anchor_rect : Option<Rect>,  // Value of the `anchor_rect` property from `PortalProps`
+    pub dismiss: Option<DismissParam>,      // Participation in the dismissal stack
     pub vertical_param: AxisParam,
     pub horizontal_param: AxisParam,
-    pub content: Option<PortalContentProps>,
-    pub overlay: Option<PortalOverlayProps>,
+    pub contents: Vec<(u64, Registered<PortalContentProps>)>, // Registered `PortalContent`s with their ids, in mount order
+    pub overlay: Option<Registered<PortalOverlayProps>>,
+}
+
+// Props registered by a child component, shared by every copy of the entry instead of cloned with it
+// Registration only replaces the value when the props changed, so comparing pointers is enough
+struct Registered<T>(Rc<T>);
+
+impl<T> Registered<T> {
+    fn new(props: T) -> Self {
+        Self(Rc::new(props))
+    }
+}
+
+impl<T> Clone for Registered<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> PartialEq for Registered<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T> Deref for Registered<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl PortalEntryData {
+    fn is_open(&self) -> bool {
+        self.open && !self.suppressed
+    }
+
+    // First registered content, which decides the role reported for the portal and where its overlay renders
+    fn content(&self) -> Option<&PortalContentProps> {
+        self.contents.first().map(|(_, content)| &**content)
+    }
+
+    // Whether any content is a modal dialog
+    fn is_modal(&self) -> bool {
+        self.contents
+            .iter()
+            .any(|(_, content)| content.role == Some(ContentRole::Dialog))
+    }
+
+    // Name of the `PortalTarget` a content renders into
+    fn target_of<'a>(&'a self, content: &'a PortalContentProps) -> Option<&'a String> {
+        content.target.as_ref().or(self.target.as_ref())
+    }
+
+    // Name of the `PortalTarget` the overlay renders into
+    fn target(&self) -> Option<&String> {
+        match self.content() {
+            Some(content) => self.target_of(content),
+            None => self.target.as_ref(),
+        }
+    }
+
+    // Whether the contents are shown in their own native window rather than an outlet
+    fn is_detached(&self) -> bool {
+        cfg!(all(feature = "desktop", not(target_arch = "wasm32"))) && matches!(self.window, PortalWindow::Detached(_))
+    }
+
+    // Whether the primary content is an `auto` popover, which the browser dismisses by itself
+    fn is_light_dismissed(&self) -> bool {
+        self.light_dismiss && has_eval() && self.mount_selector.is_none() && !self.is_detached()
+    }
+
+    fn stack_position(&self) -> StackPosition {
+        StackPosition { layer: self.layer, stack_key: self.stack_key, open_seq: self.open_seq, id: self.id.0 }
+    }
+
+    fn dismissal(&self) -> Dismissal {
+        match (self.is_light_dismissed(), &self.dismiss) {
+            (true, _) => Dismissal::Native,
+            (false, Some(_)) => Dismissal::Requested,
+            (false, None) => Dismissal::Ignored,
+        }
+    }
+
+    // Whether CSS anchor positioning can place `content`: anchored to a `PortalAnchor` of this document, with
+    // overflow policies CSS can express
+    fn css_anchorable(&self, content: &PortalContentProps) -> bool {
+        let (param_v, param_h) = content.axis_params(self);
+        self.has_anchor_component
+            && !self.frame_anchor
+            && self.custom_anchor_rect.is_none()
+            && !self.is_detached()
+            && css_anchor::can_place(&param_v, &param_h)
+    }
+
+    // Whether CSS anchor positioning places every content, so the anchor needs no measuring
+    fn css_anchored(&self) -> bool {
+        !self.contents.is_empty() && self.contents.iter().all(|(_, content)| self.css_anchorable(content))
+    }
+
+    // Whether anything of this portal renders into `target`
+    fn renders_in(&self, target: Option<&String>) -> bool {
+        if self.is_detached() {
+            return false;
+        }
+        self.target() == target || self.contents.iter().any(|(_, content)| self.target_of(content) == target)
+    }
+}
+
+// Dismissal settings of a portal
+#[derive(Clone, PartialEq)]
+struct DismissParam {
+    pub on_dismiss: Callback<DismissReason>,
+    pub on_escape: bool,
+    pub on_outside_click: bool,
+}
+
+// Open portal ids from bottom to top
+fn open_stack_order(entries: &HashMap<PortalId, PortalEntryData>) -> Vec<PortalId> {
+    sorted_ids(entries, PortalEntryData::is_open)
+}
+
+// Rendered portal ids (open, or closing with an exit transition) from bottom to top
+fn render_order(entries: &HashMap<PortalId, PortalEntryData>) -> Vec<PortalId> {
+    sorted_ids(entries, |data| data.is_open() || data.closing)
+}
+
+fn sorted_ids(
+    entries: &HashMap<PortalId, PortalEntryData>,
+    pred: impl Fn(&PortalEntryData) -> bool,
+) -> Vec<PortalId> {
+    stack_order(entries.values().filter(|data| pred(data)), |data| data.stack_position())
+        .into_iter()
+        .map(|data| data.id)
+        .collect::<Vec<_>>()
+}
+
+// Hides the matching open portals until their `open` prop is turned off, and notifies `on_dismiss` so app state can follow
+fn close_entries(
+    mut entries: Signal<HashMap<PortalId, PortalEntryData>>,
+    pred: impl Fn(&PortalEntryData) -> bool,
+    reason: DismissReason,
+) {
+    let callbacks = {
+        let mut entries = entries.write();
+        entries
+            .values_mut()
+            .filter(|data| data.is_open() && pred(data))
+            .filter_map(|data| {
+                data.suppressed = true;
+                data.dismiss.as_ref().map(|d| d.on_dismiss)
+            })
+            .collect::<Vec<_>>()
+    };
+    for on_dismiss in callbacks {
+        on_dismiss.call(reason);
+    }
+}
+
+// Touch that dismisses only if it ends as a tap. Under touch, `pointerdown` also starts scrolls (including the
+// flick of a momentum scroll), so outside dismissal waits for a `pointerup` close to where the touch started
+#[derive(Clone, Copy, PartialEq)]
+struct PendingTap {
+    pointer_id: i32,
+    start: ClientPoint,
+    inside: Option<PortalId>, // Portal whose content received the `pointerdown`
 }
 
-// Struct that manages placement parameters
-#[derive(Clone, PartialEq)]
-struct AxisParam {
-    pub alignment: Alignment,
-    pub spread: Spread,
-    pub offset: f64,
-    pub overflow_policy: OverflowPolicy,
+// How far a touch may move and still be a tap, in CSS pixels
+const TAP_SLOP: f64 = 10.0;
+
+impl PendingTap {
+    // `None` for mouse and pen, which dismiss on `pointerdown` as before
+    fn start(evt: &PointerEvent, inside: Option<PortalId>) -> Option<Self> {
+        (evt.pointer_type() == "touch").then(|| PendingTap {
+            pointer_id: evt.pointer_id(),
+            start: evt.client_coordinates(),
+            inside,
+        })
+    }
+
+    fn is_tap(&self, evt: &PointerEvent) -> bool {
+        evt.pointer_id() == self.pointer_id && (evt.client_coordinates() - self.start).length() <= TAP_SLOP
+    }
+}
+
+// Sends a dismissal request to the topmost dismissible portal only
+// `inside` is the portal whose content received the pointer, which is not an outside click for that portal
+fn dismiss_topmost(
+    entries: Signal<HashMap<PortalId, PortalEntryData>>,
+    reason: DismissReason,
+    inside: Option<PortalId>,
+) {
+    // A light-dismissed popover on top is closed by the browser, which reports it through its `toggle` event
+    let target = {
+        let entries = entries.peek();
+        let stacked = open_stack_order(&entries).into_iter().filter_map(|id| entries.get(&id));
+        dismissal_target(stacked, |data| data.dismissal()).and_then(|data| data.dismiss.clone().map(|d| (data.id, d)))
+    };
+
+    let Some((id, dismiss)) = target else {
+        return;
+    };
+    let enabled = match reason {
+        DismissReason::EscapeKey => dismiss.on_escape,
+        DismissReason::OutsideClick => dismiss.on_outside_click && inside != Some(id),
+        DismissReason::Programmatic | DismissReason::GroupSwitch | DismissReason::Timeout | DismissReason::LightDismiss | DismissReason::Swipe => true,
+    };
+    if enabled {
+        dismiss.on_dismiss.call(reason);
+    }
 }
 
 // ------ Internal Components ---------------------------------------------------------------------------------------------------------------
 
 #[derive(Props, Clone, PartialEq)]
-struct PortalOutletProps {}
+struct PortalOutletProps {
+    #[props(optional)]
+    pub target: Option<String>,
+    pub style: String,
+    #[props(extends=GlobalAttributes)]
+    attributes: Vec<Attribute>,
+}
 
 // Component that renders registered portal content and overlay in layer order
+// Only the portals routed to `target` are rendered
 #[component]
 fn PortalOutlet(props: PortalOutletProps) -> Element {
-    let _ = &props; // Dummy to avoid warnings
-
     let provider_ctx = use_context::<PortalProviderContext>();
     let mut rect = use_signal(|| None);
+    let mut outlet_rect = provider_ctx.outlet_rect;
+
+    let mut target = use_signal(|| props.target.clone());
+    if *target.peek() != props.target {
+        target.set(props.target.clone());
+    }
+    // Only re-render when the set, order, or overlays of the routed portals change, not on every entry update
+    let layout = {
+        let provider_ctx = provider_ctx.clone();
+        use_memo(move || outlet_layout(&provider_ctx, target.read().as_ref()))
+    };
+    let OutletLayout { sorted_ids, overlay_id, shared_overlay_pos, raised_overlay } = layout();
+
+    #[cfg(feature = "debug")]
+    if let Some(r) = rect() {
+        crate::debug::record_outlet(provider_ctx.debug_rects, props.target.clone(), r, placement_bounds(r, provider_ctx.settings.read().bounds_padding));
+    }
+
+    let ProviderSettings { z_index_base, z_index_stride, .. } = *provider_ctx.settings.read();
+    let z_index_of = |i: usize| z_index_base + i as i64 * z_index_stride;
+
+    let portals = match rect() {
+        None => rsx! {},
+        Some(outlet_rect) => rsx! {
+            for (i, id) in sorted_ids.iter().enumerate() {
+                PortalEntry {
+                    key : "{id}",
+                    id : *id,
+                    target : props.target.clone(),
+                    z_index : z_index_of(i) + 1,
+                    outlet_rect : outlet_rect,
+                }
+
+                if overlay_id == Some(*id) && raised_overlay.is_none() {
+                    PortalOverlayEntry {
+                        id : *id,
+                        z_index : z_index_of(i),
+                    }
+                }
+            }
+
+            if let Some((id, i)) = raised_overlay {
+                PortalOverlayEntry {
+                    id : id,
+                    z_index : z_index_of(i),
+                }
+            }
+
+            // Kept in a fixed slot so the scrim element survives changes of the topmost modal
+            if let Some(i) = shared_overlay_pos {
+                PortalOverlayEntry {
+                    id : sorted_ids[i],
+                    z_index : z_index_of(i),
+                }
+            }
+        },
+    };
+    let portals = match &provider_ctx.shadow_root {
+        Some(style) => rsx! {
+            ShadowOutlet { style : style.clone(), {portals} }
+        },
+        None => portals,
+    };
+
+    rsx! {
+        RectObserver {
+            on_rect_changed : move |r : Rect| {
+                #[cfg(feature = "perf")]
+                provider_ctx.perf.count_outlet_report();
+                if target.peek().is_none() {
+                    outlet_rect.set(Some(r));
+                }
+                rect.set(Some(r))
+            },
+            attributes : props.attributes,
+            style : props.style,
+            measure_key : props.target.as_ref().map_or_else(|| "outlet".to_string(), |target| format!("outlet:{target}")),
+            {portals}
+        }
+    }
+}
+
+#[derive(Props, Clone, PartialEq)]
+struct ShadowOutletProps {
+    pub style: String,
+    children: Element,
+}
+
+// Container of an outlet's portals, moved into an open shadow root attached to the outlet once mounted
+// (`PortalProviderProps::shadow_root`). Being the outlet's only child, it is the only node moved: the renderer keeps
+// inserting and removing the portals inside it
+#[component]
+fn ShadowOutlet(props: ShadowOutletProps) -> Element {
+    let key = use_hook(|| alloc_component_id("dioxus-portal-shadow-outlet-"));
+    let onmounted = {
+        let key = key.clone();
+        move |_| {
+            document::eval(&js_code_of_attach_shadow_outlet(&key, &props.style));
+        }
+    };
+    rsx! {
+        div {
+            id : key,
+            style : "display: contents;",
+            onmounted : onmounted,
+            {props.children}
+        }
+    }
+}
 
+// What an outlet renders: its portals in stacking order and where overlays go
+#[derive(Clone, PartialEq)]
+struct OutletLayout {
+    sorted_ids: Vec<PortalId>,
+    overlay_id: Option<PortalId>,               // Portal whose overlay is rendered in its own slot
+    shared_overlay_pos: Option<usize>,          // Slot of the shared scrim
+    raised_overlay: Option<(PortalId, usize)>,  // Overlay moved under the topmost modal, and its slot
+}
+
+fn outlet_layout(provider_ctx: &PortalProviderContext, target: Option<&String>) -> OutletLayout {
     let sorted_ids = {
         let entries = provider_ctx.entries.read();
-        let mut ids = entries
-            .values()
-            .filter(|data| data.open)
-            .map(|data| (data.id, data.layer))
-            .collect::<Vec<_>>();
-        ids.sort_by_key(|(_, layer)| *layer);
-        ids.into_iter().map(|(id, _)| id).collect::<Vec<_>>()
+        render_order(&entries)
+            .into_iter()
+            .filter(|id| entries.get(id).unwrap().renders_in(target))
+            .collect::<Vec<_>>()
     };
 
+    let ProviderSettings { shared_overlay, overlay_stacking, .. } = *provider_ctx.settings.read();
+
     let overlay_id = {
         let entries = provider_ctx.entries.read();
+        let has_default_overlay = provider_ctx.default_overlay.read().is_some();
         sorted_ids
             .iter()
-            .rfind(|id| entries.get(id).unwrap().overlay.is_some())
+            .rfind(|id| {
+                let data = entries.get(id).unwrap();
+                let has_overlay = data.overlay.is_some() || (has_default_overlay && data.is_modal());
+                // In shared mode, modal portals are covered by the shared scrim
+                has_overlay && !(shared_overlay && data.is_modal()) && data.target() == target
+            })
             .map(|id| *id)
     };
 
-    let outlet_measured = rect().is_some();
+    // Position of the modal the shared scrim sits under: the topmost open one, or the one fading out
+    let shared_overlay_pos = if shared_overlay {
+        let entries = provider_ctx.entries.read();
+        let modal_pos = |open: bool| {
+            sorted_ids.iter().rposition(|id| {
+                let data = entries.get(id).unwrap();
+                data.is_modal() && data.is_open() == open && data.target() == target
+            })
+        };
+        modal_pos(true).or_else(|| modal_pos(false))
+    } else {
+        None
+    };
 
-    rsx! {
-        RectObserver {
-            on_rect_changed : move |r : Rect| { rect.set(Some(r)) },
-            style : "position: absolute; top: 0; left: 0; width: 100%; height: 100%; z-index: 1; pointer-events: none;",
+    // Overlay stacked under the topmost open modal rather than in its owner's slot. Rendered in a fixed slot so
+    // the element survives when it moves between modals
+    let raised_overlay = match (overlay_stacking, overlay_id) {
+        (OverlayStacking::TopmostModal, Some(overlay_id)) => {
+            let entries = provider_ctx.entries.read();
+            let owner_pos = sorted_ids.iter().position(|id| *id == overlay_id).unwrap();
+            let modal_pos = sorted_ids.iter().rposition(|id| {
+                let data = entries.get(id).unwrap();
+                data.is_modal() && data.is_open()
+            });
+            Some((overlay_id, modal_pos.map_or(owner_pos, |pos| pos.max(owner_pos))))
+        }
+        _ => None,
+    };
 
-            if outlet_measured {
-                for (i, id) in sorted_ids.iter().enumerate() {
-                    PortalEntry {
-                        id : *id,
-                        z_index : i * 2 + 1,
-                        outlet_rect : rect().unwrap(),
-                    }
+    OutletLayout { sorted_ids, overlay_id, shared_overlay_pos, raised_overlay }
+}
 
-                    if overlay_id == Some(*id) {
-                        PortalOverlayEntry {
-                            id : *id,
-                            z_index : i * 2,
-                        }
-                    }
-                }
-            }
-        }
-    }
+// Registration data of portal `id`, behind a memo so the component only re-renders when this portal changes.
+// Callers are keyed by `id`, which therefore never changes
+fn use_entry_data(entries: Signal<HashMap<PortalId, PortalEntryData>>, id: PortalId) -> Memo<Option<PortalEntryData>> {
+    use_memo(move || entries.read().get(&id).cloned())
 }
 
 #[derive(Props, Clone, PartialEq)]
 struct PortalEntryProps {
     pub id: PortalId,
-    pub z_index: usize,
+    pub target: Option<String>,
+    pub z_index: i64,
     pub outlet_rect: Rect,
 }
 
-// Component that renders a single registered portal content
+// Component that renders the contents of a single registered portal routed to `target`
 #[component]
 fn PortalEntry(props: PortalEntryProps) -> Element {
     let provider_ctx = use_context::<PortalProviderContext>();
+    let id = props.id;
+    #[cfg(feature = "perf")]
+    provider_ctx.perf.count(id, |counters| counters.renders += 1);
+    let entry = use_entry_data(provider_ctx.entries, id);
+    let entry = entry.read();
+    let Some(data) = entry.as_ref() else {
+        log_missing_entry(id, "PortalEntry render");
+        return rsx! {};
+    };
+
+    let use_custom_anchor = data.custom_anchor_rect.is_some();
+    // Contents placed by CSS do not wait for the anchor, which is then never measured
+    let css_anchored = provider_ctx.css_anchor.cloned() && data.css_anchored();
+    let anchor_preparing =
+        !use_custom_anchor && !css_anchored && data.has_anchor_component && data.measured_anchor_rect.is_none();
+
+    if anchor_preparing {
+        return rsx! {};
+    }
+
+    let content_ids = data
+        .contents
+        .iter()
+        .filter(|(_, content)| data.target_of(content) == props.target.as_ref())
+        .map(|(content_id, _)| *content_id)
+        .collect::<Vec<_>>();
+
+    rsx! {
+        for content_id in content_ids {
+            PortalContentEntry {
+                key : "{content_id}",
+                id : id,
+                content_id : content_id,
+                z_index : props.z_index,
+                outlet_rect : props.outlet_rect,
+            }
+        }
+    }
+}
+
+#[derive(Props, Clone, PartialEq)]
+struct PortalContentEntryProps {
+    pub id: PortalId,
+    pub content_id: u64,
+    pub z_index: i64,
+    pub outlet_rect: Rect,
+}
+
+// Component that renders one positioned content of a portal
+#[component]
+fn PortalContentEntry(props: PortalContentEntryProps) -> Element {
+    let mut provider_ctx = use_context::<PortalProviderContext>();
     let mut size = use_signal(|| None);
 
+    #[cfg(feature = "perf")]
+    provider_ctx.perf.count(props.id, |counters| counters.renders += 1);
+    #[cfg(feature = "perf")]
+    let perf = provider_ctx.perf.clone();
     let on_rect_changed = move |r: Rect| {
+        #[cfg(feature = "perf")]
+        perf.count(props.id, |counters| counters.content_reports += 1);
         let current = *size.read();
         let new = Some(r.size);
         if current != new {
@@ -410,73 +2385,533 @@ fn PortalEntry(props: PortalEntryProps) -> Element {
     };
 
     let id = props.id;
+    let content_id = props.content_id;
     let z_index = props.z_index;
-    let entries = provider_ctx.entries.read();
-    let data = entries.get(&id).unwrap();
-
-    let use_custom_anchor = data.custom_anchor_rect.is_some();
-    let anchor_preparing = !use_custom_anchor && data.has_anchor_component && data.measured_anchor_rect.is_none();
-    let has_content = data.content.is_some();
+    let layout_cache = use_hook(|| {
+        let layout_cache = LayoutCache::default();
+        provider_ctx.layouts.write().insert((id, content_id), layout_cache.clone());
+        layout_cache
+    });
+    {
+        let mut layouts = provider_ctx.layouts;
+        use_drop(move || {
+            layouts.write().remove(&(id, content_id));
+        });
+    }
 
-    if anchor_preparing || !has_content {
-        return rsx! {};
+    #[cfg(feature = "debug")]
+    use_drop(move || crate::debug::record_content(provider_ctx.debug_rects, (id, content_id), None));
+    {
+        let direct = provider_ctx.direct_positions.clone();
+        use_drop(move || {
+            if let Some(direct) = direct {
+                direct.unregister(id, content_id);
+            }
+        });
     }
+    let entry = use_entry_data(provider_ctx.entries, id);
+    let entry = entry.read();
+    let Some(data) = entry.as_ref() else {
+        log_missing_entry(id, "PortalContentEntry render");
+        return rsx! {};
+    };
+    let Some(content_props) = data
+        .contents
+        .iter()
+        .find(|(other, _)| *other == content_id)
+        .map(|(_, content)| content)
+    else {
+        return rsx! {};
+    };
 
-    let anchor_rect = if use_custom_anchor {
-        data.custom_anchor_rect.clone()
+    let anchor_rect = anchor_rect_of(&provider_ctx, data);
+
+    // Placement remembered from the previous open, used until the content is measured. Only the first content
+    // of a portal is remembered
+    let is_primary = data.contents.first().is_some_and(|(first, _)| *first == content_id);
+    let remember_placement = data.remember_placement && is_primary;
+    let memory = if remember_placement {
+        provider_ctx.placements.peek().get(&id).copied()
     } else {
-        data.measured_anchor_rect.clone()
+        None
+    };
+    let measured = *size.read();
+    let mount = match (&data.mount_selector, data.teleport.unwrap_or(provider_ctx.settings.read().teleport)) {
+        // Moving the wrapper out of the outlet needs eval
+        _ if !has_eval() => ContentMount::Outlet,
+        _ if data.native_dialog && content_props.role == Some(ContentRole::Dialog) => ContentMount::Dialog,
+        (Some(selector), _) => ContentMount::Selector(selector.clone()),
+        (None, _) if data.light_dismiss => ContentMount::TopLayer,
+        (None, true) => ContentMount::TopLayer,
+        (None, false) => ContentMount::Outlet,
+    };
+    let reset_style = if mount == ContentMount::TopLayer { TOP_LAYER_RESET_STYLE } else { "" };
+
+    let raise_on_interact = data.raise_on_interact;
+    let handle = provider_ctx.handle();
+    let css_anchored = provider_ctx.css_anchor.cloned() && data.css_anchorable(content_props);
+    // Under CSS anchor positioning the browser resolves the anchor width itself
+    let anchor_width_style = match anchor_rect {
+        _ if !content_props.match_anchor_width => String::new(),
+        _ if css_anchored => "min-width: anchor-size(width); ".to_string(),
+        Some(anchor) => format!("min-width: {}px; ", anchor.width()),
+        None => String::new(),
+    };
+    // Side of the anchor the content ended up on, and the style variables that follow the anchor
+    let mut side = None;
+    let mut anchored_style = String::new();
+    // Room between the anchor and the bounds on the content's side, for contents that size themselves to it
+    let available_style = |size: Size2D<f64, Pixels>| {
+        format!(" --dioxus-portal-available-width: {}px; --dioxus-portal-available-height: {}px;", size.width, size.height)
     };
+    let mut available = String::new();
 
-    let content_props = data.content.as_ref().unwrap();
-    let content_style = match *size.read() {
-        None => format!(
-            "{} width: fit-content; height: fit-content; position: absolute; z-index: {}; opacity: 0; pointer-events: none;",
-            content_props.style, z_index
-        ),
+    let content_style = match measured.or(memory.map(|m| m.size)) {
+        // Placed by the browser against the anchor, without measuring either
+        _ if css_anchored => {
+            if let Some(direct) = &provider_ctx.direct_positions {
+                direct.unregister(id, content_id);
+            }
+            let (param_v, param_h) = content_props.axis_params(data);
+            side = side_of(&param_v, &param_h, param_v.alignment, param_h.alignment);
+            format!(
+                "{reset_style} pointer-events: auto; opacity: 1; {} width: fit-content; height: fit-content; {} z-index: {};",
+                content_props.style,
+                css_anchor::placement_style(id, &param_v, &param_h),
+                z_index
+            )
+        }
+        None => {
+            if let Some(direct) = &provider_ctx.direct_positions {
+                direct.unregister(id, content_id);
+            }
+            if let Some(anchor) = anchor_rect {
+                let (param_v, param_h) = content_props.axis_params(data);
+                let bounds = placement_bounds(props.outlet_rect, provider_ctx.settings.read().bounds_padding);
+                available = available_style(available_size(&param_v, &param_h, None, anchor, bounds));
+            }
+            // Hinted first pass: placed where the content is expected, still hidden until measured
+            let hint = data.initial_position.filter(|_| is_primary).map(|pos| {
+                if mount.is_fixed() {
+                    format!("position: fixed; top: {}px; left: {}px;", pos.y, pos.x)
+                } else {
+                    format!("position: absolute; top: {}px; left: {}px;", pos.y - props.outlet_rect.origin.y, pos.x - props.outlet_rect.origin.x)
+                }
+            });
+            format!(
+            "{}{} width: fit-content; height: fit-content; {} z-index: {}; opacity: 0; pointer-events: none;",
+                reset_style,
+                content_props.style,
+                hint.as_deref().unwrap_or("position: absolute;"),
+                z_index
+            )
+        }
         Some(size) => {
-            let pos =
-                calc_content_position(data, size, anchor_rect, props.outlet_rect);
+            let (param_v, param_h) = content_props.axis_params(data);
+            let bounds = placement_bounds(props.outlet_rect, provider_ctx.settings.read().bounds_padding);
+            // The remembered alignments only stand in for the unmeasured first pass; once measured, the content is
+            // placed from its configured alignments again, so a flip resolves back when the anchor moves
+            let layout_memory = memory.filter(|_| measured.is_none());
+            let inputs = LayoutInputs { param_v, param_h, size, anchor: anchor_rect, bounds, memory: layout_memory };
+            #[cfg(feature = "perf")]
+            provider_ctx.perf.count_layout(id, layout_cache.is_cached(&inputs));
+            let (pos, resolved) = layout_cache.position(inputs);
+
+            if remember_placement && measured.is_some() && memory != Some(resolved) {
+                provider_ctx.placements.write().insert(id, resolved);
+            }
+
+            side = side_of(&param_v, &param_h, resolved.vertical, resolved.horizontal);
+            if let Some(anchor) = anchor_rect {
+                let properties = anchored_properties(&param_v, &param_h, resolved, anchor, bounds, Rect::new(pos, size));
+                anchored_style = properties.iter().map(|(name, value)| format!(" {name}: {value}px;")).collect();
+            }
+
+            // Keep what this render placed the content with, so anchor moves can be applied without one
+            if let Some(direct) = &provider_ctx.direct_positions {
+                if data.custom_anchor_rect.is_some() {
+                    direct.unregister(id, content_id);
+                } else {
+                    let layout_cache = layout_cache.clone();
+                    let origin = if mount.is_fixed() { Point2D::zero() } else { props.outlet_rect.origin };
+                    #[cfg(feature = "debug")]
+                    let debug_rects = provider_ctx.debug_rects;
+                    #[cfg(feature = "perf")]
+                    let perf = provider_ctx.perf.clone();
+                    direct.register(id, content_id, format!("{id}-{content_id}"), move |anchor| {
+                        let inputs =
+                            LayoutInputs { param_v, param_h, size, anchor: Some(anchor), bounds, memory: layout_memory };
+                        #[cfg(feature = "perf")]
+                        perf.count_layout(id, layout_cache.is_cached(&inputs));
+                        let (pos, moved) = layout_cache.position(inputs);
+                        // A change of side is left to a render, which also updates `data-side`
+                        if moved.vertical != resolved.vertical || moved.horizontal != resolved.horizontal {
+                            return None;
+                        }
+                        #[cfg(feature = "debug")]
+                        crate::debug::record_content(debug_rects, (id, content_id), Some(Rect::new(pos, size)));
+                        Some(Placed {
+                            top: pos.y - origin.y,
+                            left: pos.x - origin.x,
+                            properties: anchored_properties(&param_v, &param_h, moved, anchor, bounds, Rect::new(pos, size)),
+                        })
+                    });
+                }
+            }
 
-            // Since `calc_content_position` uses the viewport as the reference, convert to a position relative to the outlet
-            let top = pos.y - props.outlet_rect.origin.y;
-            let left = pos.x - props.outlet_rect.origin.x;
+            #[cfg(feature = "debug")]
+            crate::debug::record_content(provider_ctx.debug_rects, (id, content_id), Some(Rect::new(pos, size)));
+
+            if mount.is_fixed() {
+                // Outside the outlet the content is fixed to the viewport, which is the reference of `calc_content_position`
+                format!("{reset_style} pointer-events: auto; opacity: 1; {} width: fit-content; height: fit-content; position: fixed; top: {}px; left: {}px; z-index: {};", content_props.style, pos.y, pos.x, z_index)
+            } else {
+                // Since `calc_content_position` uses the viewport as the reference, convert to a position relative to the outlet
+                let top = pos.y - props.outlet_rect.origin.y;
+                let left = pos.x - props.outlet_rect.origin.x;
 
-            format!("pointer-events: auto; opacity: 1; {} width: fit-content; height: fit-content; position: absolute; top: {}px; left: {}px; z-index: {};", content_props.style, top, left, z_index)
+                format!("pointer-events: auto; opacity: 1; {} width: fit-content; height: fit-content; position: absolute; top: {}px; left: {}px; z-index: {};", content_props.style, top, left, z_index)
+            }
         }
     };
+    // The content fades together with the overlay. A modal that scrolls (e.g. a sheet with `overflow: auto`)
+    // does not chain its scroll to the page under it, unless its style says otherwise
+    let content_style = {
+        let closing_style = if data.closing { " pointer-events: none;" } else { "" };
+        let contain_style = if data.is_modal() { "overscroll-behavior: contain; " } else { "" };
+        format!("{contain_style}{anchor_width_style}{content_style} {}{closing_style}{anchored_style}{available}", transition_style(data, &transition_of(&provider_ctx, data)))
+    };
 
-    rsx! {
+    let mut attributes = content_props.attributes.clone();
+    if let Some(role) = content_props.role {
+        attributes.extend(role.attributes());
+    }
+    attributes.push(Attribute::new(
+        "data-state",
+        if data.closing { "closed" } else { "open" },
+        None,
+        false,
+    ));
+    if let Some(side) = side {
+        attributes.push(Attribute::new("data-side", side, None, false));
+    }
+    // Only one content can be light-dismissed: showing another `auto` popover that is not nested in it closes it
+    let light_dismiss = mount == ContentMount::TopLayer && data.light_dismiss && is_primary;
+    if mount == ContentMount::TopLayer {
+        attributes.push(Attribute::new("popover", if light_dismiss { "auto" } else { "manual" }, None, false));
+    }
+    let teleport_key = format!("{id}-{content_id}");
+    if mount != ContentMount::Outlet {
+        attributes.push(Attribute::new(TELEPORT_ATTRIBUTE, teleport_key.clone(), None, false));
+    }
+    if provider_ctx.direct_positions.is_some() {
+        attributes.push(Attribute::new(POSITION_ATTRIBUTE, teleport_key.clone(), None, false));
+    }
+
+    // Move the wrapper out of the outlet once the content is in the DOM
+    let entries = provider_ctx.entries;
+    let in_dialog = mount == ContentMount::Dialog;
+    let onmounted = move |_| match &mount {
+        ContentMount::Outlet => {}
+        ContentMount::TopLayer => {
+            let mut eval = document::eval(&js_code_of_show_in_top_layer(&teleport_key, id, light_dismiss));
+            if light_dismiss {
+                spawn(async move {
+                    if eval.recv::<bool>().await.is_ok() {
+                        close_entries(entries, |data| data.id == id, DismissReason::LightDismiss);
+                    }
+                });
+            }
+        }
+        ContentMount::Selector(selector) => {
+            document::eval(&js_code_of_append_to_selector(&teleport_key, selector));
+        }
+        ContentMount::Dialog => {
+            document::eval(&js_code_of_show_modal_dialog(&teleport_key));
+        }
+    };
+
+    let content = rsx! {
         RectObserver {
             on_rect_changed : on_rect_changed,
-            attributes : content_props.attributes.clone(),
+            attributes : attributes,
             style : content_style,
-            {content_props.children.clone()}
+            throttle : data.throttle,
+            paused : css_anchored,
+            measure_key : format!("content:{id}:{content_id}"),
+            div {
+                style : "display: contents;",
+                onmounted : onmounted,
+                onpointerdown : move |_| {
+                    provider_ctx.pointer_target.set(Some(id));
+                    if raise_on_interact {
+                        handle.bring_to_front(id);
+                    }
+                },
+                onpointerenter : move |_| { provider_ctx.hovered.write().insert(id); },
+                onpointerleave : move |_| { provider_ctx.hovered.write().remove(&id); },
+                {content_props.children.clone()}
+            }
+        }
+    };
+    if in_dialog {
+        rsx! {
+            NativeDialog {
+                target : format!("dioxus-portal-dialog-{id}-{content_id}"),
+                z_index : z_index,
+                {content}
+            }
+        }
+    } else {
+        content
+    }
+}
+
+#[derive(Props, Clone, PartialEq)]
+struct NativeDialogProps {
+    pub target: String,
+    pub z_index: i64,
+    children: Element,
+}
+
+// Modal `<dialog>` around a content (`PortalProps::native_dialog`). `showModal()` makes everything outside the dialog
+// inert, so it holds its own outlet, above the content, and the portals opened from the content render there
+// The dialog covers the viewport, so clicks beside the content land on it and count as outside clicks
+#[component]
+fn NativeDialog(props: NativeDialogProps) -> Element {
+    use_context_provider(|| DialogTarget(props.target.clone()));
+    rsx! {
+        dialog {
+            style : DIALOG_STYLE,
+            {props.children}
+            PortalOutlet {
+                target : props.target,
+                style : format!("position: fixed; inset: 0; pointer-events: none; z-index: {};", props.z_index + 1),
+            }
         }
     }
 }
 
+// Where the positioned wrapper of a portal is placed in the DOM
+#[derive(Clone, PartialEq)]
+enum ContentMount {
+    Outlet,
+    TopLayer,
+    Selector(String),
+    Dialog, // Inside a modal `<dialog>` rendered in place of the wrapper
+}
+
+impl ContentMount {
+    // Whether the wrapper is fixed to the viewport rather than placed within the outlet
+    fn is_fixed(&self) -> bool {
+        *self != ContentMount::Outlet
+    }
+}
+
+// Resets the UA styles of `[popover]` so the wrapper is laid out like the outlet-rendered one
+const TOP_LAYER_RESET_STYLE: &str =
+    "inset: auto; margin: 0; padding: 0; border: none; background: transparent; color: inherit; overflow: visible;";
+// Resets the UA styles of `dialog` and stretches it over the viewport, where the wrapper is fixed as in the top layer
+const DIALOG_STYLE: &str = "position: fixed; inset: 0; width: 100vw; height: 100vh; max-width: none; max-height: none; margin: 0; padding: 0; border: none; background: transparent; color: inherit; overflow: visible;";
+const TELEPORT_ATTRIBUTE: &str = "data-dioxus-portal-teleport";
+const ANCHOR_ATTRIBUTE: &str = "data-dioxus-portal-anchor";
+const SHADOW_HOST_ATTRIBUTE: &str = "data-dioxus-portal-shadow-host";
+// Bubbling events crossing shadow boundaries, whose target the shadow outlet restores
+const COMPOSED_EVENTS: &str = r#""click", "dblclick", "contextmenu", "auxclick", "pointerdown", "pointerup", "pointermove", "pointerover", "pointerout", "pointercancel", "mousedown", "mouseup", "mousemove", "mouseover", "mouseout", "wheel", "keydown", "keyup", "keypress", "input", "beforeinput", "focusin", "focusout", "touchstart", "touchend", "touchmove", "touchcancel", "dragstart", "drag", "dragend", "dragenter", "dragover", "dragleave", "drop", "copy", "cut", "paste", "compositionstart", "compositionupdate", "compositionend""#;
+// Bubbling events stopping at shadow boundaries, which the shadow outlet dispatches again from the outlet
+const UNCOMPOSED_EVENTS: &str = r#""change", "submit", "reset", "select""#;
+
+// Generates JS code to show a teleported wrapper in the top layer
+// Nodes are not moved to `document.body` since the renderer delegates events to its root element
+// The portal's `PortalAnchor` is passed as the popover's source, so a popover anchored inside another one counts
+// as nested in it and does not light-dismiss it. With `light_dismiss`, sends once the browser hides the wrapper
+fn js_code_of_show_in_top_layer(key: &str, id: PortalId, light_dismiss: bool) -> String {
+    let find = js_deep_query(&format!("[{TELEPORT_ATTRIBUTE}=\"{key}\"]"));
+    format!(
+        r#"
+    try {{
+      const el = {find};
+      if (el && typeof el.showPopover === "function" && !el.matches(":popover-open")) {{
+        if ({light_dismiss}) {{
+          el.addEventListener("toggle", (e) => {{
+            if (e.newState === "closed") {{
+              dioxus.send(true);
+            }}
+          }});
+        }}
+        const source = document.querySelector(`[{ANCHOR_ATTRIBUTE}="{id}"]`);
+        el.showPopover(source ? {{ source }} : undefined);
+      }}
+    }} catch (e) {{
+      console.error(`teleport error: ${{e}}`);
+    }}
+"#
+    )
+}
+
+// Generates JS code to open the `<dialog>` around a wrapper as a modal
+// `cancel` (Escape) is prevented, so the dialog only closes when the portal does and `on_dismiss` decides
+fn js_code_of_show_modal_dialog(key: &str) -> String {
+    let find = js_deep_query(&format!("[{TELEPORT_ATTRIBUTE}=\"{key}\"]"));
+    format!(
+        r#"
+    try {{
+      const el = {find};
+      const dialog = el && el.closest("dialog");
+      if (dialog && typeof dialog.showModal === "function" && !dialog.open) {{
+        dialog.addEventListener("cancel", (e) => e.preventDefault());
+        dialog.showModal();
+      }}
+    }} catch (e) {{
+      console.error(`dialog error: ${{e}}`);
+    }}
+"#
+    )
+}
+
+// Generates JS code to append a teleported wrapper to the node matching `selector`
+// The renderer removes the wrapper from there when the portal closes
+fn js_code_of_append_to_selector(key: &str, selector: &str) -> String {
+    let selector = selector.replace('\\', "\\\\").replace('`', "\\`");
+    let find = js_deep_query(&format!("[{TELEPORT_ATTRIBUTE}=\"{key}\"]"));
+    format!(
+        r#"
+    try {{
+      const el = {find};
+      const container = document.querySelector(`{selector}`);
+      if (!container) {{
+        console.error(`teleport error: no element matches {selector}`);
+        return;
+      }}
+      if (el && el.parentNode !== container) {{
+        container.appendChild(el);
+      }}
+    }} catch (e) {{
+      console.error(`teleport error: ${{e}}`);
+    }}
+"#
+    )
+}
+
+// Generates JS code to attach an open shadow root to the outlet of the container `id` and move the container into it. The renderer delegates events to its root element, where events from inside a shadow root
+// arrive retargeted to the outlet, so the root gives them back their original target; the bubbling events that do
+// not cross shadow boundaries are dispatched again from the outlet
+fn js_code_of_attach_shadow_outlet(id: &str, style: &str) -> String {
+    let style = style.replace('\\', "\\\\").replace('`', "\\`").replace('$', "\\$");
+    let find = js_deep_query(&format!("#{id}"));
+    format!(
+        r#"
+    try {{
+      const container = {find};
+      const outlet = container && container.parentElement;
+      if (outlet && !outlet.shadowRoot) {{
+        const root = outlet.attachShadow({{ mode: "open" }});
+        const style = document.createElement("style");
+        style.textContent = `{style}`;
+        root.appendChild(style);
+        root.appendChild(container);
+        outlet.setAttribute("{SHADOW_HOST_ATTRIBUTE}", "");
+        const keepTarget = (e, target) => Object.defineProperty(e, "target", {{ configurable: true, get: () => target }});
+        for (const type of [{COMPOSED_EVENTS}]) {{
+          root.addEventListener(type, (e) => keepTarget(e, e.composedPath()[0]), {{ capture: true }});
+        }}
+        for (const type of [{UNCOMPOSED_EVENTS}]) {{
+          root.addEventListener(type, (e) => {{
+            if (!e.bubbles || e.cancelBubble) return;
+            const copy = new Event(e.type, {{ bubbles: true, cancelable: e.cancelable }});
+            keepTarget(copy, e.target);
+            outlet.dispatchEvent(copy);
+            if (copy.defaultPrevented) e.preventDefault();
+          }});
+        }}
+      }}
+    }} catch (e) {{
+      console.error(`shadow root error: ${{e}}`);
+    }}
+"#
+    )
+}
+
+// JS expression evaluating to the first element matching `selector` in the document or in the shadow roots of the
+// outlets (`PortalProviderProps::shadow_root`), nested ones included
+fn js_deep_query(selector: &str) -> String {
+    format!(
+        r#"((selector) => {{ const find = (root) => root.querySelector(selector) || Array.from(root.querySelectorAll("[{SHADOW_HOST_ATTRIBUTE}]"), (host) => host.shadowRoot && find(host.shadowRoot)).find(Boolean); return find(document); }})(`{selector}`)"#
+    )
+}
+
 #[derive(Props, Clone, PartialEq)]
 struct PortalOverlayEntryProps {
     pub id: PortalId,
-    pub z_index: usize,
+    pub z_index: i64,
 }
 
 #[component]
 fn PortalOverlayEntry(props: PortalOverlayEntryProps) -> Element {
     let provider_ctx = use_context::<PortalProviderContext>();
+    let mut pending_tap = use_signal(|| None::<PendingTap>);
     let id = props.id;
     let z_index = props.z_index;
-    let entries = provider_ctx.entries.read();
-    let data = entries.get(&id).unwrap();
+    let entry = use_entry_data(provider_ctx.entries, id);
+    let entry = entry.read();
+    let Some(data) = entry.as_ref() else {
+        log_missing_entry(id, "PortalOverlayEntry render");
+        return rsx! {};
+    };
 
     match &data.overlay {
-        None => rsx! {},
+        None => {
+            // Modal portals without their own overlay use the provider's default
+            let default_overlay = provider_ctx.default_overlay.read();
+            match (&*default_overlay, data.is_modal()) {
+                (Some(overlay), true) => rsx! {
+                    div {
+                        style : format!("pointer-events: auto; {OVERLAY_TOUCH_STYLE} position: absolute; top: 0; left: 0; width: 100%; height: 100%; z-index: {}; {}", z_index, transition_style(data, &transition_of(&provider_ctx, data))),
+                        {overlay.clone()}
+                    }
+                },
+                _ => rsx! {},
+            }
+        }
         Some(overlay_props) => {
-            let overlay_style = format!("pointer-events: auto; {} position: absolute; top: 0; left: 0; width: 100%; height: 100%; z-index: {};", overlay_props.style, z_index);
+            let behavior = overlay_props.overlay_behavior;
+            let (pointer_events, touch_style) = if behavior == Some(OverlayClick::PassThrough) || data.closing {
+                ("none", "")
+            } else {
+                ("auto", OVERLAY_TOUCH_STYLE)
+            };
+            let transition_style = transition_style(data, &transition_of(&provider_ctx, data));
+            let overlay_style = format!("pointer-events: {}; {touch_style} {} position: absolute; top: 0; left: 0; width: 100%; height: 100%; z-index: {}; {}", pointer_events, overlay_props.style, z_index, transition_style);
+            let entries = provider_ctx.entries;
             rsx! {
                 div {
                     style : overlay_style,
+                    "data-state" : if data.closing { "closed" } else { "open" },
+                    onpointerdown : move |evt: PointerEvent| match behavior {
+                        Some(OverlayClick::Dismiss) => {
+                            evt.stop_propagation();
+                            match PendingTap::start(&evt, None) {
+                                Some(tap) => pending_tap.set(Some(tap)),
+                                None => close_entries(entries, |data| data.id == id, DismissReason::OutsideClick),
+                            }
+                        }
+                        Some(OverlayClick::Block) => evt.stop_propagation(),
+                        _ => {}
+                    },
+                    onpointerup : move |evt: PointerEvent| {
+                        if behavior == Some(OverlayClick::Dismiss) {
+                            evt.stop_propagation();
+                            if pending_tap.take().is_some_and(|tap| tap.is_tap(&evt)) {
+                                close_entries(entries, |data| data.id == id, DismissReason::OutsideClick);
+                            }
+                        }
+                    },
+                    onpointercancel : move |_| pending_tap.set(None),
+                    onclick : move |evt: MouseEvent| {
+                        if behavior == Some(OverlayClick::Block) {
+                            evt.stop_propagation();
+                        }
+                    },
                     ..overlay_props.attributes.clone(),
                     {overlay_props.children.clone()}
                 }
@@ -485,160 +2920,100 @@ fn PortalOverlayEntry(props: PortalOverlayEntryProps) -> Element {
     }
 }
 
-// ------ Position calculation -------------------------------------------------------------------------------------------------------------------
-
-fn calc_content_range(
-    length: f64,
-    param: &AxisParam,
-    base: Range<f64>,
-    bounds: Range<f64>,
-) -> Range<f64> {
-    let desired = match (param.alignment, param.spread) {
-        (Alignment::Center, _) => {
-            let base_point = (base.start + base.end) * 0.5 + param.offset;
-            Range {
-                start: base_point - length * 0.5,
-                end: base_point + length * 0.5,
-            }
-        }
-        (Alignment::Start, Spread::Inside) => {
-            let base_point = base.start + param.offset;
-            Range {
-                start: base_point,
-                end: base_point + length,
-            }
-        }
-        (Alignment::Start, Spread::Outside) => {
-            let base_point = base.start - param.offset;
-            Range {
-                start: base_point - length,
-                end: base_point,
-            }
-        }
-        (Alignment::End, Spread::Inside) => {
-            let base_point = base.end - param.offset;
-            Range {
-                start: base_point - length,
-                end: base_point,
-            }
-        }
-        (Alignment::End, Spread::Outside) => {
-            let base_point = base.end + param.offset;
-            Range {
-                start: base_point,
-                end: base_point + length,
-            }
-        }
-    };
-
-    match (param.overflow_policy, param.alignment) {
-        (OverflowPolicy::Ignore, _) => desired,
-
-        (OverflowPolicy::Shrink, _) => Range {
-            start: desired.start.max(bounds.start),
-            end: desired.end.min(bounds.end),
-        },
+const FADE_IN_KEYFRAMES: &str = "dioxus-portal-fade-in";
+// An overlay that receives events keeps touches from panning or zooming the page under it
+const OVERLAY_TOUCH_STYLE: &str = "touch-action: none; overscroll-behavior: contain;";
 
-        (OverflowPolicy::Clamp, Alignment::Center) => desired,
-        (OverflowPolicy::Clamp, Alignment::Start) => {
-            if bounds.end < desired.end {
-                Range {
-                    start: bounds.end - length,
-                    end: bounds.end,
-                }
-            } else {
-                desired
-            }
-        }
-        (OverflowPolicy::Clamp, Alignment::End) => {
-            if desired.start < bounds.start {
-                Range {
-                    start: bounds.start,
-                    end: bounds.start + length,
-                }
-            } else {
-                desired
-            }
-        }
+// Transition of a portal: provider defaults, overridden by its overlay, disabled by reduced motion
+fn transition_of(provider_ctx: &PortalProviderContext, data: &PortalEntryData) -> Transition {
+    provider_ctx.motion.read().resolve(data.overlay.as_deref(), *provider_ctx.prefers_reduced_motion.read())
+}
 
-        (OverflowPolicy::Flip, Alignment::Center) => desired,
-        (OverflowPolicy::Flip, _) if bounds.start <= desired.start && desired.end <= bounds.end => {
-            desired
-        }
-        (OverflowPolicy::Flip, _) => {
-            let flip_alignment = if param.alignment == Alignment::Start {
-                Alignment::End
-            } else {
-                Alignment::Start
-            };
-            let param = AxisParam {
-                spread: param.spread,
-                offset: param.offset,
-                alignment: flip_alignment,
-                overflow_policy: OverflowPolicy::Clamp,
-            };
-            calc_content_range(length, &param, base, bounds)
-        }
+// Fade in on mount and fade out while closing
+fn transition_style(data: &PortalEntryData, transition: &Transition) -> String {
+    let easing = &transition.easing;
+    match (data.closing, transition.enter_ms, transition.exit_ms) {
+        (false, 0, _) | (true, _, 0) => String::new(),
+        (false, ms, _) => format!("animation: {FADE_IN_KEYFRAMES} {ms}ms {easing};"),
+        (true, _, ms) => format!("opacity: 0; transition: opacity {ms}ms {easing};"),
     }
 }
 
-fn calc_content_position(
-    data: &PortalEntryData,
-    content_size: Size2D<f64, Pixels>,
+// ------ Position calculation -------------------------------------------------------------------------------------------------------------------
+
+// Inputs of `calc_content_position`
+#[derive(Clone, Copy, PartialEq)]
+struct LayoutInputs {
+    param_v: AxisParam,
+    param_h: AxisParam,
+    size: Size2D<f64, Pixels>,
     anchor: Option<Rect>,
     bounds: Rect,
-) -> Point2D<f64, Pixels> {
-    let bounds_v = Range {
-        start: bounds.min_y(),
-        end: bounds.max_y(),
-    };
-    let bounds_h = Range {
-        start: bounds.min_x(),
-        end: bounds.max_x(),
-    };
+    memory: Option<ResolvedPlacement>,
+}
 
-    match anchor {
-        Some(anchor) => {
-            let anchor_v = Range {
-                start: anchor.min_y(),
-                end: anchor.max_y(),
-            };
-            let anchor_h = Range {
-                start: anchor.min_x(),
-                end: anchor.max_x(),
-            };
+// Last layout of a content. Renders (and direct position writes) whose inputs did not change reuse the result
+// instead of placing the content again
+#[derive(Clone, Default)]
+struct LayoutCache(Rc<Cell<Option<(LayoutInputs, Layout)>>>);
 
-            let range_v = calc_content_range(
-                content_size.height,
-                &data.vertical_param,
-                anchor_v,
-                bounds_v,
-            );
-            let range_h = calc_content_range(
-                content_size.width,
-                &data.horizontal_param,
-                anchor_h,
-                bounds_h,
-            );
+// Position of a content and the placement it resolved to
+type Layout = (Point2D<f64, Pixels>, ResolvedPlacement);
+
+impl LayoutCache {
+    // Whether `position` would return the cached layout for `inputs`
+    #[cfg(feature = "perf")]
+    fn is_cached(&self, inputs: &LayoutInputs) -> bool {
+        self.0.get().is_some_and(|(cached, _)| cached == *inputs)
+    }
 
-            Point2D::new(range_h.start, range_v.start)
+    fn position(&self, inputs: LayoutInputs) -> Layout {
+        if let Some((cached, output)) = self.0.get() {
+            if cached == inputs {
+                return output;
+            }
         }
-        None => {
-            let param_v = AxisParam {
-                spread: Spread::Inside,
-                ..data.vertical_param
-            };
-            let param_h = AxisParam {
-                spread: Spread::Inside,
-                ..data.horizontal_param
-            };
+        let output =
+            calc_content_position(inputs.param_v, inputs.param_h, inputs.size, inputs.anchor, inputs.bounds, inputs.memory);
+        self.0.set(Some((inputs, output)));
+        output
+    }
+}
 
-            let range_v =
-                calc_content_range(content_size.height, &param_v, bounds_v.clone(), bounds_v);
-            let range_h =
-                calc_content_range(content_size.width, &param_h, bounds_h.clone(), bounds_h);
+// Style variables of a content placed at `content` that follow its anchor: where the anchor's center lies on the
+// content (for arrows), and the room between the anchor and the bounds on the content's side
+fn anchored_properties(
+    param_v: &AxisParam,
+    param_h: &AxisParam,
+    resolved: ResolvedPlacement,
+    anchor: Rect,
+    bounds: Rect,
+    content: Rect,
+) -> Vec<(&'static str, f64)> {
+    let center = anchor.center();
+    let available = available_size(param_v, param_h, Some(resolved), anchor, bounds);
+    vec![
+        ("--dioxus-portal-arrow-x", (center.x - content.origin.x).clamp(0.0, content.width())),
+        ("--dioxus-portal-arrow-y", (center.y - content.origin.y).clamp(0.0, content.height())),
+        ("--dioxus-portal-available-width", available.width),
+        ("--dioxus-portal-available-height", available.height),
+    ]
+}
 
-            Point2D::new(range_h.start, range_v.start)
-        }
+// Side of the anchor a content is placed on (`top`, `bottom`, `left`, or `right`), given the alignments it
+// resolved to: the axis spreading outside the anchor, vertical first. `None` when it overlaps the anchor
+fn side_of(param_v: &AxisParam, param_h: &AxisParam, vertical: Alignment, horizontal: Alignment) -> Option<&'static str> {
+    match (param_v.spread, vertical, param_h.spread, horizontal) {
+        (Spread::Outside, Alignment::Start, _, _) => Some("top"),
+        (Spread::Outside, Alignment::End, _, _) => Some("bottom"),
+        (_, _, Spread::Outside, Alignment::Start) => Some("left"),
+        (_, _, Spread::Outside, Alignment::End) => Some("right"),
+        _ => None,
     }
 }
+
+// Anchor rectangle of a portal: `anchor_rect`, else the latest one applied directly, else the measured one
+fn anchor_rect_of(provider_ctx: &PortalProviderContext, data: &PortalEntryData) -> Option<Rect> {
+    let direct = provider_ctx.direct_positions.as_ref().and_then(|direct| direct.anchor(data.id));
+    data.custom_anchor_rect.or(direct).or(data.measured_anchor_rect)
+}
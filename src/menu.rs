@@ -0,0 +1,309 @@
+//! Keyboard navigation for menu-style portal content.
+//!
+//! - `PortalMenuContent` registers its children as the portal content (with `role="menu"`, or another `role` such
+//!   as `ContentRole::Listbox`) and wraps them in an element handling ArrowUp/ArrowDown/Home/End focus movement, Enter activation, and Escape closing.
+//! - Items are located on the JS side with a CSS selector (`[role="menuitem"]` by default). Disabled items
+//!   (`disabled` or `aria-disabled="true"`) are skipped.
+//! - Typing printable characters moves focus to the first item whose text (or `data-text-value`) starts with
+//!   the typed prefix. The prefix is kept on the JS side and reset after `typeahead_timeout_ms`.
+use dioxus_lib::core::use_drop;
+use dioxus_lib::html::{Key, Modifiers};
+use dioxus_lib::{document, prelude::*};
+
+use crate::{alloc_component_id, ContentRole, PortalContent};
+
+/// Properties for `PortalMenuContent`.
+/// `style`/`attributes` are applied to the positioned content wrapper, like `PortalContent`.
+#[derive(Props, Clone, PartialEq)]
+pub struct PortalMenuContentProps {
+    #[props(default)]
+    pub style: String,
+
+    /// Role of the positioned wrapper.
+    #[props(default = ContentRole::Menu)]
+    pub role: ContentRole,
+
+    /// Make the content at least as wide as the anchor, as `PortalContent::match_anchor_width`.
+    #[props(default = false)]
+    pub match_anchor_width: bool,
+
+    /// Called when Escape is pressed. Typically sets the owning portal's `open` to `false`.
+    #[props(default)]
+    pub on_close: Callback<()>,
+
+    /// CSS selector matching the navigable items.
+    #[props(default = DEFAULT_ITEM_SELECTOR.to_string())]
+    pub item_selector: String,
+
+    /// Wrap around when moving past the first/last item.
+    #[props(default = true)]
+    pub loop_focus: bool,
+
+    /// Focus the first item when the menu is mounted.
+    #[props(default = true)]
+    pub auto_focus: bool,
+
+    /// Move focus to the item matching typed characters.
+    #[props(default = true)]
+    pub typeahead: bool,
+
+    /// Idle time after which the typed prefix is reset.
+    #[props(default = 500)]
+    pub typeahead_timeout_ms: u32,
+
+    #[props(extends = GlobalAttributes)]
+    attributes: Vec<Attribute>,
+
+    children: Element,
+}
+
+/// Portal content with menu keyboard navigation.
+#[component]
+pub fn PortalMenuContent(props: PortalMenuContentProps) -> Element {
+    let id = use_memo(|| alloc_component_id("dioxus-portal-menu-"));
+    let id = id();
+
+    let onmounted = {
+        let id = id.clone();
+        let item_selector = props.item_selector.clone();
+        move |_| {
+            if props.auto_focus {
+                document::eval(&js_code_of_move_focus(
+                    &id,
+                    &item_selector,
+                    FocusMove::First,
+                    false,
+                ));
+            }
+        }
+    };
+
+    let onkeydown = {
+        let id = id.clone();
+        let item_selector = props.item_selector.clone();
+        move |evt: KeyboardEvent| {
+            let focus_move = match evt.key() {
+                Key::ArrowDown => Some(FocusMove::Next),
+                Key::ArrowUp => Some(FocusMove::Prev),
+                Key::Home => Some(FocusMove::First),
+                Key::End => Some(FocusMove::Last),
+                _ => None,
+            };
+
+            if let Some(focus_move) = focus_move {
+                evt.prevent_default();
+                document::eval(&js_code_of_move_focus(
+                    &id,
+                    &item_selector,
+                    focus_move,
+                    props.loop_focus,
+                ));
+                return;
+            }
+
+            match evt.key() {
+                Key::Enter => {
+                    evt.prevent_default();
+                    document::eval(&js_code_of_activate_item(&id, &item_selector));
+                }
+                Key::Escape => {
+                    // Closing is handled here, so keep the provider's dismissal stack from closing another layer
+                    evt.prevent_default();
+                    evt.stop_propagation();
+                    props.on_close.call(());
+                }
+                Key::Character(ch)
+                    if props.typeahead
+                        && !evt
+                            .modifiers()
+                            .intersects(Modifiers::CONTROL | Modifiers::META | Modifiers::ALT) =>
+                {
+                    document::eval(&js_code_of_typeahead(
+                        &id,
+                        &item_selector,
+                        &ch,
+                        props.typeahead_timeout_ms,
+                    ));
+                }
+                _ => {}
+            }
+        }
+    };
+
+    // Discard the typeahead state on unmount
+    {
+        let id = id.clone();
+        use_drop(move || {
+            document::eval(&js_code_of_clear_typeahead(&id));
+        });
+    }
+
+    rsx! {
+        PortalContent {
+            role : props.role,
+            match_anchor_width : props.match_anchor_width,
+            style : props.style,
+            attributes : props.attributes,
+
+            div {
+                id : id,
+                onmounted : onmounted,
+                onkeydown : onkeydown,
+                {props.children}
+            }
+        }
+    }
+}
+
+const DEFAULT_ITEM_SELECTOR: &str = r#"[role="menuitem"]"#;
+const TYPEAHEAD_REG_KEY: &str = "dioxus-portal-menu-typeahead";
+
+/// Direction of a focus movement among menu items.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum FocusMove {
+    First,
+    Last,
+    Next,
+    Prev,
+}
+
+impl FocusMove {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FocusMove::First => "first",
+            FocusMove::Last => "last",
+            FocusMove::Next => "next",
+            FocusMove::Prev => "prev",
+        }
+    }
+}
+
+/// Generates the JS snippet collecting the enabled items of the menu into `items`.
+fn js_code_of_collect_items(menu_id: &str, item_selector: &str) -> String {
+    let item_selector = item_selector.replace('\\', "\\\\").replace('`', "\\`");
+    format!(
+        r#"
+      const root = document.getElementById("{menu_id}");
+      if (!root) return;
+      const items = Array.from(root.querySelectorAll(`{item_selector}`)).filter(
+        (el) => !el.disabled && el.getAttribute("aria-disabled") !== "true"
+      );
+      for (const el of items) {{
+        if (!el.hasAttribute("tabindex")) el.tabIndex = -1;
+      }}
+"#
+    )
+}
+
+/// Generates JS code to move focus among menu items.
+pub(crate) fn js_code_of_move_focus(
+    menu_id: &str,
+    item_selector: &str,
+    focus_move: FocusMove,
+    loop_focus: bool,
+) -> String {
+    let collect = js_code_of_collect_items(menu_id, item_selector);
+    let focus_move = focus_move.as_str();
+    format!(
+        r#"
+    try {{
+      {collect}
+      if (items.length === 0) return;
+
+      const current = items.indexOf(document.activeElement);
+      let next;
+      switch ("{focus_move}") {{
+        case "first": next = 0; break;
+        case "last": next = items.length - 1; break;
+        case "next":
+          next = current < 0 ? 0 : current + 1;
+          if (next >= items.length) next = {loop_focus} ? 0 : items.length - 1;
+          break;
+        case "prev":
+          next = current < 0 ? items.length - 1 : current - 1;
+          if (next < 0) next = {loop_focus} ? items.length - 1 : 0;
+          break;
+      }}
+      items[next].focus();
+    }} catch (e) {{
+      console.error(`menu focus error: ${{e}}`);
+    }}
+"#
+    )
+}
+
+/// Generates JS code to activate (click) the focused menu item.
+fn js_code_of_activate_item(menu_id: &str, item_selector: &str) -> String {
+    let collect = js_code_of_collect_items(menu_id, item_selector);
+    format!(
+        r#"
+    try {{
+      {collect}
+      const current = items.indexOf(document.activeElement);
+      if (current >= 0) items[current].click();
+    }} catch (e) {{
+      console.error(`menu activate error: ${{e}}`);
+    }}
+"#
+    )
+}
+
+/// Generates JS code to accumulate a typed character and focus the first matching item.
+fn js_code_of_typeahead(menu_id: &str, item_selector: &str, ch: &str, timeout_ms: u32) -> String {
+    let collect = js_code_of_collect_items(menu_id, item_selector);
+    let ch = ch.replace('\\', "\\\\").replace('"', "\\\"");
+    format!(
+        r#"
+    try {{
+      const REG_KEY = Symbol.for("{TYPEAHEAD_REG_KEY}");
+      if (!globalThis[REG_KEY]) {{
+        globalThis[REG_KEY] = new Map();
+      }}
+      const reg = globalThis[REG_KEY];
+      const state = reg.get("{menu_id}") || {{ prefix: "", time: 0 }};
+      const now = Date.now();
+      if (now - state.time > {timeout_ms}) state.prefix = "";
+      const ch = "{ch}";
+      if (ch === " " && state.prefix === "") return; // leave a lone space to the focused item
+      state.prefix += ch.toLowerCase();
+      state.time = now;
+      reg.set("{menu_id}", state);
+
+      {collect}
+      if (items.length === 0) return;
+
+      const text = (el) => (el.dataset.textValue ?? el.textContent ?? "").trim().toLowerCase();
+      const current = items.indexOf(document.activeElement);
+
+      // Repeating one character cycles through items starting with it, like a native <select>
+      const repeated = state.prefix.length > 1 && [...state.prefix].every((c) => c === state.prefix[0]);
+      const prefix = repeated ? state.prefix[0] : state.prefix;
+      const start = repeated || state.prefix.length === 1 ? current + 1 : Math.max(current, 0);
+
+      for (let i = 0; i < items.length; i++) {{
+        const item = items[(start + i) % items.length];
+        if (text(item).startsWith(prefix)) {{
+          item.focus();
+          break;
+        }}
+      }}
+    }} catch (e) {{
+      console.error(`menu typeahead error: ${{e}}`);
+    }}
+"#
+    )
+}
+
+/// Generates JS code to discard the typeahead state of a menu.
+fn js_code_of_clear_typeahead(menu_id: &str) -> String {
+    format!(
+        r#"
+    try {{
+      const reg = globalThis[Symbol.for("{TYPEAHEAD_REG_KEY}")];
+      if (reg) reg.delete("{menu_id}");
+    }} catch (e) {{
+      console.error(`menu typeahead error: ${{e}}`);
+    }}
+"#
+    )
+}
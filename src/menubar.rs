@@ -0,0 +1,291 @@
+//! Ready-made menubar built on `PortalGroup` and the dropdown menu panel.
+//!
+//! - `Menubar` renders a horizontal `role="menubar"` and wraps its `MenubarMenu`s in a `PortalGroup`, so opening
+//!   one menu closes the others at once instead of letting them fade out.
+//! - The open menu is shared: while one is open, hovering another trigger or pressing ArrowLeft/ArrowRight (on a
+//!   trigger or inside the open menu) moves the open menu there. Otherwise the arrows, Home, and End only move focus
+//!   between triggers.
+//! - Triggers use a roving tabindex: only the last focused one (initially the first) is in the Tab order. Menus
+//!   are ordered as they were first rendered.
+//! - Menus are `MenuPanel`s, so `MenuItem`, `MenuCheckboxItem`, and `MenuSeparator` work inside them as in a
+//!   `DropdownMenu`.
+use std::rc::Rc;
+
+use dioxus_lib::core::use_drop;
+use dioxus_lib::html::Key;
+use dioxus_lib::prelude::*;
+
+use crate::disclosure::use_disclosure;
+use crate::dropdown_menu::MenuPanel;
+use crate::focus::use_trigger_state;
+use crate::menu::FocusMove;
+use crate::{alloc_component_id, Layer, PlacementConfig, Portal, PortalAnchor, PortalGroup};
+
+/// Properties for `Menubar`.
+#[derive(Props, Clone, PartialEq)]
+pub struct MenubarProps {
+    /// Accessible name of the menubar.
+    #[props(optional, into)]
+    pub aria_label: Option<String>,
+
+    /// Stacking layer of the menus.
+    #[props(default = Layer::Dropdown.base(), into)]
+    pub layer: i32,
+
+    /// Declarations appended to the default style of the bar.
+    #[props(default)]
+    pub style: String,
+
+    /// `MenubarMenu`s.
+    children: Element,
+}
+
+/// Horizontal bar of menus sharing one open state.
+#[component]
+pub fn Menubar(props: MenubarProps) -> Element {
+    let open = use_signal(|| None);
+    let current = use_signal(|| None);
+    let menus = use_signal(Vec::new);
+    let mut layer = use_signal(|| props.layer);
+    if *layer.peek() != props.layer {
+        layer.set(props.layer);
+    }
+    use_context_provider(|| MenubarContext {
+        open,
+        current,
+        menus,
+        layer,
+    });
+
+    rsx! {
+        div {
+            role : "menubar",
+            "aria-orientation" : "horizontal",
+            "aria-label" : props.aria_label,
+            style : "display: flex; align-items: center; gap: 2px; padding: 2px; {props.style}",
+            PortalGroup {
+                skip_delay_ms : 0,
+                {props.children}
+            }
+        }
+    }
+}
+
+/// State shared by the menus of a `Menubar`.
+#[derive(Clone, Copy)]
+struct MenubarContext {
+    open: Signal<Option<String>>,    // Menu currently open
+    current: Signal<Option<String>>, // Trigger in the Tab order, the first one when unset
+    menus: Signal<Vec<MenubarEntry>>,
+    layer: Signal<i32>,
+}
+
+#[derive(Clone)]
+struct MenubarEntry {
+    id: String,
+    trigger: Option<Rc<MountedData>>,
+}
+
+impl MenubarContext {
+    /// Menu reached from `from` by `focus_move`, wrapping around at both ends.
+    fn target(&self, from: &str, focus_move: FocusMove) -> Option<MenubarEntry> {
+        let menus = self.menus.peek();
+        let last = menus.len().checked_sub(1)?;
+        let index = menus.iter().position(|entry| entry.id == from);
+        let index = match (focus_move, index) {
+            (FocusMove::First, _) => 0,
+            (FocusMove::Last, _) => last,
+            (FocusMove::Next, Some(i)) if i < last => i + 1,
+            (FocusMove::Next, _) => 0,
+            (FocusMove::Prev, Some(i)) if i > 0 => i - 1,
+            (FocusMove::Prev, _) => last,
+        };
+        Some(menus[index].clone())
+    }
+
+    /// Moves focus from the trigger of `from`, and the open menu along with it when one is open.
+    fn step(&mut self, from: &str, focus_move: FocusMove) {
+        let Some(target) = self.target(from, focus_move) else {
+            return;
+        };
+        self.current.set(Some(target.id.clone()));
+        if self.open.peek().is_some() {
+            // The opened menu focuses its first item
+            self.open.set(Some(target.id));
+        } else if let Some(trigger) = target.trigger {
+            spawn(async move {
+                let _ = trigger.set_focus(true).await;
+            });
+        }
+    }
+
+    fn is_tabbable(&self, id: &str) -> bool {
+        match &*self.current.read() {
+            Some(current) => current == id,
+            None => self
+                .menus
+                .read()
+                .first()
+                .is_some_and(|entry| entry.id == id),
+        }
+    }
+}
+
+/// Properties for `MenubarMenu`.
+#[derive(Props, Clone, PartialEq)]
+pub struct MenubarMenuProps {
+    /// Content of the trigger button.
+    pub label: Element,
+
+    /// Placement against the trigger.
+    #[props(default = PlacementConfig::bottom().align_start().offset(4.0).flip())]
+    pub placement: PlacementConfig,
+
+    /// Declarations appended to the default style of the trigger button.
+    #[props(default)]
+    pub trigger_style: String,
+
+    /// Declarations appended to the default style of the menu panel.
+    #[props(default)]
+    pub style: String,
+
+    /// `MenuItem`s, `MenuCheckboxItem`s, and `MenuSeparator`s.
+    children: Element,
+}
+
+/// Top-level trigger of a `Menubar` and its menu.
+#[component]
+pub fn MenubarMenu(props: MenubarMenuProps) -> Element {
+    let mut ctx = use_context::<MenubarContext>();
+    let menu_id = use_hook(|| alloc_component_id("dioxus-portal-menubar-"));
+    let trigger_id = format!("{menu_id}-trigger");
+
+    {
+        let menu_id = menu_id.clone();
+        use_hook(move || {
+            ctx.menus.write().push(MenubarEntry {
+                id: menu_id,
+                trigger: None,
+            });
+        });
+    }
+    {
+        let menu_id = menu_id.clone();
+        use_drop(move || {
+            ctx.menus.write().retain(|entry| entry.id != menu_id);
+            if ctx.open.peek().as_ref() == Some(&menu_id) {
+                ctx.open.set(None);
+            }
+        });
+    }
+
+    let open = ctx.open.read().as_ref() == Some(&menu_id);
+    let on_open_change = {
+        let menu_id = menu_id.clone();
+        use_callback(move |requested: bool| {
+            if requested {
+                ctx.open.set(Some(menu_id.clone()));
+            } else if ctx.open.peek().as_ref() == Some(&menu_id) {
+                ctx.open.set(None);
+            }
+        })
+    };
+    let mut disclosure = use_disclosure(Some(open), on_open_change);
+    // Moving to another menu leaves focus to it; only closing the whole bar gives it back to the trigger
+    let restore = ctx.open.read().is_none();
+    use_trigger_state(&trigger_id, &menu_id, "menu", open, restore);
+
+    let tabbable = ctx.is_tabbable(&menu_id);
+    let step = {
+        let menu_id = menu_id.clone();
+        move |focus_move: FocusMove| ctx.step(&menu_id, focus_move)
+    };
+
+    rsx! {
+        Portal {
+            open : open,
+            layer : *ctx.layer.read(),
+            config : props.placement,
+            on_dismiss : move |_| disclosure.set(false),
+
+            PortalAnchor {
+                style : "display: inline-block;",
+                span {
+                    id : trigger_id,
+                    style : "display: contents;",
+                    button {
+                        r#type : "button",
+                        role : "menuitem",
+                        tabindex : if tabbable { "0" } else { "-1" },
+                        style : "padding: 4px 10px; border: none; border-radius: 4px; background: none; font: inherit; cursor: default; {props.trigger_style}",
+                        onmounted : {
+                            let menu_id = menu_id.clone();
+                            move |evt: MountedEvent| {
+                                let mut menus = ctx.menus.write();
+                                if let Some(entry) = menus.iter_mut().find(|entry| entry.id == menu_id) {
+                                    entry.trigger = Some(evt.data());
+                                }
+                            }
+                        },
+                        onfocus : {
+                            let menu_id = menu_id.clone();
+                            move |_| ctx.current.set(Some(menu_id.clone()))
+                        },
+                        onpointerdown : move |_| disclosure.press(),
+                        onclick : move |_| disclosure.click(),
+                        onpointerenter : move |evt: PointerEvent| {
+                            if evt.pointer_type() != "touch" && ctx.open.peek().is_some() && !open {
+                                disclosure.set(true);
+                            }
+                        },
+                        onkeydown : {
+                            let mut step = step.clone();
+                            move |evt: KeyboardEvent| {
+                                let focus_move = match evt.key() {
+                                    Key::ArrowRight => FocusMove::Next,
+                                    Key::ArrowLeft => FocusMove::Prev,
+                                    Key::Home => FocusMove::First,
+                                    Key::End => FocusMove::Last,
+                                    Key::ArrowDown | Key::ArrowUp => {
+                                        evt.prevent_default();
+                                        disclosure.set(true);
+                                        return;
+                                    }
+                                    _ => return,
+                                };
+                                evt.prevent_default();
+                                step(focus_move);
+                            }
+                        },
+                        {props.label}
+                    }
+                }
+            }
+
+            MenuPanel {
+                id : menu_id.clone(),
+                layer : *ctx.layer.read(),
+                loop_focus : true,
+                style : props.style,
+                on_close : move |_| disclosure.set(false),
+                div {
+                    style : "display: contents;",
+                    // Arrows across the menubar; the panel handles the vertical ones
+                    onkeydown : {
+                        let mut step = step;
+                        move |evt: KeyboardEvent| {
+                            let focus_move = match evt.key() {
+                                Key::ArrowRight => FocusMove::Next,
+                                Key::ArrowLeft => FocusMove::Prev,
+                                _ => return,
+                            };
+                            evt.prevent_default();
+                            step(focus_move);
+                        }
+                    },
+                    {props.children}
+                }
+            }
+        }
+    }
+}
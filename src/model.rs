@@ -0,0 +1,880 @@
+//! Renderer-agnostic placement model: the placement and stacking types, the math that positions a content
+//! against its anchor, and the order of the registered portals.
+//!
+//! - Nothing here depends on a renderer, eval, or the DOM, only on `euclid` geometry in CSS pixels, so the
+//!   layout can be reused by other front ends (TUI or native experiments) and checked without a browser.
+//! - `calc_content_position` places a content of a given size against an anchor rectangle (or within the bounds
+//!   when there is none), per axis, applying the axis' `OverflowPolicy` against the bounds.
+//! - `stack_order` sorts portals from bottom to top by their `StackPosition`, and `dismissal_target` picks the
+//!   portal a dismissal request (Escape, outside click) goes to. The registry itself stays with the components,
+//!   since its entries hold elements and callbacks; they hand over only what these need.
+use std::{fmt, ops::Range};
+
+use euclid::{Point2D, Size2D};
+
+/// Unit of CSS pixels for `euclid` types. Values measured by the renderer (e.g. `MountedData::get_client_rect`)
+/// are converted with `cast_unit`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Pixels;
+
+/// Rectangle in CSS pixels, in viewport coordinates unless stated otherwise.
+pub type Rect = euclid::Rect<f64, Pixels>;
+
+/// Side of the anchor (or of the bounds) a content is aligned to along one axis.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", serde(rename_all = "snake_case"))]
+pub enum Alignment {
+    Start,
+    Center,
+    End,
+}
+
+/// Whether a content aligned to a side extends inside the anchor or away from it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", serde(rename_all = "snake_case"))]
+pub enum Spread {
+    Inside,
+    Outside,
+}
+
+/// What happens along one axis when the content would leave the bounds.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", serde(rename_all = "snake_case"))]
+pub enum OverflowPolicy {
+    Ignore,
+    Shrink,
+    Clamp,
+    Flip,
+}
+
+impl fmt::Display for Alignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Alignment::Start => "start",
+            Alignment::Center => "center",
+            Alignment::End => "end",
+        })
+    }
+}
+
+impl fmt::Display for Spread {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Spread::Inside => "inside",
+            Spread::Outside => "outside",
+        })
+    }
+}
+
+impl fmt::Display for OverflowPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OverflowPolicy::Ignore => "ignore",
+            OverflowPolicy::Shrink => "shrink",
+            OverflowPolicy::Clamp => "clamp",
+            OverflowPolicy::Flip => "flip",
+        })
+    }
+}
+
+/// Placement parameters of one axis.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AxisParam {
+    pub alignment: Alignment,
+    pub spread: Spread,
+    pub offset: f64,
+    pub overflow_policy: OverflowPolicy,
+}
+
+/// `<alignment> <spread> <offset>px <overflow policy>`, e.g. `end outside 8px flip`.
+impl fmt::Display for AxisParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}px {}",
+            self.alignment, self.spread, self.offset, self.overflow_policy
+        )
+    }
+}
+
+impl AxisParam {
+    /// Default vertical placement: below the anchor.
+    pub const VERTICAL: AxisParam = AxisParam {
+        alignment: Alignment::End,
+        spread: Spread::Outside,
+        offset: 0.0,
+        overflow_policy: OverflowPolicy::Clamp,
+    };
+
+    /// Default horizontal placement: centered on the anchor.
+    pub const HORIZONTAL: AxisParam = AxisParam {
+        alignment: Alignment::Center,
+        spread: Spread::Inside,
+        offset: 0.0,
+        overflow_policy: OverflowPolicy::Clamp,
+    };
+
+    /// Applies the per-portal overrides on top of these defaults.
+    pub(crate) fn with_overrides(
+        self,
+        alignment: Option<Alignment>,
+        spread: Option<Spread>,
+        offset: Option<f64>,
+        overflow_policy: Option<OverflowPolicy>,
+    ) -> AxisParam {
+        AxisParam {
+            alignment: alignment.unwrap_or(self.alignment),
+            spread: spread.unwrap_or(self.spread),
+            offset: offset.unwrap_or(self.offset),
+            overflow_policy: overflow_policy.unwrap_or(self.overflow_policy),
+        }
+    }
+}
+
+/// Placement of a portal as one value: both axes and an optional fixed anchor rectangle, e.g. to persist a
+/// user-customized panel placement or load it from a config file (with the `serialize` feature). Fields missing
+/// from a deserialized value take their defaults.
+///
+/// Built fluently from the side of the anchor the content goes to, e.g.
+/// `PlacementConfig::bottom().align_start().offset(8.0).flip()`. The builder methods are `const`, so configs can be
+/// shared as constants. The side axis is the one whose spread is `Outside` (the vertical one when both or neither
+/// are); `offset` and the overflow methods apply to it, and the `align_*` methods to the other axis.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", serde(default))]
+pub struct PlacementConfig {
+    pub vertical: AxisParam,
+    pub horizontal: AxisParam,
+    /// Anchor rectangle used instead of a `PortalAnchor`, as `PortalProps::anchor_rect`.
+    pub anchor_rect: Option<Rect>,
+}
+
+impl Default for PlacementConfig {
+    fn default() -> Self {
+        Self::bottom()
+    }
+}
+
+impl PlacementConfig {
+    /// Below the anchor, centered on it.
+    pub const fn bottom() -> Self {
+        PlacementConfig {
+            vertical: AxisParam::VERTICAL,
+            horizontal: AxisParam::HORIZONTAL,
+            anchor_rect: None,
+        }
+    }
+
+    /// Above the anchor, centered on it.
+    pub const fn top() -> Self {
+        let mut config = Self::bottom();
+        config.vertical.alignment = Alignment::Start;
+        config
+    }
+
+    /// Left of the anchor, centered on it.
+    pub const fn left() -> Self {
+        PlacementConfig {
+            vertical: AxisParam {
+                alignment: Alignment::Center,
+                spread: Spread::Inside,
+                ..AxisParam::VERTICAL
+            },
+            horizontal: AxisParam {
+                alignment: Alignment::Start,
+                spread: Spread::Outside,
+                ..AxisParam::HORIZONTAL
+            },
+            anchor_rect: None,
+        }
+    }
+
+    /// Right of the anchor, centered on it.
+    pub const fn right() -> Self {
+        let mut config = Self::left();
+        config.horizontal.alignment = Alignment::End;
+        config
+    }
+
+    /// Aligns the start edges of the content and the anchor along the other axis.
+    pub const fn align_start(self) -> Self {
+        self.align(Alignment::Start)
+    }
+
+    /// Centers the content on the anchor along the other axis.
+    pub const fn align_center(self) -> Self {
+        self.align(Alignment::Center)
+    }
+
+    /// Aligns the end edges of the content and the anchor along the other axis.
+    pub const fn align_end(self) -> Self {
+        self.align(Alignment::End)
+    }
+
+    /// Gap between the anchor and the content.
+    pub const fn offset(mut self, offset: f64) -> Self {
+        self.side_axis_mut().offset = offset;
+        self
+    }
+
+    /// Moves the content to the opposite side when it would leave the bounds.
+    pub const fn flip(self) -> Self {
+        self.overflow(OverflowPolicy::Flip)
+    }
+
+    /// What happens when the content would leave the bounds on its side's axis.
+    pub const fn overflow(mut self, policy: OverflowPolicy) -> Self {
+        self.side_axis_mut().overflow_policy = policy;
+        self
+    }
+
+    /// Places against `rect` instead of a `PortalAnchor`.
+    pub const fn anchor_rect(mut self, rect: Rect) -> Self {
+        self.anchor_rect = Some(rect);
+        self
+    }
+
+    const fn horizontal_side(&self) -> bool {
+        matches!(self.horizontal.spread, Spread::Outside)
+            && matches!(self.vertical.spread, Spread::Inside)
+    }
+
+    const fn side_axis_mut(&mut self) -> &mut AxisParam {
+        if self.horizontal_side() {
+            &mut self.horizontal
+        } else {
+            &mut self.vertical
+        }
+    }
+
+    const fn align(mut self, alignment: Alignment) -> Self {
+        let axis = if self.horizontal_side() {
+            &mut self.vertical
+        } else {
+            &mut self.horizontal
+        };
+        axis.alignment = alignment;
+        axis.spread = Spread::Inside;
+        self
+    }
+}
+
+/// Semantic stacking layers. Each maps to a reserved range of `LAYER_RANGE` values starting at its base, so portals
+/// from independent component libraries stack consistently. Plain integers below `Layer::Dropdown` stay available
+/// for application-specific layers.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Layer {
+    Dropdown,
+    Popover,
+    Modal,
+    Toast,
+    Tooltip,
+}
+
+impl fmt::Display for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Layer::Dropdown => "dropdown",
+            Layer::Popover => "popover",
+            Layer::Modal => "modal",
+            Layer::Toast => "toast",
+            Layer::Tooltip => "tooltip",
+        })
+    }
+}
+
+/// Number of layer values reserved for each `Layer`.
+pub const LAYER_RANGE: i32 = 1000;
+
+impl Layer {
+    /// First value of the reserved range.
+    pub const fn base(self) -> i32 {
+        let index = match self {
+            Layer::Dropdown => 1,
+            Layer::Popover => 2,
+            Layer::Modal => 3,
+            Layer::Toast => 4,
+            Layer::Tooltip => 5,
+        };
+        index * LAYER_RANGE
+    }
+
+    /// Value `n` steps above the base, kept inside the reserved range.
+    pub const fn offset(self, n: i32) -> i32 {
+        let n = if n < 0 {
+            0
+        } else if n >= LAYER_RANGE {
+            LAYER_RANGE - 1
+        } else {
+            n
+        };
+        self.base() + n
+    }
+
+    /// Semantic layer whose reserved range contains `layer`.
+    pub fn of(layer: i32) -> Option<Layer> {
+        [
+            Layer::Dropdown,
+            Layer::Popover,
+            Layer::Modal,
+            Layer::Toast,
+            Layer::Tooltip,
+        ]
+        .into_iter()
+        .find(|l| (l.base()..l.base() + LAYER_RANGE).contains(&layer))
+    }
+}
+
+impl From<Layer> for i32 {
+    fn from(layer: Layer) -> i32 {
+        layer.base()
+    }
+}
+
+/// Position of a portal in its provider's stack. Ordered from bottom to top by layer value, then by `stack_key`
+/// (changed by `bring_to_front` / `send_to_back`), then by `open_seq` so the last opened is on top, and last by
+/// the portal's number, so the order never depends on the registry's iteration order.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct StackPosition {
+    pub layer: i32,
+    pub stack_key: i64,
+    pub open_seq: u64,
+    pub id: u64,
+}
+
+/// `items` sorted from bottom to top by their `StackPosition`.
+pub fn stack_order<T>(
+    items: impl IntoIterator<Item = T>,
+    position: impl Fn(&T) -> StackPosition,
+) -> Vec<T> {
+    let mut items = items.into_iter().collect::<Vec<_>>();
+    items.sort_by_key(|item| position(item));
+    items
+}
+
+/// How a portal takes dismissal requests.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Dismissal {
+    /// Lets them through to the portals below.
+    Ignored,
+    /// Takes them.
+    Requested,
+    /// Dismissed by the renderer itself (e.g. a light-dismissed popover), which keeps them from the portals below.
+    Native,
+}
+
+/// Item of `stacked` (from bottom to top) a dismissal request goes to: the topmost one that does not let it
+/// through, unless the renderer dismisses that one itself. `None` when no item takes it.
+pub fn dismissal_target<T>(
+    stacked: impl DoubleEndedIterator<Item = T>,
+    dismissal: impl Fn(&T) -> Dismissal,
+) -> Option<T> {
+    stacked
+        .rev()
+        .find(|item| dismissal(item) != Dismissal::Ignored)
+        .filter(|item| dismissal(item) == Dismissal::Requested)
+}
+
+/// Placement resolved by the layout, remembered for `remember_placement`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResolvedPlacement {
+    /// Vertical alignment after flipping.
+    pub vertical: Alignment,
+    /// Horizontal alignment after flipping.
+    pub horizontal: Alignment,
+    /// Size of the content that was placed.
+    pub size: Size2D<f64, Pixels>,
+}
+
+/// Outlet rect shrunk by `padding`, never past its center.
+pub fn placement_bounds(outlet_rect: Rect, padding: f64) -> Rect {
+    let padding_v = padding.min(outlet_rect.size.height / 2.0);
+    let padding_h = padding.min(outlet_rect.size.width / 2.0);
+    outlet_rect.inflate(-padding_h, -padding_v)
+}
+
+/// Range of a content of `length` along one axis, placed against `base` within `bounds`, and the alignment
+/// actually used (which differs from `param.alignment` when flipped).
+pub fn calc_content_range(
+    length: f64,
+    param: &AxisParam,
+    base: Range<f64>,
+    bounds: Range<f64>,
+) -> (Range<f64>, Alignment) {
+    let desired = match (param.alignment, param.spread) {
+        (Alignment::Center, _) => {
+            let base_point = (base.start + base.end) * 0.5 + param.offset;
+            Range {
+                start: base_point - length * 0.5,
+                end: base_point + length * 0.5,
+            }
+        }
+        (Alignment::Start, Spread::Inside) => {
+            let base_point = base.start + param.offset;
+            Range {
+                start: base_point,
+                end: base_point + length,
+            }
+        }
+        (Alignment::Start, Spread::Outside) => {
+            let base_point = base.start - param.offset;
+            Range {
+                start: base_point - length,
+                end: base_point,
+            }
+        }
+        (Alignment::End, Spread::Inside) => {
+            let base_point = base.end - param.offset;
+            Range {
+                start: base_point - length,
+                end: base_point,
+            }
+        }
+        (Alignment::End, Spread::Outside) => {
+            let base_point = base.end + param.offset;
+            Range {
+                start: base_point,
+                end: base_point + length,
+            }
+        }
+    };
+
+    let range = match (param.overflow_policy, param.alignment) {
+        (OverflowPolicy::Ignore, _) => desired,
+
+        (OverflowPolicy::Shrink, _) => Range {
+            start: desired.start.max(bounds.start),
+            end: desired.end.min(bounds.end),
+        },
+
+        (OverflowPolicy::Clamp, Alignment::Center) => desired,
+        (OverflowPolicy::Clamp, Alignment::Start) => {
+            if bounds.end < desired.end {
+                Range {
+                    start: bounds.end - length,
+                    end: bounds.end,
+                }
+            } else {
+                desired
+            }
+        }
+        (OverflowPolicy::Clamp, Alignment::End) => {
+            if desired.start < bounds.start {
+                Range {
+                    start: bounds.start,
+                    end: bounds.start + length,
+                }
+            } else {
+                desired
+            }
+        }
+
+        (OverflowPolicy::Flip, Alignment::Center) => desired,
+        (OverflowPolicy::Flip, _) if bounds.start <= desired.start && desired.end <= bounds.end => {
+            desired
+        }
+        (OverflowPolicy::Flip, _) => {
+            let flip_alignment = if param.alignment == Alignment::Start {
+                Alignment::End
+            } else {
+                Alignment::Start
+            };
+            let param = AxisParam {
+                spread: param.spread,
+                offset: param.offset,
+                alignment: flip_alignment,
+                overflow_policy: OverflowPolicy::Clamp,
+            };
+            return calc_content_range(length, &param, base, bounds);
+        }
+    };
+
+    (range, param.alignment)
+}
+
+/// Viewport position of a content of `content_size` placed against `anchor` (or within `bounds` without one),
+/// and the placement it resolved to. `memory` overrides the starting alignments with the ones resolved on a
+/// previous open; pass it only for the first layout after a reopen, before the content is measured, since the
+/// remembered alignments would otherwise keep a flip from resolving back.
+pub fn calc_content_position(
+    mut param_v: AxisParam,
+    mut param_h: AxisParam,
+    content_size: Size2D<f64, Pixels>,
+    anchor: Option<Rect>,
+    bounds: Rect,
+    memory: Option<ResolvedPlacement>,
+) -> (Point2D<f64, Pixels>, ResolvedPlacement) {
+    let bounds_v = Range {
+        start: bounds.min_y(),
+        end: bounds.max_y(),
+    };
+    let bounds_h = Range {
+        start: bounds.min_x(),
+        end: bounds.max_x(),
+    };
+
+    if let Some(memory) = memory {
+        param_v.alignment = memory.vertical;
+        param_h.alignment = memory.horizontal;
+    }
+
+    let ((range_v, alignment_v), (range_h, alignment_h)) = match anchor {
+        Some(anchor) => {
+            let anchor_v = Range {
+                start: anchor.min_y(),
+                end: anchor.max_y(),
+            };
+            let anchor_h = Range {
+                start: anchor.min_x(),
+                end: anchor.max_x(),
+            };
+
+            (
+                calc_content_range(content_size.height, &param_v, anchor_v, bounds_v),
+                calc_content_range(content_size.width, &param_h, anchor_h, bounds_h),
+            )
+        }
+        None => {
+            let param_v = AxisParam {
+                spread: Spread::Inside,
+                ..param_v
+            };
+            let param_h = AxisParam {
+                spread: Spread::Inside,
+                ..param_h
+            };
+
+            (
+                calc_content_range(content_size.height, &param_v, bounds_v.clone(), bounds_v),
+                calc_content_range(content_size.width, &param_h, bounds_h.clone(), bounds_h),
+            )
+        }
+    };
+
+    let resolved = ResolvedPlacement {
+        vertical: alignment_v,
+        horizontal: alignment_h,
+        size: content_size,
+    };
+    (Point2D::new(range_h.start, range_v.start), resolved)
+}
+
+/// Room along one axis for a content placed by `param` (with the resolved `alignment`) against `base`, up to the
+/// edge of `bounds` it extends towards. Centered contents get the whole of `bounds`.
+pub fn available_length(
+    param: &AxisParam,
+    alignment: Alignment,
+    base: Range<f64>,
+    bounds: Range<f64>,
+) -> f64 {
+    let length = match (alignment, param.spread) {
+        (Alignment::Center, _) => bounds.end - bounds.start,
+        (Alignment::Start, Spread::Outside) => base.start - param.offset - bounds.start,
+        (Alignment::Start, Spread::Inside) => bounds.end - (base.start + param.offset),
+        (Alignment::End, Spread::Outside) => bounds.end - (base.end + param.offset),
+        (Alignment::End, Spread::Inside) => base.end - param.offset - bounds.start,
+    };
+    length.max(0.0)
+}
+
+/// Room for a content placed against `anchor` within `bounds`, as (width, height). Without a `resolved` placement
+/// (before the content is measured), an axis that may flip gets the larger of its two sides, so the content is not
+/// shrunk to fit the side it is about to flip away from.
+pub fn available_size(
+    param_v: &AxisParam,
+    param_h: &AxisParam,
+    resolved: Option<ResolvedPlacement>,
+    anchor: Rect,
+    bounds: Rect,
+) -> Size2D<f64, Pixels> {
+    let axis =
+        |param: &AxisParam, resolved: Option<Alignment>, base: Range<f64>, bounds: Range<f64>| {
+            match resolved {
+                Some(alignment) => available_length(param, alignment, base, bounds),
+                None if param.overflow_policy == OverflowPolicy::Flip
+                    && param.alignment != Alignment::Center =>
+                {
+                    let flipped = if param.alignment == Alignment::Start {
+                        Alignment::End
+                    } else {
+                        Alignment::Start
+                    };
+                    available_length(param, param.alignment, base.clone(), bounds.clone())
+                        .max(available_length(param, flipped, base, bounds))
+                }
+                None => available_length(param, param.alignment, base, bounds),
+            }
+        };
+    Size2D::new(
+        axis(
+            param_h,
+            resolved.map(|r| r.horizontal),
+            anchor.min_x()..anchor.max_x(),
+            bounds.min_x()..bounds.max_x(),
+        ),
+        axis(
+            param_v,
+            resolved.map(|r| r.vertical),
+            anchor.min_y()..anchor.max_y(),
+            bounds.min_y()..bounds.max_y(),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> Rect {
+        Rect::new(Point2D::new(x, y), Size2D::new(width, height))
+    }
+
+    const BOUNDS: Rect = Rect {
+        origin: Point2D::new(0.0, 0.0),
+        size: Size2D::new(400.0, 600.0),
+    };
+
+    fn param(alignment: Alignment, spread: Spread, overflow_policy: OverflowPolicy) -> AxisParam {
+        AxisParam {
+            alignment,
+            spread,
+            offset: 0.0,
+            overflow_policy,
+        }
+    }
+
+    // Range of a content 30 long against `base`, and the alignment it resolved to
+    fn place(param: AxisParam, base: Range<f64>, bounds: Range<f64>) -> (Range<f64>, Alignment) {
+        calc_content_range(30.0, &param, base, bounds)
+    }
+
+    #[test]
+    fn alignments_and_spreads_within_bounds() {
+        use Alignment::*;
+        use Spread::*;
+        let cases = [
+            (Start, Inside, 100.0..130.0),
+            (Start, Outside, 70.0..100.0),
+            (Center, Inside, 95.0..125.0),
+            (Center, Outside, 95.0..125.0),
+            (End, Inside, 90.0..120.0),
+            (End, Outside, 120.0..150.0),
+        ];
+        for policy in [
+            OverflowPolicy::Ignore,
+            OverflowPolicy::Shrink,
+            OverflowPolicy::Clamp,
+            OverflowPolicy::Flip,
+        ] {
+            for (alignment, spread, expected) in cases.clone() {
+                let resolved = place(param(alignment, spread, policy), 100.0..120.0, 0.0..200.0);
+                assert_eq!(
+                    resolved,
+                    (expected, alignment),
+                    "{alignment} {spread} {policy}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn offset_moves_away_from_the_anchor() {
+        let below = AxisParam {
+            offset: 8.0,
+            ..param(Alignment::End, Spread::Outside, OverflowPolicy::Ignore)
+        };
+        let above = AxisParam {
+            alignment: Alignment::Start,
+            ..below
+        };
+        assert_eq!(place(below, 100.0..120.0, 0.0..200.0).0, 128.0..158.0);
+        assert_eq!(place(above, 100.0..120.0, 0.0..200.0).0, 62.0..92.0);
+    }
+
+    #[test]
+    fn ignore_leaves_the_bounds() {
+        use Alignment::*;
+        let ignore = |alignment| param(alignment, Spread::Outside, OverflowPolicy::Ignore);
+        assert_eq!(
+            place(ignore(End), 100.0..120.0, 0.0..140.0),
+            (120.0..150.0, End)
+        );
+        assert_eq!(
+            place(ignore(Start), 10.0..30.0, 0.0..140.0),
+            (-20.0..10.0, Start)
+        );
+        assert_eq!(
+            place(ignore(Center), 0.0..10.0, 0.0..140.0),
+            (-10.0..20.0, Center)
+        );
+    }
+
+    #[test]
+    fn flip_moves_to_the_other_side() {
+        use Alignment::*;
+        let flip = |alignment| param(alignment, Spread::Outside, OverflowPolicy::Flip);
+        assert_eq!(
+            place(flip(End), 100.0..120.0, 0.0..140.0),
+            (70.0..100.0, Start)
+        );
+        assert_eq!(
+            place(flip(Start), 10.0..30.0, 0.0..140.0),
+            (30.0..60.0, End)
+        );
+        // Centered contents have no other side
+        assert_eq!(
+            place(flip(Center), 0.0..10.0, 0.0..140.0),
+            (-10.0..20.0, Center)
+        );
+    }
+
+    #[test]
+    fn clamp_keeps_inside_contents_within_bounds() {
+        use Alignment::*;
+        let clamp = |alignment| param(alignment, Spread::Inside, OverflowPolicy::Clamp);
+        assert_eq!(
+            place(clamp(Start), 180.0..195.0, 0.0..200.0),
+            (170.0..200.0, Start)
+        );
+        assert_eq!(place(clamp(End), 5.0..20.0, 0.0..200.0), (0.0..30.0, End));
+        assert_eq!(
+            place(clamp(Center), 0.0..10.0, 0.0..200.0),
+            (-10.0..20.0, Center)
+        );
+    }
+
+    #[test]
+    fn shrink_cuts_at_the_bounds() {
+        use Alignment::*;
+        let shrink = |alignment| param(alignment, Spread::Outside, OverflowPolicy::Shrink);
+        assert_eq!(
+            place(shrink(End), 100.0..120.0, 0.0..140.0),
+            (120.0..140.0, End)
+        );
+        assert_eq!(
+            place(shrink(Start), 10.0..30.0, 0.0..140.0),
+            (0.0..10.0, Start)
+        );
+        assert_eq!(
+            place(shrink(Center), 5.0..15.0, 0.0..140.0),
+            (0.0..25.0, Center)
+        );
+    }
+
+    #[test]
+    fn position_without_anchor_stays_inside_the_bounds() {
+        let config = PlacementConfig::bottom();
+        let (pos, resolved) = calc_content_position(
+            config.vertical,
+            config.horizontal,
+            Size2D::new(50.0, 30.0),
+            None,
+            BOUNDS,
+            None,
+        );
+        assert_eq!(pos, Point2D::new(175.0, 570.0));
+        assert_eq!(resolved.vertical, Alignment::End);
+        assert_eq!(resolved.horizontal, Alignment::Center);
+    }
+
+    #[test]
+    fn position_flips_each_axis_independently() {
+        let config = PlacementConfig::right().align_start().flip();
+        let (pos, resolved) = calc_content_position(
+            config.vertical,
+            config.horizontal,
+            Size2D::new(50.0, 30.0),
+            Some(rect(360.0, 100.0, 20.0, 20.0)),
+            BOUNDS,
+            None,
+        );
+        assert_eq!(pos, Point2D::new(310.0, 100.0));
+        assert_eq!(resolved.horizontal, Alignment::Start);
+        assert_eq!(resolved.vertical, Alignment::Start);
+    }
+
+    #[test]
+    fn flip_resolves_back_once_the_anchor_has_room_again() {
+        let config = PlacementConfig::bottom().flip();
+        let size = Size2D::new(50.0, 30.0);
+
+        // No room below the anchor: flipped above it
+        let (pos, resolved) = calc_content_position(
+            config.vertical,
+            config.horizontal,
+            size,
+            Some(rect(100.0, 560.0, 50.0, 20.0)),
+            BOUNDS,
+            None,
+        );
+        assert_eq!(pos.y, 530.0);
+        assert_eq!(resolved.vertical, Alignment::Start);
+
+        // Reopened elsewhere: the remembered side is used for the unmeasured first layout...
+        let (pos, _) = calc_content_position(
+            config.vertical,
+            config.horizontal,
+            size,
+            Some(rect(100.0, 100.0, 50.0, 20.0)),
+            BOUNDS,
+            Some(resolved),
+        );
+        assert_eq!(pos.y, 70.0);
+
+        // ...and the layouts after it drop back below the anchor
+        let (pos, resolved) = calc_content_position(
+            config.vertical,
+            config.horizontal,
+            size,
+            Some(rect(100.0, 100.0, 50.0, 20.0)),
+            BOUNDS,
+            None,
+        );
+        assert_eq!(pos.y, 120.0);
+        assert_eq!(resolved.vertical, Alignment::End);
+    }
+
+    fn position(layer: i32, stack_key: i64, open_seq: u64, id: u64) -> StackPosition {
+        StackPosition {
+            layer,
+            stack_key,
+            open_seq,
+            id,
+        }
+    }
+
+    #[test]
+    fn stack_order_goes_by_layer_then_stack_key_then_opening() {
+        let portals = [
+            ("modal", position(Layer::Modal.base(), 0, 1, 1)),
+            ("raised", position(Layer::Dropdown.base(), 1, 2, 2)),
+            ("later", position(Layer::Dropdown.base(), 0, 4, 3)),
+            ("earlier", position(Layer::Dropdown.base(), 0, 3, 4)),
+        ];
+        let order = stack_order(portals, |(_, position)| *position);
+        assert_eq!(
+            order.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+            ["earlier", "later", "raised", "modal"]
+        );
+    }
+
+    #[test]
+    fn dismissal_goes_to_the_topmost_portal_taking_it() {
+        use Dismissal::*;
+        assert_eq!(
+            dismissal_target([Requested, Ignored].into_iter(), |d| *d),
+            Some(Requested)
+        );
+        // A natively dismissed portal on top keeps the request from the ones below
+        assert_eq!(
+            dismissal_target([Requested, Native, Ignored].into_iter(), |d| *d),
+            None
+        );
+        assert_eq!(dismissal_target([Ignored].into_iter(), |d| *d), None);
+    }
+}
@@ -0,0 +1,101 @@
+//! Animation settings shared by the portals of a provider.
+//!
+//! - `PortalProvider` holds the default enter/exit durations and easing. A `PortalOverlay` can override them
+//!   for its own portal with `transition_ms` / `transition_easing`.
+//! - `ReducedMotion` decides whether the user's `prefers-reduced-motion` setting disables the transitions.
+//!   The media query is watched on the JS side so changes apply without a reload.
+use dioxus_lib::{document, prelude::*};
+
+use crate::PortalOverlayProps;
+
+/// How portal transitions react to the `prefers-reduced-motion` media query.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ReducedMotion {
+    /// Disable transitions when the user prefers reduced motion.
+    System,
+    /// Always disable transitions.
+    Reduce,
+    /// Always run transitions.
+    Ignore,
+}
+
+/// Provider-scoped animation defaults.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct MotionDefaults {
+    pub enter_ms: u64,
+    pub exit_ms: u64,
+    pub easing: String,
+    pub reduced_motion: ReducedMotion,
+}
+
+/// Transition actually applied to one portal.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct Transition {
+    pub enter_ms: u64,
+    pub exit_ms: u64,
+    pub easing: String,
+}
+
+impl MotionDefaults {
+    /// Resolves the transition of a portal from these defaults, its overlay's overrides, and the user preference.
+    pub fn resolve(
+        &self,
+        overlay: Option<&PortalOverlayProps>,
+        prefers_reduced: bool,
+    ) -> Transition {
+        let reduced = match self.reduced_motion {
+            ReducedMotion::System => prefers_reduced,
+            ReducedMotion::Reduce => true,
+            ReducedMotion::Ignore => false,
+        };
+        if reduced {
+            return Transition {
+                enter_ms: 0,
+                exit_ms: 0,
+                easing: self.easing.clone(),
+            };
+        }
+
+        let ms = overlay.and_then(|overlay| overlay.transition_ms);
+        Transition {
+            enter_ms: ms.unwrap_or(self.enter_ms),
+            exit_ms: ms.unwrap_or(self.exit_ms),
+            easing: overlay
+                .and_then(|overlay| overlay.transition_easing.clone())
+                .unwrap_or_else(|| self.easing.clone()),
+        }
+    }
+}
+
+/// Tracks `prefers-reduced-motion: reduce`. Stays `false` on renderers without `matchMedia`.
+pub(crate) fn use_prefers_reduced_motion() -> Signal<bool> {
+    let mut prefers_reduced = use_signal(|| false);
+    use_hook(|| {
+        if !crate::has_eval() {
+            return;
+        }
+        spawn(async move {
+            crate::client::client_ready().await;
+            let mut eval = document::eval(JS_CODE_OF_WATCH_REDUCED_MOTION);
+            while let Ok(matches) = eval.recv::<bool>().await {
+                if *prefers_reduced.peek() != matches {
+                    prefers_reduced.set(matches);
+                }
+            }
+        });
+    });
+    prefers_reduced
+}
+
+// Sends the current value of the media query and every later change
+const JS_CODE_OF_WATCH_REDUCED_MOTION: &str = r#"
+    try {
+      if (typeof matchMedia === "function") {
+        const mq = matchMedia("(prefers-reduced-motion: reduce)");
+        dioxus.send(mq.matches);
+        mq.addEventListener("change", (e) => dioxus.send(e.matches));
+      }
+    } catch (e) {
+      console.error(`reduced motion error: ${e}`);
+    }
+"#;
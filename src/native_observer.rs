@@ -0,0 +1,97 @@
+//! `MeasureBackend` for renderers without a JS runtime, such as Blitz / dioxus-native (`native` feature).
+//!
+//! - Elements are measured with `MountedData::get_client_rect`, which these renderers answer from their own
+//!   layout. Only the border box is available, whatever the `observed_box`.
+//! - There are no resize or scroll notifications to listen to, so every observed element is measured again
+//!   on an interval (one frame, or the `IntervalMs` of its throttle) and reported when it changed.
+//! - Visibility cannot be determined, so elements are reported visible, as the eval observer does without
+//!   `IntersectionObserver`.
+use std::{cell::RefCell, collections::HashMap, rc::Rc, sync::OnceLock, time::Instant};
+
+use dioxus_lib::core::Task;
+use dioxus_lib::prelude::*;
+
+use crate::rect_history::RectSample;
+use crate::rect_observer::{
+    MeasureBackend, ObserveTarget, ObserverError, ReportOptions, ThrottleMode,
+};
+use crate::timer::sleep;
+use crate::PortalRoot;
+
+/// Interval between measurements of the elements observed once per frame.
+const FRAME_MS: u64 = 16;
+
+thread_local! {
+    /// Measurement loops by observation id.
+    static LOOPS: RefCell<HashMap<String, Task>> = RefCell::new(HashMap::new());
+}
+
+/// Backend measuring through `MountedData`.
+pub(crate) struct MountedBackend;
+
+impl MeasureBackend for MountedBackend {
+    fn observe(
+        &self,
+        id: &str,
+        target: &ObserveTarget,
+        options: ReportOptions,
+        on_sample: Rc<dyn Fn(RectSample)>,
+        _on_error: Option<Rc<dyn Fn(ObserverError)>>,
+    ) -> bool {
+        let mounted = match target {
+            ObserveTarget::Element {
+                mounted: Some(mounted),
+                ..
+            }
+            | ObserveTarget::Root(PortalRoot::Mounted(mounted)) => mounted.clone(),
+            // Not mounted yet, or only reachable by selector
+            _ => return false,
+        };
+        if LOOPS.with(|loops| loops.borrow().contains_key(id)) {
+            return true;
+        }
+        let interval_ms = match options.throttle {
+            ThrottleMode::IntervalMs(ms) => u64::from(ms),
+            ThrottleMode::AnimationFrame | ThrottleMode::Immediate => FRAME_MS,
+        };
+        let task = spawn(async move {
+            let mut last = None;
+            loop {
+                if let Ok(rect) = mounted.get_client_rect().await {
+                    if last != Some(rect) {
+                        last = Some(rect);
+                        on_sample(RectSample {
+                            rect: rect.cast_unit(),
+                            time_ms: now_ms(),
+                        });
+                    }
+                }
+                sleep(interval_ms).await;
+            }
+        });
+        LOOPS.with(|loops| loops.borrow_mut().insert(id.to_string(), task));
+        true
+    }
+
+    fn watch_visibility(
+        &self,
+        _id: &str,
+        _target: &ObserveTarget,
+        on_visible: Rc<dyn Fn(bool)>,
+    ) -> bool {
+        on_visible(true);
+        true
+    }
+
+    fn unobserve(&self, id: &str) {
+        if let Some(task) = LOOPS.with(|loops| loops.borrow_mut().remove(id)) {
+            task.cancel();
+        }
+    }
+}
+
+/// Milliseconds since the first measurement, the clock of `RectSample::time_ms` in this backend.
+fn now_ms() -> f64 {
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
@@ -0,0 +1,104 @@
+//! Counters of the observer/layout pipeline (`perf` feature), for measuring regressions.
+//!
+//! - The provider keeps one `PerfCounters`. The outlet and entry components count the rectangle reports they
+//!   receive, the layout passes they run (or reuse from `LayoutCache`), and their renders, per portal.
+//! - Counting never writes a signal, so the instrumentation does not cause the renders it counts.
+//! - `use_perf_counters` returns the provider's counters; `snapshot` reads them and `reset` starts a new run.
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use dioxus_lib::prelude::*;
+
+use crate::{PortalId, PortalProviderContext};
+
+/// Counts of one portal.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct PortalCounters {
+    /// Rectangles of the `PortalAnchor` received from the observer.
+    pub anchor_reports: u64,
+    /// Rectangles of the contents received from the observer.
+    pub content_reports: u64,
+    /// Content positions computed by the layout.
+    pub layout_passes: u64,
+    /// Content positions reused because the layout inputs did not change.
+    pub layout_reuses: u64,
+    /// Renders of the portal's entry and content components in the outlet.
+    pub renders: u64,
+}
+
+impl PortalCounters {
+    fn add(&mut self, other: &PortalCounters) {
+        self.anchor_reports += other.anchor_reports;
+        self.content_reports += other.content_reports;
+        self.layout_passes += other.layout_passes;
+        self.layout_reuses += other.layout_reuses;
+        self.renders += other.renders;
+    }
+}
+
+/// Counts of a provider since its mount or the last `PerfCounters::reset`.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct PerfSnapshot {
+    /// Rectangles of the outlets (default and `PortalTarget`s) received from the observer.
+    pub outlet_reports: u64,
+    /// Counts by portal, including portals that have unmounted since.
+    pub portals: HashMap<PortalId, PortalCounters>,
+}
+
+impl PerfSnapshot {
+    /// Sum of the counts of every portal.
+    pub fn total(&self) -> PortalCounters {
+        let mut total = PortalCounters::default();
+        for counters in self.portals.values() {
+            total.add(counters);
+        }
+        total
+    }
+}
+
+/// Counters of a `PortalProvider`, returned by `use_perf_counters`.
+#[derive(Clone, Default)]
+pub struct PerfCounters(Rc<RefCell<PerfSnapshot>>);
+
+impl std::fmt::Debug for PerfCounters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("PerfCounters")
+            .field(&*self.0.borrow())
+            .finish()
+    }
+}
+
+impl PerfCounters {
+    /// Current counts.
+    pub fn snapshot(&self) -> PerfSnapshot {
+        self.0.borrow().clone()
+    }
+
+    /// Sets every count back to zero.
+    pub fn reset(&self) {
+        *self.0.borrow_mut() = PerfSnapshot::default();
+    }
+
+    pub(crate) fn count(&self, id: PortalId, f: impl FnOnce(&mut PortalCounters)) {
+        f(self.0.borrow_mut().portals.entry(id).or_default());
+    }
+
+    pub(crate) fn count_outlet_report(&self) {
+        self.0.borrow_mut().outlet_reports += 1;
+    }
+
+    /// Counts a layout of portal `id`, computed or reused depending on `cached`.
+    pub(crate) fn count_layout(&self, id: PortalId, cached: bool) {
+        self.count(id, |counters| {
+            if cached {
+                counters.layout_reuses += 1;
+            } else {
+                counters.layout_passes += 1;
+            }
+        });
+    }
+}
+
+/// Counters of the enclosing `PortalProvider`. Panics outside one.
+pub fn use_perf_counters() -> PerfCounters {
+    use_context::<PortalProviderContext>().perf
+}
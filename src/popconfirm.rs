@@ -0,0 +1,210 @@
+//! Ready-made confirmation popover built on the portal primitives.
+//!
+//! - `Popconfirm` wraps a `trigger` like `Popover`, and shows a `title` (and optional `description`) with Cancel and
+//!   Confirm buttons anchored to it, as an `alertdialog`. Opening focuses the confirm button; closing returns focus
+//!   to the trigger.
+//! - Escape, outside clicks, and Cancel all cancel: `on_cancel` is called and the popover closes.
+//! - Confirm calls `on_confirm` and closes, or with `on_confirm_async`, awaits the returned future first. Meanwhile
+//!   the popover stays open in a loading state (`aria-busy`, buttons inert) and ignores dismissals.
+use std::future::Future;
+use std::pin::Pin;
+
+use dioxus_lib::prelude::*;
+
+use crate::disclosure::use_disclosure;
+use crate::focus::use_trigger_state;
+use crate::{
+    alloc_component_id, Layer, PlacementConfig, Portal, PortalAnchor, PortalArrow, PortalContent,
+};
+
+/// Future returned by `Popconfirm::on_confirm_async`, e.g. `Box::pin(async move { ... })`.
+pub type ConfirmFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+/// Properties for `Popconfirm`.
+#[derive(Props, Clone, PartialEq)]
+pub struct PopconfirmProps {
+    /// Element opening the confirmation, typically a button.
+    pub trigger: Element,
+
+    /// Question asked, which also labels the popover.
+    #[props(into)]
+    pub title: String,
+
+    /// Details under the title, which also describe the popover.
+    #[props(optional, into)]
+    pub description: Option<String>,
+
+    #[props(default = "OK".to_string(), into)]
+    pub confirm_label: String,
+
+    #[props(default = "Cancel".to_string(), into)]
+    pub cancel_label: String,
+
+    /// Called on Confirm, right before closing.
+    #[props(default)]
+    pub on_confirm: Callback<()>,
+
+    /// Called on Confirm instead of `on_confirm`. The popover stays open, loading, until the future resolves.
+    #[props(optional)]
+    pub on_confirm_async: Option<Callback<(), ConfirmFuture>>,
+
+    /// Called on Cancel, Escape, and outside clicks.
+    #[props(default)]
+    pub on_cancel: Callback<()>,
+
+    /// Controlled open state. When unset, the popconfirm keeps its own.
+    #[props(optional)]
+    pub open: Option<bool>,
+
+    /// Called with the requested open state on trigger clicks, answers, and dismissals.
+    #[props(default)]
+    pub on_open_change: Callback<bool>,
+
+    /// Style the confirm button as destructive.
+    #[props(default = false)]
+    pub danger: bool,
+
+    /// Placement against the trigger.
+    #[props(default = PlacementConfig::top().offset(8.0).flip())]
+    pub placement: PlacementConfig,
+
+    /// Stacking layer.
+    #[props(default = Layer::Popover.base(), into)]
+    pub layer: i32,
+
+    /// Render a `PortalArrow` pointing at the trigger.
+    #[props(default = true)]
+    pub arrow: bool,
+
+    /// Declarations appended to the default style of the popover box.
+    #[props(default)]
+    pub style: String,
+}
+
+/// Trigger asking for confirmation in an anchored popover.
+#[component]
+pub fn Popconfirm(props: PopconfirmProps) -> Element {
+    let content_id = use_hook(|| alloc_component_id("dioxus-portal-popconfirm-"));
+    let trigger_id = format!("{content_id}-trigger");
+    let title_id = format!("{content_id}-title");
+    let description_id = format!("{content_id}-description");
+    let mut disclosure = use_disclosure(props.open, props.on_open_change);
+    let open = disclosure.open;
+    let mut loading = use_signal(|| false);
+    use_trigger_state(&trigger_id, &content_id, "dialog", open, true);
+
+    let on_cancel = props.on_cancel;
+    let mut cancel = move || {
+        if !*loading.peek() {
+            on_cancel.call(());
+            disclosure.set(false);
+        }
+    };
+    let on_confirm = props.on_confirm;
+    let on_confirm_async = props.on_confirm_async;
+    let mut confirm = move || {
+        if *loading.peek() {
+            return;
+        }
+        let Some(on_confirm_async) = on_confirm_async else {
+            on_confirm.call(());
+            disclosure.set(false);
+            return;
+        };
+        let future = on_confirm_async.call(());
+        loading.set(true);
+        spawn(async move {
+            future.await;
+            loading.set(false);
+            disclosure.set(false);
+        });
+    };
+    let busy = loading();
+    let described_by = props.description.as_ref().map(|_| description_id.clone());
+
+    let confirm_color = if props.danger { "#dc2626" } else { "#2563eb" };
+    let inert_style = if busy {
+        "opacity: 0.6; cursor: progress;"
+    } else {
+        "cursor: pointer;"
+    };
+
+    rsx! {
+        Portal {
+            open : open,
+            layer : props.layer,
+            config : props.placement,
+            on_dismiss : move |_| cancel(),
+
+            PortalAnchor {
+                style : "display: inline-block;",
+                span {
+                    id : trigger_id,
+                    style : "display: contents;",
+                    // Runs before the provider dismisses the popover as an outside click
+                    onpointerdown : move |_| disclosure.press(),
+                    onclick : move |_| {
+                        if !busy {
+                            disclosure.click();
+                        }
+                    },
+                    {props.trigger}
+                }
+            }
+
+            PortalContent {
+                div {
+                    id : content_id,
+                    role : "alertdialog",
+                    "aria-labelledby" : title_id.clone(),
+                    "aria-describedby" : described_by,
+                    "aria-busy" : busy.then_some("true"),
+                    "data-state" : if open { "open" } else { "closed" },
+                    style : "position: relative; max-width: 320px; padding: 12px; border: 1px solid #e5e7eb; border-radius: 8px; background: white; box-shadow: 0 8px 24px rgba(0,0,0,.12); outline: none; {props.style}",
+                    div {
+                        id : title_id,
+                        style : "font-weight: 600;",
+                        {props.title.clone()}
+                    }
+                    if let Some(description) = &props.description {
+                        div {
+                            id : description_id,
+                            style : "margin-top: 4px; color: #4b5563;",
+                            {description.clone()}
+                        }
+                    }
+                    div {
+                        style : "display: flex; justify-content: flex-end; gap: 8px; margin-top: 12px;",
+                        button {
+                            r#type : "button",
+                            "aria-disabled" : busy.then_some("true"),
+                            style : "padding: 4px 12px; border: 1px solid #d1d5db; border-radius: 6px; background: white; font: inherit; {inert_style}",
+                            onclick : move |_| cancel(),
+                            {props.cancel_label.clone()}
+                        }
+                        // Stays focusable while loading (`aria-disabled` rather than `disabled`), so focus is not
+                        // dropped to the page
+                        button {
+                            r#type : "button",
+                            "aria-disabled" : busy.then_some("true"),
+                            style : "padding: 4px 12px; border: 1px solid {confirm_color}; border-radius: 6px; background: {confirm_color}; color: white; font: inherit; {inert_style}",
+                            onmounted : move |evt: MountedEvent| {
+                                spawn(async move {
+                                    let _ = evt.data().set_focus(true).await;
+                                });
+                            },
+                            onclick : move |_| confirm(),
+                            {props.confirm_label.clone()}
+                            if busy {
+                                "…"
+                            }
+                        }
+                    }
+                    if props.arrow {
+                        PortalArrow {}
+                    }
+                }
+            }
+        }
+    }
+}
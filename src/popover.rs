@@ -0,0 +1,125 @@
+//! Ready-made popover built on the portal primitives.
+//!
+//! - `Popover` renders its `trigger` inside a `PortalAnchor` and toggles `content` on click (or Enter/Space on
+//!   a focusable trigger). It joins the dismissal stack, so outside clicks and Escape close it one layer at a
+//!   time.
+//! - Open state is internal unless `open` is set; either way `on_open_change` reports every requested change.
+//!   A press on the trigger while open closes it (see `disclosure`).
+//! - Opening focuses the first tabbable element of the content, and closing returns focus to the trigger (see
+//!   `focus`). The trigger gets `aria-haspopup`, `aria-expanded`, `aria-controls`, and `data-state`.
+use dioxus_lib::prelude::*;
+
+use crate::disclosure::use_disclosure;
+use crate::focus::{focus_first, use_trigger_state};
+use crate::{
+    alloc_component_id, Layer, PlacementConfig, Portal, PortalAnchor, PortalArrow, PortalContent,
+};
+
+/// Properties for `Popover`.
+#[derive(Props, Clone, PartialEq)]
+pub struct PopoverProps {
+    /// Element toggling the popover, typically a button.
+    pub trigger: Element,
+
+    /// Body of the popover.
+    pub content: Element,
+
+    /// Controlled open state. When unset, the popover keeps its own.
+    #[props(optional)]
+    pub open: Option<bool>,
+
+    /// Called with the requested open state on trigger clicks and dismissals.
+    #[props(default)]
+    pub on_open_change: Callback<bool>,
+
+    /// Placement against the trigger.
+    #[props(default = PlacementConfig::bottom().offset(8.0).flip())]
+    pub placement: PlacementConfig,
+
+    /// Stacking layer.
+    #[props(default = Layer::Popover.base(), into)]
+    pub layer: i32,
+
+    /// Render a `PortalArrow` pointing at the trigger.
+    #[props(default = true)]
+    pub arrow: bool,
+
+    /// Focus the first tabbable element of the content on open.
+    #[props(default = true)]
+    pub auto_focus: bool,
+
+    /// Return focus to the trigger on close.
+    #[props(default = true)]
+    pub restore_focus: bool,
+
+    /// Close on clicks outside the content.
+    #[props(default = true)]
+    pub dismiss_on_outside_click: bool,
+
+    /// Declarations appended to the default style of the popover box.
+    #[props(default)]
+    pub style: String,
+}
+
+/// Trigger toggling an anchored, dismissible panel.
+#[component]
+pub fn Popover(props: PopoverProps) -> Element {
+    let content_id = use_hook(|| alloc_component_id("dioxus-portal-popover-"));
+    let trigger_id = format!("{content_id}-trigger");
+    let mut disclosure = use_disclosure(props.open, props.on_open_change);
+    let open = disclosure.open;
+
+    use_trigger_state(
+        &trigger_id,
+        &content_id,
+        "dialog",
+        open,
+        props.restore_focus,
+    );
+
+    let auto_focus = props.auto_focus;
+    let onmounted = {
+        let content_id = content_id.clone();
+        move |_| {
+            if auto_focus {
+                focus_first(&content_id);
+            }
+        }
+    };
+
+    rsx! {
+        Portal {
+            open : open,
+            layer : props.layer,
+            config : props.placement,
+            on_dismiss : move |_| disclosure.set(false),
+            dismiss_on_outside_click : props.dismiss_on_outside_click,
+
+            PortalAnchor {
+                style : "display: inline-block;",
+                span {
+                    id : trigger_id,
+                    style : "display: contents;",
+                    // Runs before the provider dismisses the popover as an outside click
+                    onpointerdown : move |_| disclosure.press(),
+                    onclick : move |_| disclosure.click(),
+                    {props.trigger}
+                }
+            }
+
+            PortalContent {
+                div {
+                    id : content_id,
+                    role : "dialog",
+                    "data-state" : if open { "open" } else { "closed" },
+                    style : "position: relative; min-width: 160px; padding: 12px; border: 1px solid #e5e7eb; border-radius: 8px; background: white; box-shadow: 0 8px 24px rgba(0,0,0,.12); outline: none; {props.style}",
+                    onmounted : onmounted,
+                    {props.content}
+                    if props.arrow {
+                        PortalArrow {}
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Timestamped rectangle reports and the recent history kept by `RectObserver` (`history_len`).
+//!
+//! - Every report carries the time it was measured, in milliseconds of the page clock (`performance.now()`),
+//!   so only differences between samples of the same page are meaningful.
+//! - `RectHistory` keeps the last samples of one observer and derives the velocity from them. Consumers use it
+//!   for smoothing, predictive placement during fast scrolls, or waiting for the element to settle.
+use std::collections::VecDeque;
+
+use euclid::Vector2D;
+
+use crate::model::Pixels;
+use crate::rect_observer::Rect;
+
+/// A rectangle and when it was measured.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RectSample {
+    pub rect: Rect,
+    /// Measurement time in milliseconds of the page clock.
+    pub time_ms: f64,
+}
+
+/// The latest samples of an observed element, oldest first.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct RectHistory {
+    samples: VecDeque<RectSample>,
+    capacity: usize,
+}
+
+impl RectHistory {
+    /// Empty history keeping at most `capacity` samples.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Appends a sample, dropping the oldest one when full.
+    pub fn push(&mut self, sample: RectSample) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Samples, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = &RectSample> {
+        self.samples.iter()
+    }
+
+    /// The most recent sample.
+    pub fn latest(&self) -> Option<RectSample> {
+        self.samples.back().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Average velocity of the rectangle's origin over the kept samples, in pixels per second.
+    /// `None` with fewer than two samples or when they were measured at the same time.
+    pub fn velocity(&self) -> Option<Vector2D<f64, Pixels>> {
+        let (first, last) = (self.samples.front()?, self.samples.back()?);
+        let elapsed_ms = last.time_ms - first.time_ms;
+        if elapsed_ms <= 0.0 {
+            return None;
+        }
+        Some((last.rect.origin - first.rect.origin) * (1000.0 / elapsed_ms))
+    }
+
+    /// Rectangle at `time_ms` if it kept moving at `velocity`; the latest one without a velocity.
+    pub fn predict(&self, time_ms: f64) -> Option<Rect> {
+        let latest = self.latest()?;
+        let velocity = self.velocity().unwrap_or_default();
+        let ahead_s = (time_ms - latest.time_ms) / 1000.0;
+        Some(latest.rect.translate(velocity * ahead_s))
+    }
+}
@@ -1,28 +1,465 @@
 //! Utility that observes an element's rectangle (`getBoundingClientRect` equivalent) and notifies Rust.
 //!
-//! - The `RectObserver` component hooks JS `ResizeObserver` and `scroll`/`resize` events on its own
-//!   DOM element, throttling via rAF while sending rectangles.
-//! - Observation handles are managed by a JS-side registry (`REG_KEY`), ensuring proper start/stop
-//!   on mount/unmount.
+//! - The `RectObserver` component registers its own DOM element with a shared JS manager, which keeps one
+//!   `ResizeObserver`, one set of window `scroll`/`resize` listeners, and one rAF flush for every tracked element.
+//!   Element scroll listeners are attached only to the scroll containers the tracked elements are inside.
+//! - The manager lives in a JS-side registry (`REG_KEY`), ensuring proper start/stop on mount/unmount.
+//!   Every `PortalProvider` provides an `ObserverNamespace`, so the registry key and element ids are namespaced
+//!   per provider instance. Both also carry `instance_salt`, which keeps apart several apps (or copies of this
+//!   crate) on one page even though each counts its ids from zero.
 //! - The received rectangle is propagated upward via the `on_rect_changed` callback.
-use dioxus_lib::core::use_drop;
-use dioxus_lib::html::geometry::Pixels;
+//! - Under a `RectHub` (provided by each `PortalProvider`), rectangles are not sent per observer: the manager
+//!   collects them into one `{ id, rect }` batch per frame, streamed over the hub's single eval channel and
+//!   dispatched to the observers' callbacks on the Rust side.
+//! - `use_root_rect` tracks an element that is not rendered by this crate (a `PortalRoot`), either through the
+//!   same JS observer looked up by selector, or by re-reading `MountedData` on scroll/resize.
+//! - Where `ResizeObserver` is missing (some embedded webviews and older engines), the manager polls element
+//!   sizes on an interval instead (`RectHub::poll_ms`, `DEFAULT_POLL_MS` outside a provider).
+//! - Changes smaller than a threshold (`RectHub::min_delta`) are dropped on both sides, so sub-pixel jitter of
+//!   `getBoundingClientRect` does not reach the callbacks.
+//! - A `MutationObserver` on the document re-walks the scroll containers of elements that were moved (keyed
+//!   reorders, drag and drop), and stops measuring elements while they are out of the document.
+//! - Nothing is measured or sent while the document is hidden (background tab); every element is measured
+//!   once when it becomes visible again.
+//! - Elements are looked up by id in the document and in open shadow roots, and scroll containers are
+//!   collected across shadow boundaries, so apps rendered inside web components are observed too.
+//! - With `on_visibility_changed`, the element is also watched by the manager's shared `IntersectionObserver`
+//!   (against the viewport, which accounts for clipping by scroll containers), reported over its own channel.
+//! - With the `web` feature on wasm32, elements are observed by `web_observer` through `web-sys` instead,
+//!   without any eval. With the `native` feature, renderers without a JS runtime (Blitz / dioxus-native, with
+//!   `PortalProvider::eval` off) are measured by `native_observer` through `MountedData`. Both implement the public `MeasureBackend` trait, as
+//!   does the eval observer (`EvalBackend`), which remains the fallback for what they cannot observe wherever
+//!   eval is available. A `PortalProvider`'s `measure_backend` replaces all of them below it.
+//! - Under server-side rendering nothing is observed on the server: evals wait for `client::client_ready`, and
+//!   `RectObserver` renders its element id only once mounted on the client (see `client`).
+//! - With `MeasureFrequency::Low` (set on the hub), the manager ignores scrolls and continuous tracking, and only
+//!   measures when an observation starts, when an element or the window resizes, and on `RectHub::remeasure`.
+//!   Over LiveView every eval message is a network hop, so per-frame streaming would be too chatty.
+//! - `RectObserver`, `Rect`, and `use_element_rect` are public API, usable outside portals (sticky headers,
+//!   virtualizers). The JS manager, hub, and namespacing stay internal.
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    sync::OnceLock,
+};
+
+use dioxus_lib::core::{use_drop, Task};
 use dioxus_lib::{document, prelude::*};
 use euclid::{Point2D, Size2D};
 use serde::Deserialize;
 
-pub type Rect = euclid::Rect<f64, Pixels>;
+use crate::client::{client_ready, on_client, use_client_mounted};
+use crate::rect_history::{RectHistory, RectSample};
+use crate::{has_eval, PortalRoot};
+
+pub use crate::model::Rect;
+
+/// How often an observer reports rectangle changes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ThrottleMode {
+    /// At most one report per animation frame.
+    AnimationFrame,
+    /// At most one report per interval, in milliseconds (the last change of the interval is reported).
+    IntervalMs(u32),
+    /// Report from the event that caused the change, without waiting for a frame.
+    Immediate,
+}
+
+/// Which box of the element is measured and reported.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ObservedBox {
+    /// Border edges (`getBoundingClientRect`).
+    BorderBox,
+    /// Content edges, inside the padding and border.
+    ContentBox,
+    /// Margin edges, outside the margin.
+    MarginBox,
+}
+
+impl ObservedBox {
+    /// Value of the `box` constant of `js_code_of_start_observer`.
+    fn js_value(&self) -> &'static str {
+        match self {
+            ObservedBox::BorderBox => "border",
+            ObservedBox::ContentBox => "content",
+            ObservedBox::MarginBox => "margin",
+        }
+    }
+}
+
+/// Failure of an observation, reported to `RectObserver::on_error` and `PortalProvider::on_observer_error`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ObserverError {
+    /// The observed element was not in the document when observation started.
+    ElementNotFound,
+    /// The observation script threw, with the JS error message.
+    Script(String),
+}
+
+impl ObserverError {
+    /// Error of an `ObserverMessage::Error` with the given `error` kind.
+    fn from_js(kind: &str, message: String) -> Self {
+        match kind {
+            "not_found" => ObserverError::ElementNotFound,
+            _ => ObserverError::Script(message),
+        }
+    }
+}
+
+impl std::fmt::Display for ObserverError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObserverError::ElementNotFound => write!(f, "observed element not found"),
+            ObserverError::Script(message) => write!(f, "observer script error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ObserverError {}
+
+/// How often the observers of a `PortalProvider` measure through eval.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MeasureFrequency {
+    /// Whenever an element resizes or moves with a scroll, and every frame with `track_continuously`.
+    Continuous,
+    /// Only when an observation starts (e.g. a portal opens), when an element or the window resizes, and on
+    /// `PortalProviderHandle::reposition`. Scrolls are not followed. Meant for LiveView, where every report is a
+    /// network round trip.
+    Low,
+}
+
+impl Default for MeasureFrequency {
+    /// `Low` with the `liveview` feature, `Continuous` otherwise.
+    fn default() -> Self {
+        if cfg!(feature = "liveview") {
+            MeasureFrequency::Low
+        } else {
+            MeasureFrequency::Continuous
+        }
+    }
+}
+
+/// How an observation measures and reports, passed to `MeasureBackend::observe`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ReportOptions {
+    /// Report frequency.
+    pub throttle: ThrottleMode,
+    /// Changes where every edge moved by less than this many pixels need not be reported. Observers drop them
+    /// anyway, so honoring it only saves work.
+    pub min_delta: f64,
+    /// Box of the element that is reported.
+    pub observed_box: ObservedBox,
+    /// Whether the element should also be measured every animation frame (`track_continuously`).
+    pub continuous: bool,
+}
+
+/// Element observed through a `MeasureBackend`.
+#[derive(Clone)]
+pub enum ObserveTarget {
+    /// The element of a `RectObserver`, whose DOM id is the observation id (its first child when
+    /// `wrapperless`), with its `MountedData` once mounted. Backends that measure through `MountedData`
+    /// receive the observation again when it becomes available. `key` is the observer's `measure_key`.
+    Element {
+        wrapperless: bool,
+        mounted: Option<Rc<MountedData>>,
+        key: Option<String>,
+    },
+    /// An element that is not rendered by this crate, such as a `PortalProvider`'s `root`.
+    Root(PortalRoot),
+    /// The element matching `selector` inside the same-origin iframe matching `frame`, reported in the
+    /// coordinates of the host viewport (`PortalFrameAnchor`).
+    Frame { frame: String, selector: String },
+}
+
+impl std::fmt::Debug for ObserveTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObserveTarget::Element {
+                wrapperless,
+                mounted,
+                key,
+            } => f
+                .debug_struct("Element")
+                .field("wrapperless", wrapperless)
+                .field("mounted", &mounted.is_some())
+                .field("key", key)
+                .finish(),
+            ObserveTarget::Root(root) => f.debug_tuple("Root").field(root).finish(),
+            ObserveTarget::Frame { frame, selector } => f
+                .debug_struct("Frame")
+                .field("frame", frame)
+                .field("selector", selector)
+                .finish(),
+        }
+    }
+}
+
+impl PartialEq for ObserveTarget {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                ObserveTarget::Element {
+                    wrapperless: a,
+                    mounted: mounted_a,
+                    key: key_a,
+                },
+                ObserveTarget::Element {
+                    wrapperless: b,
+                    mounted: mounted_b,
+                    key: key_b,
+                },
+            ) => {
+                a == b
+                    && key_a == key_b
+                    && match (mounted_a, mounted_b) {
+                        (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+                        (a, b) => a.is_none() && b.is_none(),
+                    }
+            }
+            (ObserveTarget::Root(a), ObserveTarget::Root(b)) => a == b,
+            (
+                ObserveTarget::Frame {
+                    frame: frame_a,
+                    selector: selector_a,
+                },
+                ObserveTarget::Frame {
+                    frame: frame_b,
+                    selector: selector_b,
+                },
+            ) => frame_a == frame_b && selector_a == selector_b,
+            _ => false,
+        }
+    }
+}
+
+/// Source of the rectangles reported by `RectObserver` and the portals.
+///
+/// Observations are keyed by an id, unique per observer, that `unobserve` receives when the observer stops or
+/// unmounts. `observe` is called from the observer's scope, so a backend may `spawn` tasks tied to it.
+///
+/// By default, elements are measured by the backend of the build (`web-sys` with the `web` feature on wasm32,
+/// `MountedData` with `native` where the provider turns `eval` off), and what it declines by the JS observer
+/// run through `document::eval`. A backend given to `PortalProvider::measure_backend` replaces all of them for
+/// the observers below the provider, e.g. to feed fixed rectangles in tests or to measure on a host with no DOM.
+pub trait MeasureBackend {
+    /// Starts observing `target` under `id`, calling `on_sample` with its viewport rectangle now and after
+    /// every change, and `on_error` (when set) if the observation fails. Calling it again for an `id` that is
+    /// observed replaces the observation. Returns `false` when this backend cannot observe the target.
+    fn observe(
+        &self,
+        id: &str,
+        target: &ObserveTarget,
+        options: ReportOptions,
+        on_sample: Rc<dyn Fn(RectSample)>,
+        on_error: Option<Rc<dyn Fn(ObserverError)>>,
+    ) -> bool;
+
+    /// Calls `on_visible` with whether `target` is visible, once after the start and then on every change.
+    /// Returns `false` when this backend cannot watch it, which is the default.
+    fn watch_visibility(
+        &self,
+        id: &str,
+        target: &ObserveTarget,
+        on_visible: Rc<dyn Fn(bool)>,
+    ) -> bool {
+        let _ = (id, target, on_visible);
+        false
+    }
+
+    /// Stops the observation and the visibility watch registered under `id`, if any.
+    fn unobserve(&self, id: &str);
+}
+
+/// `MeasureBackend` given to a `PortalProvider`, compared by identity.
+#[derive(Clone)]
+pub struct SharedMeasureBackend(Rc<dyn MeasureBackend>);
+
+impl SharedMeasureBackend {
+    pub fn new(backend: impl MeasureBackend + 'static) -> Self {
+        SharedMeasureBackend(Rc::new(backend))
+    }
+}
+
+impl PartialEq for SharedMeasureBackend {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl std::fmt::Debug for SharedMeasureBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SharedMeasureBackend")
+    }
+}
+
+/// Built-in `MeasureBackend` of this build, if any. The `MountedData` one only stands in for the eval observer
+/// on renderers without eval.
+fn builtin_backend(eval: bool) -> Option<&'static dyn MeasureBackend> {
+    #[cfg(all(feature = "web", target_arch = "wasm32"))]
+    return Some(&crate::web_observer::WebSysBackend);
+    #[cfg(all(feature = "native", not(target_arch = "wasm32")))]
+    return (!eval).then_some(&crate::native_observer::MountedBackend as &dyn MeasureBackend);
+    #[allow(unreachable_code)]
+    {
+        let _ = eval;
+        None
+    }
+}
+
+/// Backends of an observer, in the order they are tried.
+#[derive(Clone)]
+struct Backends {
+    /// The provider's `measure_backend`, which replaces the others.
+    custom: Option<SharedMeasureBackend>,
+    eval: Rc<EvalBackend>,
+    /// Whether the renderer runs eval (`PortalProvider::eval`).
+    has_eval: bool,
+}
+
+impl Backends {
+    fn each(&self) -> Vec<&dyn MeasureBackend> {
+        if let Some(custom) = &self.custom {
+            return vec![&*custom.0];
+        }
+        // Only the eval observer runs the provider's `js_rect_hook`
+        let mut backends = match self.eval.rect_hook() {
+            Some(_) if self.has_eval => Vec::new(),
+            _ => Vec::from_iter(builtin_backend(self.has_eval)),
+        };
+        if self.has_eval {
+            backends.push(&*self.eval);
+        }
+        backends
+    }
+
+    /// Whether an observer should pass its `MountedData` to the backends. Renderers whose backends measure
+    /// through selectors leave it out, so the observation does not restart when the element mounts.
+    fn need_mounted(&self) -> bool {
+        self.custom.is_some() || !self.has_eval
+    }
+
+    fn observe(
+        &self,
+        id: &str,
+        target: &ObserveTarget,
+        options: ReportOptions,
+        on_sample: Rc<dyn Fn(RectSample)>,
+        on_error: Option<Rc<dyn Fn(ObserverError)>>,
+    ) {
+        self.each().into_iter().any(|backend| {
+            backend.observe(id, target, options, on_sample.clone(), on_error.clone())
+        });
+    }
+
+    fn watch_visibility(&self, id: &str, target: &ObserveTarget, on_visible: Rc<dyn Fn(bool)>) {
+        self.each()
+            .into_iter()
+            .any(|backend| backend.watch_visibility(id, target, on_visible.clone()));
+    }
+
+    fn unobserve(&self, id: &str) {
+        for backend in self.each() {
+            backend.unobserve(id);
+        }
+    }
+}
+
+/// Backends of the calling observer: the enclosing provider's `measure_backend`, or the defaults.
+fn use_backends() -> Backends {
+    let custom = try_use_context::<SharedMeasureBackend>();
+    let namespace = try_use_context::<ObserverNamespace>();
+    let hub = try_use_context::<RectHub>();
+    use_hook(|| Backends {
+        custom,
+        eval: Rc::new(EvalBackend {
+            reg_key: reg_key(namespace.as_ref()),
+            poll_ms: poll_ms_of(hub.as_ref()),
+            hub,
+            tasks: RefCell::new(HashMap::new()),
+        }),
+        has_eval: has_eval(),
+    })
+}
+
+impl ThrottleMode {
+    /// Value of the manager entry's `throttle` field.
+    fn js_value(&self) -> String {
+        match self {
+            ThrottleMode::AnimationFrame => r#""frame""#.to_string(),
+            ThrottleMode::IntervalMs(ms) => ms.to_string(),
+            ThrottleMode::Immediate => r#""immediate""#.to_string(),
+        }
+    }
+}
 
 /// Properties for `RectObserver`.
 /// Sends rectangles to `on_rect_changed`. `style`/`attributes` are applied to the wrapping `div`.
+/// The observation is set up on mount from `throttle`, `min_delta`, `history_len`, `track_continuously`,
+/// `wrapperless`, `measure_key`, and whether `on_visibility_changed` is set; later changes of these are ignored.
 #[derive(Props, PartialEq, Debug, Clone)]
 pub struct RectObserverProps {
+    /// Receives the rectangle of the wrapping `div` after mount and on every change.
     #[props(default)]
     pub on_rect_changed: Callback<Rect>,
 
+    /// Style of the wrapping `div`.
     #[props(default)]
     pub style: String,
 
+    /// Report frequency.
+    #[props(default = ThrottleMode::AnimationFrame)]
+    pub throttle: ThrottleMode,
+
+    /// Changes where every edge moved by less than this many pixels are not reported. Defaults to the
+    /// enclosing provider's `observer_min_delta`.
+    #[props(optional)]
+    pub min_delta: Option<f64>,
+
+    /// Box of the element that is reported.
+    #[props(default = ObservedBox::BorderBox)]
+    pub observed_box: ObservedBox,
+
+    /// Receives whether any part of the element is visible in the viewport and its scroll containers: once
+    /// after mount, then on every change.
+    #[props(optional)]
+    pub on_visibility_changed: Option<Callback<bool>>,
+
+    /// Suspends observation (rectangle and visibility) while `true`. Resuming measures right away.
+    #[props(default = false)]
+    pub paused: bool,
+
+    /// Number of recent samples passed to `on_history_changed`.
+    #[props(default = 0)]
+    pub history_len: usize,
+
+    /// Receives the last `history_len` timestamped rectangles (with their velocity) on every report.
+    #[props(optional)]
+    pub on_history_changed: Option<Callback<RectHistory>>,
+
+    /// Receives observation failures (element not found, measurement or script errors), e.g. to fall back
+    /// to another layout or report them. Defaults to the enclosing provider's `on_observer_error`.
+    #[props(optional)]
+    pub on_error: Option<Callback<ObserverError>>,
+
+    /// Also measures the element every animation frame while observed, reporting when it moved. Catches moves
+    /// no event announces, such as a CSS transition or animation on an ancestor. Costs a layout read per
+    /// frame, so enable it only where needed.
+    #[props(default = false)]
+    pub track_continuously: bool,
+
+    /// Makes the wrapping `div` `display: contents` and observes its first child element instead, so table
+    /// rows, flex/grid items, and child selectors see the children directly. The child should be a single
+    /// element that is not replaced while observed.
+    #[props(default = false)]
+    pub wrapperless: bool,
+
+    /// Label passed to the `MeasureBackend` with the observation (`ObserveTarget::Element::key`), to tell
+    /// elements apart without a DOM (e.g. in tests). The portal components set `anchor:<PortalId>`,
+    /// `content:<PortalId>`, and `outlet` (`outlet:<name>` for a `PortalTarget`).
+    #[props(optional)]
+    pub measure_key: Option<String>,
+
     #[props(extends = GlobalAttributes)]
     attributes: Vec<Attribute>,
 
@@ -32,54 +469,598 @@ pub struct RectObserverProps {
 /// Component that starts/stops observing its own element and sends changes to Rust.
 #[component]
 pub fn RectObserver(props: RectObserverProps) -> Element {
-    let id = use_memo(|| alloc_id());
+    let namespace = try_use_context::<ObserverNamespace>();
+    let hub = try_use_context::<RectHub>();
+    let backends = use_backends();
+    let id = use_memo(move || alloc_id(namespace.as_ref()));
+    let min_delta = props
+        .min_delta
+        .unwrap_or_else(|| min_delta_of(hub.as_ref()));
+    let options = ReportOptions {
+        throttle: props.throttle,
+        min_delta,
+        observed_box: props.observed_box,
+        continuous: use_hook(|| props.track_continuously),
+    };
+    let wrapperless = use_hook(|| props.wrapperless);
+    let measure_key = use_hook(|| props.measure_key.clone());
+    let on_error = props.on_error.or(hub.as_ref().and_then(|hub| hub.on_error));
+
+    // Read by the effects below, so they stop or restart the observation when it changes
+    let mut paused = use_signal(|| props.paused);
+    if *paused.peek() != props.paused {
+        paused.set(props.paused);
+    }
+    // Whether the element id is rendered, i.e. mounted on the client. The effects below start once it is
+    let client = use_client_mounted();
+    let mut id_rendered = use_signal(|| false);
+    if *id_rendered.peek() != client() {
+        id_rendered.set(client());
+    }
+    // Element of the wrapper, needed by backends that measure through `MountedData`
+    let mut mounted = use_signal(|| None::<Rc<MountedData>>);
+
+    // Applied to every backend, so reports below `min_delta` never reach the callbacks
+    let last_rect = use_hook(|| Rc::new(Cell::new(None)));
+    let history = use_hook(|| Rc::new(RefCell::new(RectHistory::new(props.history_len))));
+    let on_rect_changed = {
+        let target = props.on_rect_changed;
+        let on_history_changed = props.on_history_changed;
+        use_callback(move |sample: RectSample| {
+            if exceeds_delta(last_rect.get(), sample.rect, min_delta) {
+                last_rect.set(Some(sample.rect));
+                target.call(sample.rect);
+                if let Some(on_history_changed) = on_history_changed {
+                    history.borrow_mut().push(sample);
+                    let history = history.borrow().clone();
+                    on_history_changed.call(history);
+                }
+            }
+        })
+    };
+    let target = {
+        let backends = backends.clone();
+        move || ObserveTarget::Element {
+            wrapperless,
+            key: measure_key.clone(),
+            // Only read when needed, so other builds do not restart when the element mounts
+            mounted: if backends.need_mounted() {
+                mounted()
+            } else {
+                None
+            },
+        }
+    };
 
     {
         let id = id();
+        let backends = backends.clone();
+        let target = target.clone();
         use_effect(move || {
-            let js_code = js_code_of_start_observer(&id);
-            let mut eval = document::eval(&js_code);
-
-            // JS -> Rust receive loop
-            spawn(async move {
-                while let Ok(val) = eval.recv::<ObserverReport>().await {
-                    (props.on_rect_changed)(val.into());
-                }
-            });
+            if !id_rendered() {
+                return;
+            }
+            if paused() {
+                backends.unobserve(&id);
+                return;
+            }
+            let on_sample = Rc::new(move |sample| on_rect_changed.call(sample));
+            let on_error = on_error
+                .map(|on_error| Rc::new(move |error| on_error.call(error)) as Rc<dyn Fn(_)>);
+            backends.observe(&id, &target(), options, on_sample, on_error);
         });
     }
 
-    // Stop observing on unmount
+    // Visibility is watched only when requested. Pausing stops it with the rectangle
     {
         let id = id();
-        use_drop(move || {
-            let js_code = js_code_of_stop_observer(&id);
-            document::eval(&js_code);
+        let backends = backends.clone();
+        let on_visibility_changed = props.on_visibility_changed;
+        use_effect(move || {
+            let Some(on_visibility_changed) = on_visibility_changed else {
+                return;
+            };
+            if !id_rendered() || paused() {
+                return;
+            }
+            let on_visible = Rc::new(move |visible| on_visibility_changed.call(visible));
+            backends.watch_visibility(&id, &target(), on_visible);
         });
     }
 
+    // Stop observing on unmount (visibility included)
+    {
+        let id = id();
+        use_drop(move || backends.unobserve(&id));
+    }
+
+    let style = if wrapperless {
+        format!("{} display: contents;", props.style)
+    } else {
+        props.style
+    };
+
     rsx! {
         div {
-            id: id,
-            style: props.style,
+            id: id_rendered().then_some(id),
+            style: style,
+            onmounted: move |event| mounted.set(Some(event.data())),
             ..props.attributes,
             {props.children}
         }
     }
 }
 
+/// JS expression evaluating to the observed element of the `RectObserver` element `id`, also found inside
+/// shadow roots (`mgr.find`).
+fn lookup_of(id: &str, wrapperless: bool) -> String {
+    if wrapperless {
+        format!(r#"mgr.find("{id}")?.firstElementChild"#)
+    } else {
+        format!(r#"mgr.find("{id}")"#)
+    }
+}
+
+/// Where `js_code_of_start_observer` finds the observed element.
+enum Lookup {
+    /// JS expression evaluating to the element.
+    Element(String),
+    /// JS expression evaluating to a same-origin iframe, and one evaluating to the element in its document
+    /// (with the iframe bound to `frame`). The element is looked up again after every load of the iframe.
+    Frame { frame: String, element: String },
+}
+
+/// The JS observer, run through `document::eval` in the manager of `reg_key`. Created per observer, so its
+/// receive loops belong to the observer's scope.
+struct EvalBackend {
+    reg_key: String,
+    /// Size polling interval of the manager, when this observer creates it.
+    poll_ms: u64,
+    /// Hub whose channel carries the rectangles of `RectObserver` elements.
+    hub: Option<RectHub>,
+    /// Receive loops by observation id, cancelled when it stops.
+    tasks: RefCell<HashMap<String, Vec<Task>>>,
+}
+
+impl EvalBackend {
+    fn track(&self, id: &str, task: Task) {
+        self.tasks
+            .borrow_mut()
+            .entry(id.to_string())
+            .or_default()
+            .push(task);
+    }
+
+    /// The provider's `js_rect_hook`, if any.
+    fn rect_hook(&self) -> Option<&str> {
+        self.hub.as_ref().and_then(|hub| hub.rect_hook.as_deref())
+    }
+
+    /// Observes the element `lookup` evaluates to outside the hub, once on the client.
+    fn observe_lookup(
+        &self,
+        id: &str,
+        lookup: Lookup,
+        options: ReportOptions,
+        on_sample: Rc<dyn Fn(RectSample)>,
+        on_error: Option<Rc<dyn Fn(ObserverError)>>,
+    ) {
+        let reg_key = self.reg_key.clone();
+        let poll_ms = self.poll_ms;
+        let rect_hook = self.rect_hook().map(Rc::from);
+        let id = id.to_string();
+        let task = spawn({
+            let id = id.clone();
+            async move {
+                client_ready().await;
+                let mut eval = document::eval(&js_code_of_start_observer(
+                    &reg_key,
+                    poll_ms,
+                    &id,
+                    &lookup,
+                    None,
+                    options,
+                    rect_hook.as_deref(),
+                ));
+                while let Ok(message) = eval.recv::<ObserverMessage>().await {
+                    match message {
+                        ObserverMessage::Rect(report) => on_sample(report.into()),
+                        ObserverMessage::Error { error, message } => {
+                            if let Some(on_error) = &on_error {
+                                on_error(ObserverError::from_js(&error, message));
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        self.track(&id, task);
+    }
+}
+
+impl MeasureBackend for EvalBackend {
+    fn observe(
+        &self,
+        id: &str,
+        target: &ObserveTarget,
+        options: ReportOptions,
+        on_sample: Rc<dyn Fn(RectSample)>,
+        on_error: Option<Rc<dyn Fn(ObserverError)>>,
+    ) -> bool {
+        match target {
+            ObserveTarget::Element { wrapperless, .. } => {
+                let lookup = Lookup::Element(lookup_of(id, *wrapperless));
+                let hub_id = self.hub.as_ref().map(|hub| {
+                    // Reports arrive through the hub's channel
+                    hub.listeners
+                        .borrow_mut()
+                        .insert(id.to_string(), on_sample.clone());
+                    hub.id.as_str()
+                });
+                let js_code = js_code_of_start_observer(
+                    &self.reg_key,
+                    self.poll_ms,
+                    id,
+                    &lookup,
+                    hub_id,
+                    options,
+                    self.rect_hook(),
+                );
+                let mut eval = document::eval(&js_code);
+
+                // JS -> Rust receive loop. Under a hub only failures arrive here
+                if self.hub.is_some() && on_error.is_none() {
+                    return true;
+                }
+                let task = spawn(async move {
+                    while let Ok(message) = eval.recv::<ObserverMessage>().await {
+                        match message {
+                            ObserverMessage::Rect(report) => on_sample(report.into()),
+                            ObserverMessage::Error { error, message } => {
+                                if let Some(on_error) = &on_error {
+                                    on_error(ObserverError::from_js(&error, message));
+                                }
+                            }
+                        }
+                    }
+                });
+                self.track(id, task);
+            }
+            ObserveTarget::Root(PortalRoot::Selector(selector)) => {
+                let lookup =
+                    Lookup::Element(format!("document.querySelector({})", js_template(selector)));
+                self.observe_lookup(id, lookup, options, on_sample, on_error);
+            }
+            // Translated to the host viewport by the observer, which follows the frame's window
+            ObserveTarget::Frame { frame, selector } => {
+                let lookup = Lookup::Frame {
+                    frame: format!("document.querySelector({})", js_template(frame)),
+                    element: format!(
+                        "frame.contentDocument?.querySelector({})",
+                        js_template(selector)
+                    ),
+                };
+                self.observe_lookup(id, lookup, options, on_sample, on_error);
+            }
+            ObserveTarget::Root(PortalRoot::Mounted(mounted)) => {
+                // The element is only reachable through `MountedData`, so JS just reports when to re-read it
+                let reg_key = self.reg_key.clone();
+                let poll_ms = self.poll_ms;
+                let ticker_id = id.to_string();
+                let mounted = mounted.clone();
+                let task = spawn(async move {
+                    client_ready().await;
+                    let mut eval =
+                        document::eval(&js_code_of_start_ticker(&reg_key, poll_ms, &ticker_id));
+                    while let Ok(time_ms) = eval.recv::<f64>().await {
+                        if let Ok(rect) = mounted.get_client_rect().await {
+                            on_sample(RectSample {
+                                rect: rect.cast_unit(),
+                                time_ms,
+                            });
+                        }
+                    }
+                });
+                self.track(id, task);
+            }
+        }
+        true
+    }
+
+    fn watch_visibility(
+        &self,
+        id: &str,
+        target: &ObserveTarget,
+        on_visible: Rc<dyn Fn(bool)>,
+    ) -> bool {
+        let ObserveTarget::Element { wrapperless, .. } = target else {
+            return false;
+        };
+        let lookup = lookup_of(id, *wrapperless);
+        let js_code = js_code_of_start_visibility(&self.reg_key, self.poll_ms, id, &lookup);
+        let mut eval = document::eval(&js_code);
+        let task = spawn(async move {
+            while let Ok(visible) = eval.recv::<bool>().await {
+                on_visible(visible);
+            }
+        });
+        self.track(id, task);
+        true
+    }
+
+    fn unobserve(&self, id: &str) {
+        if let Some(hub) = &self.hub {
+            hub.listeners.borrow_mut().remove(id);
+        }
+        for task in self.tasks.borrow_mut().remove(id).into_iter().flatten() {
+            task.cancel();
+        }
+        if on_client() {
+            document::eval(&js_code_of_stop_observer(&self.reg_key, id));
+        }
+    }
+}
+
+/// Callbacks of the observers of a `RectHub`, by observation id.
+type Listeners = HashMap<String, Rc<dyn Fn(RectSample)>>;
+
+/// Batched rectangle channel shared by the `RectObserver`s below it.
+#[derive(Clone)]
+pub(crate) struct RectHub {
+    id: String,
+    reg_key: String,
+    frequency: MeasureFrequency,
+    /// Size polling interval used by the manager when `ResizeObserver` is unavailable.
+    poll_ms: u64,
+    /// Threshold below which rectangle changes are dropped, for the observers that do not set their own.
+    min_delta: f64,
+    listeners: Rc<RefCell<Listeners>>,
+    /// Receives the failures of the observers that do not set their own `on_error`.
+    on_error: Option<Callback<ObserverError>>,
+    /// JS function adjusting each measured rectangle before it is sent (`PortalProvider::js_rect_hook`).
+    rect_hook: Option<Rc<str>>,
+}
+
+/// Opens a `RectHub` for the calling component and provides it to its descendants.
+/// Only the values at mount time are used.
+pub(crate) fn use_rect_hub(
+    poll_ms: u64,
+    min_delta: f64,
+    frequency: MeasureFrequency,
+    on_error: Option<Callback<ObserverError>>,
+    rect_hook: Option<String>,
+) -> RectHub {
+    let namespace = try_use_context::<ObserverNamespace>();
+    let reg_key = use_hook(|| reg_key(namespace.as_ref()));
+    let eval = use_hook(has_eval);
+    let hub = use_hook(|| {
+        let hub = RectHub {
+            id: alloc_id(namespace.as_ref()),
+            reg_key: reg_key.clone(),
+            frequency,
+            poll_ms,
+            min_delta,
+            listeners: Rc::new(RefCell::new(HashMap::new())),
+            on_error,
+            rect_hook: rect_hook.map(Rc::from),
+        };
+        provide_context(hub.clone())
+    });
+
+    // A provider's `measure_backend` replaces the eval observer, which would be the only user of the channel
+    let custom_backend = try_use_context::<SharedMeasureBackend>().is_some();
+    use_hook(|| {
+        if !eval || custom_backend {
+            return;
+        }
+        let hub = hub.clone();
+        let reg_key = reg_key.clone();
+        spawn(async move {
+            // Observers registering before the channel opens have their reports kept in the hub's batch
+            client_ready().await;
+            let low_frequency = hub.frequency == MeasureFrequency::Low;
+            let mut eval = document::eval(&js_code_of_open_hub(
+                &reg_key,
+                hub.poll_ms,
+                &hub.id,
+                low_frequency,
+            ));
+            while let Ok(batch) = eval.recv::<Vec<BatchedReport>>().await {
+                for report in batch {
+                    // Release the borrow before calling, as callbacks may start or stop observers
+                    let listener = hub.listeners.borrow().get(&report.id).cloned();
+                    if let Some(listener) = listener {
+                        listener(report.rect.into());
+                    }
+                }
+            }
+        });
+    });
+
+    {
+        let hub_id = hub.id.clone();
+        use_drop(move || {
+            if eval && on_client() {
+                document::eval(&js_code_of_close_hub(&reg_key, &hub_id));
+            }
+        });
+    }
+
+    hub
+}
+
+impl RectHub {
+    /// Measures every element of the hub's registry once, including the ones observed without the hub.
+    pub(crate) fn remeasure(&self) {
+        if has_eval() && on_client() {
+            document::eval(&js_code_of_remeasure(&self.reg_key));
+        }
+    }
+}
+
+/// Tracks the rectangle of an element obtained from an `onmounted` event, in viewport coordinates.
+///
+/// The element is re-measured whenever anything scrolls or the window resizes (and on its own resizes with the
+/// `web` feature). Yields `None` until `mounted` is set and measured. Passing another element restarts tracking.
+pub fn use_element_rect(mounted: Option<Rc<MountedData>>) -> ReadSignal<Option<Rect>> {
+    use_root_rect(mounted.map(PortalRoot::Mounted)).into()
+}
+
+/// Disposes the JS registry of the enclosing `ObserverNamespace` when the calling component unmounts (SPA
+/// teardown, hot reload): remaining entries are stopped, shared observers and listeners are released, and the
+/// registry is deleted. Observers stopping afterwards find no registry and do nothing.
+pub(crate) fn use_registry_teardown() {
+    let namespace = try_use_context::<ObserverNamespace>();
+    let reg_key = use_hook(|| reg_key(namespace.as_ref()));
+    let eval = use_hook(has_eval);
+    use_drop(move || {
+        if eval && on_client() {
+            document::eval(&js_code_of_dispose_registry(&reg_key));
+        }
+    });
+}
+
+/// Tracks the rectangle of `root`. Restarts when `root` changes and yields `None` until the first measurement.
+pub(crate) fn use_root_rect(root: Option<PortalRoot>) -> Signal<Option<Rect>> {
+    use_tracked_rect(root.map(ObserveTarget::Root))
+}
+
+/// Tracks the rectangle of the element matching `selector` inside the same-origin iframe matching `frame`,
+/// translated to the coordinates of the host viewport. Yields `None` until the first measurement.
+pub(crate) fn use_frame_rect(frame: String, selector: String) -> Signal<Option<Rect>> {
+    use_tracked_rect(Some(ObserveTarget::Frame { frame, selector }))
+}
+
+/// Tracks the rectangle of `target`, restarting when it changes.
+fn use_tracked_rect(target: Option<ObserveTarget>) -> Signal<Option<Rect>> {
+    let namespace = try_use_context::<ObserverNamespace>();
+    let handle_id = use_hook(|| alloc_id(namespace.as_ref()));
+    let hub = try_use_context::<RectHub>();
+    let backends = use_backends();
+    let min_delta = min_delta_of(hub.as_ref());
+    let on_error = hub.as_ref().and_then(|hub| hub.on_error);
+    let options = ReportOptions {
+        throttle: ThrottleMode::AnimationFrame,
+        min_delta,
+        observed_box: ObservedBox::BorderBox,
+        continuous: false,
+    };
+    let mut rect = use_signal(|| None);
+    let mut current = use_signal(|| None);
+
+    // Read with `peek` so the caller does not subscribe to its own write
+    if *current.peek() != target {
+        current.set(target.clone());
+        backends.unobserve(&handle_id);
+        rect.set(None);
+
+        if let Some(target) = target {
+            let on_sample = Rc::new(move |sample: RectSample| {
+                let mut rect = rect;
+                set_if_moved(&mut rect, sample.rect, min_delta)
+            });
+            let on_error = on_error
+                .map(|on_error| Rc::new(move |error| on_error.call(error)) as Rc<dyn Fn(_)>);
+            backends.observe(&handle_id, &target, options, on_sample, on_error);
+        }
+    }
+
+    use_drop(move || backends.unobserve(&handle_id));
+
+    rect
+}
+
+/// JS template literal evaluating to `text`.
+fn js_template(text: &str) -> String {
+    let text = text.replace('\\', "\\\\").replace('`', "\\`");
+    format!("`{text}`")
+}
+
+/// Size polling interval of a manager created outside any `RectHub`.
+pub(crate) const DEFAULT_POLL_MS: u64 = 100;
+
+fn poll_ms_of(hub: Option<&RectHub>) -> u64 {
+    hub.map_or(DEFAULT_POLL_MS, |hub| hub.poll_ms)
+}
+
+/// Change threshold of observers outside any `RectHub`, in pixels.
+pub(crate) const DEFAULT_MIN_DELTA: f64 = 0.01;
+
+fn min_delta_of(hub: Option<&RectHub>) -> f64 {
+    hub.map_or(DEFAULT_MIN_DELTA, |hub| hub.min_delta)
+}
+
+/// Whether an edge of `next` moved by at least `min_delta` from `prev` (always true without `prev`).
+/// Mirrors the check of `js_code_of_start_observer`.
+fn exceeds_delta(prev: Option<Rect>, next: Rect, min_delta: f64) -> bool {
+    let Some(prev) = prev else {
+        return true;
+    };
+    let within = |a: f64, b: f64| (a - b).abs() < min_delta;
+    !(within(prev.origin.x, next.origin.x)
+        && within(prev.origin.y, next.origin.y)
+        && within(prev.size.width, next.size.width)
+        && within(prev.size.height, next.size.height))
+}
+
+fn set_if_moved(rect: &mut Signal<Option<Rect>>, next: Rect, min_delta: f64) {
+    if exceeds_delta(*rect.peek(), next, min_delta) {
+        rect.set(Some(next));
+    }
+}
+
 const ID_PREFIX: &str = "dioxus-portal-rect-observer-";
 const REG_KEY: &str = "dioxus-portal-rect-observers";
 
 static NEXT_ID: GlobalSignal<u64> = Signal::global(|| 0);
 
-fn alloc_id() -> String {
+/// Namespace for element ids and the JS registry, provided by every `PortalProvider` so that its observers
+/// do not share a registry with other providers.
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct ObserverNamespace(pub String);
+
+fn alloc_id(namespace: Option<&ObserverNamespace>) -> String {
     let n = {
         let mut w = NEXT_ID.write();
         *w += 1;
         *w
     };
-    format!("{ID_PREFIX}{}", n)
+    let salt = instance_salt();
+    match namespace {
+        Some(ObserverNamespace(ns)) => format!("{ID_PREFIX}{salt}-{ns}-{}", n),
+        None => format!("{ID_PREFIX}{salt}-{}", n),
+    }
+}
+
+fn reg_key(namespace: Option<&ObserverNamespace>) -> String {
+    let salt = instance_salt();
+    match namespace {
+        Some(ObserverNamespace(ns)) => format!("{REG_KEY}-{salt}-{ns}"),
+        None => format!("{REG_KEY}-{salt}"),
+    }
+}
+
+/// Random part of the ids and registry keys of this copy of the crate, so other Dioxus apps and other
+/// versions of this crate on the same page never produce the same ones.
+fn instance_salt() -> &'static str {
+    static SALT: OnceLock<String> = OnceLock::new();
+    SALT.get_or_init(|| {
+        let version = env!("CARGO_PKG_VERSION").replace('.', "_");
+        format!("{version}-{:08x}", random_u32())
+    })
+}
+
+#[cfg(all(feature = "web", target_arch = "wasm32"))]
+fn random_u32() -> u32 {
+    (js_sys::Math::random() * f64::from(u32::MAX)) as u32
+}
+
+/// Seeded by the OS where std has a randomness source. Constant on wasm32 without the `web` feature, where
+/// only the version part of the salt differs and apps sharing a page should set `PortalProvider::namespace`.
+#[cfg(not(all(feature = "web", target_arch = "wasm32")))]
+fn random_u32() -> u32 {
+    use std::hash::BuildHasher;
+    std::collections::hash_map::RandomState::new().hash_one(0u8) as u32
 }
 
 /// Rectangle payload sent from the JS side (serialized form).
@@ -89,6 +1070,27 @@ struct ObserverReport {
     height: f64,
     x: f64,
     y: f64,
+    #[serde(default)]
+    time: f64,
+}
+
+/// Message sent over the channel of `js_code_of_start_observer`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+enum ObserverMessage {
+    Rect(ObserverReport),
+    Error {
+        error: String,
+        #[serde(default)]
+        message: String,
+    },
+}
+
+/// One entry of a batch sent over a `RectHub` channel.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+struct BatchedReport {
+    id: String,
+    rect: ObserverReport,
 }
 
 impl From<ObserverReport> for Rect {
@@ -100,101 +1102,678 @@ impl From<ObserverReport> for Rect {
     }
 }
 
-/// Generates JS code to start observation.
-fn js_code_of_start_observer(target_id: &str) -> String {
+impl From<ObserverReport> for RectSample {
+    fn from(report: ObserverReport) -> Self {
+        RectSample {
+            time_ms: report.time,
+            rect: report.into(),
+        }
+    }
+}
+
+/// Generates JS code binding `mgr` to the shared observation manager of `reg_key`, creating it on first use.
+///
+/// The manager owns one `ResizeObserver`, one pair of window scroll/resize listeners, and one rAF flush for all
+/// tracked entries. Each entry is `{ el, report, throttle }`: `report` is called after the entry is marked
+/// dirty (its element resized, one of its scroll containers or the window scrolled, or the window resized), in
+/// the next flush, after the entry's own interval, or right away depending on `throttle`
+/// (`ThrottleMode::js_value`). The listeners are detached when the last entry is removed.
+///
+/// Entries added with `continuous` are also reported every animation frame by a loop that runs while any
+/// exists, for moves that no observer or event announces (transformed or animated ancestors).
+///
+/// Scroll listeners are attached only to the actual scroll containers among each element's ancestors, shared
+/// between the entries inside the same container. The ancestors are walked again when a scheduled element has
+/// moved to another parent. Entries without an element (tickers) still need every scroll, so a capture-phase
+/// window listener exists while any is registered.
+///
+/// Without `ResizeObserver`, the manager compares the size of every tracked element each `poll_ms` instead,
+/// for as long as it has entries. The interval is fixed by whichever caller creates the manager.
+///
+/// With `lowFrequency` (set while any open hub is `MeasureFrequency::Low`), scrolls and the continuous loop
+/// report nothing; entries are still measured when added, on resizes, and by `measureAll`. Once the last such
+/// hub closes, every entry is measured again, as scrolls were missed.
+fn js_code_of_manager(reg_key: &str, poll_ms: u64) -> String {
     format!(
         r#"
-    try {{
-      const REG_KEY = Symbol.for("{REG_KEY}");
-      const target_id = "{target_id}";
-
+      const REG_KEY = Symbol.for("{reg_key}");
       if (!globalThis[REG_KEY]) {{
-        globalThis[REG_KEY] = new Map();
+        const m = {{
+          entries: new Map(),
+          ids: new Map(), // element -> ids of the entries observing it
+          dirty: new Set(),
+          hubs: new Map(), // hub id -> {{ send, batch, lowFrequency }}
+          rafId: null,
+          listening: false,
+          sizes: new Map(), // element -> last polled size, without ResizeObserver
+          pollMs: {poll_ms},
+          pollId: null,
+          visibility: new Map(), // id -> {{ el, send }}, watched by `io`
+          io: null,
+          containers: new Map(), // scroll container -> ids of the entries inside it
+          tickers: 0, // entries without an element
+          roots: new Set(), // open shadow roots observed elements were found in
+          mo: null, // watches insertions and removals in the document while listening
+          continuous: new Set(), // ids of the entries measured every frame
+          loopId: null,
+          lowFrequency: false, // measures on adds, resizes, and `measureAll` only, while a hub asks for it
+          frames: new Map(), // id -> {{ frame, onLoad }}, entries following the loads of an iframe
+        }};
+        m.hub = (hubId) => {{
+          if (!m.hubs.has(hubId)) m.hubs.set(hubId, {{ send: null, batch: [], lowFrequency: false }});
+          return m.hubs.get(hubId);
+        }};
+        // Follows the frequencies of the open hubs, measuring everything when leaving the low frequency
+        m.updateFrequency = () => {{
+          const wasLow = m.lowFrequency;
+          m.lowFrequency = [...m.hubs.values()].some((hub) => hub.lowFrequency);
+          if (wasLow && !m.lowFrequency) m.measureAll();
+        }};
+        m.sendBatches = () => {{
+          for (const hub of m.hubs.values()) {{
+            // A hub whose channel is not open yet keeps its batch until it opens
+            if (hub.send && hub.batch.length > 0) {{
+              hub.send(hub.batch);
+              hub.batch = [];
+            }}
+          }}
+        }};
+        m.flush = () => {{
+          m.rafId = null;
+          const dirty = m.dirty;
+          m.dirty = new Set();
+          for (const id of dirty) {{
+            const entry = m.entries.get(id);
+            if (entry) entry.report();
+          }}
+          m.sendBatches();
+        }};
+        // Callers send the batches once they have scheduled every affected entry
+        m.schedule = (id) => {{
+          const entry = m.entries.get(id);
+          if (!entry) return;
+          // Measuring a removed element would report an empty rectangle, and nothing is measured in a hidden
+          // tab, which is measured again when it becomes visible
+          if (entry.removed || document.hidden) return;
+          if (entry.el && entry.el.parentNode !== entry.parent) {{
+            m.detach(id, entry);
+            m.attach(id, entry);
+          }}
+          if (entry.throttle === "immediate") {{
+            entry.report();
+          }} else if (typeof entry.throttle === "number") {{
+            if (entry.timer === null) {{
+              entry.timer = setTimeout(() => {{
+                entry.timer = null;
+                if (document.hidden) return;
+                entry.report();
+                m.sendBatches();
+              }}, entry.throttle);
+            }}
+          }} else {{
+            m.dirty.add(id);
+            if (m.rafId === null) m.rafId = requestAnimationFrame(m.flush);
+          }}
+        }};
+        // Reports the continuous entries, which only send what moved, then waits for the next frame
+        m.loop = () => {{
+          m.loopId = null;
+          if (!document.hidden && !m.lowFrequency) {{
+            for (const id of m.continuous) {{
+              const entry = m.entries.get(id);
+              if (entry && !entry.removed) entry.report();
+            }}
+            m.sendBatches();
+          }}
+          if (m.continuous.size > 0) m.loopId = requestAnimationFrame(m.loop);
+        }};
+        m.measureAll = () => {{
+          for (const id of m.entries.keys()) m.schedule(id);
+          m.sendBatches();
+        }};
+        m.onScroll = () => {{
+          if (!m.lowFrequency) m.measureAll();
+        }};
+        // One fresh measurement of every entry when the tab becomes visible again
+        m.onVisibilityChange = () => {{
+          if (!document.hidden) m.measureAll();
+        }};
+        m.onContainerScroll = (event) => {{
+          if (m.lowFrequency) return;
+          for (const id of m.containers.get(event.currentTarget) || []) m.schedule(id);
+          m.sendBatches();
+        }};
+        m.onAnyScroll = () => {{
+          if (m.lowFrequency) return;
+          for (const [id, entry] of m.entries) {{
+            if (!entry.el) m.schedule(id);
+          }}
+          m.sendBatches();
+        }};
+        // Finds an element by id in the document or in any open shadow root, trying known roots first
+        m.find = (id) => {{
+          const found = document.getElementById(id);
+          if (found) return found;
+          for (const root of m.roots) {{
+            if (!root.host.isConnected) {{
+              m.roots.delete(root);
+              continue;
+            }}
+            const el = root.getElementById(id);
+            if (el) return el;
+          }}
+          const search = (root) => {{
+            for (const node of root.querySelectorAll("*")) {{
+              if (!node.shadowRoot) continue;
+              const el = node.shadowRoot.getElementById(id) || search(node.shadowRoot);
+              if (el) return el;
+            }}
+            return null;
+          }};
+          const el = search(document);
+          if (el) m.roots.add(el.getRootNode());
+          return el;
+        }};
+        // Parent in the flattened tree, crossing slots and shadow boundaries
+        const parentOf = (node) =>
+          node.assignedSlot || node.parentElement || (node.parentNode && node.parentNode.host) || null;
+        const isScrollContainer = (node) => {{
+          const style = getComputedStyle(node);
+          return /(auto|scroll|overlay)/.test(style.overflow + style.overflowX + style.overflowY);
+        }};
+        // Listens to scrolls (and resizes, for frame windows) of `target` on behalf of the entry
+        const track = (id, entry, target) => {{
+          if (!m.containers.has(target)) {{
+            m.containers.set(target, new Set());
+            target.addEventListener("scroll", m.onContainerScroll, {{ passive: true }});
+            if (target.document) target.addEventListener("resize", m.onContainerScroll, {{ passive: true }});
+          }}
+          m.containers.get(target).add(id);
+          entry.scrollers.push(target);
+        }};
+        // Registers the entry with the scroll containers among the ancestors of its element. Elements inside
+        // same-origin iframes also follow the frame's window and the frame element's own ancestors
+        m.attach = (id, entry) => {{
+          entry.parent = entry.el.parentNode;
+          entry.scrollers = [];
+          let node = entry.el;
+          while (node) {{
+            const parent = parentOf(node);
+            if (parent) {{
+              if (isScrollContainer(parent)) track(id, entry, parent);
+              node = parent;
+              continue;
+            }}
+            const win = node.ownerDocument.defaultView;
+            if (!win || win === window || !win.frameElement) break;
+            track(id, entry, win);
+            node = win.frameElement;
+          }}
+        }};
+        m.detach = (id, entry) => {{
+          for (const node of entry.scrollers) {{
+            const ids = m.containers.get(node);
+            if (!ids) continue;
+            ids.delete(id);
+            if (ids.size === 0) {{
+              m.containers.delete(node);
+              node.removeEventListener("scroll", m.onContainerScroll);
+              node.removeEventListener("resize", m.onContainerScroll);
+            }}
+          }}
+          entry.scrollers = [];
+        }};
+        // Elements that left the document stop listening to their scroll containers; elements inserted again or
+        // moved (alone or inside an inserted subtree) walk their new ancestors and are measured
+        m.onMutation = (records) => {{
+          const added = [];
+          for (const record of records) added.push(...record.addedNodes);
+          for (const [id, entry] of m.entries) {{
+            if (!entry.el) continue;
+            if (!entry.el.isConnected) {{
+              if (!entry.removed) {{
+                m.detach(id, entry);
+                entry.parent = null;
+                entry.removed = true;
+              }}
+            }} else if (entry.removed || added.some((node) => node.contains(entry.el))) {{
+              entry.removed = false;
+              m.detach(id, entry);
+              m.attach(id, entry);
+              m.schedule(id);
+            }}
+          }}
+          m.sendBatches();
+        }};
+        m.ro = typeof ResizeObserver === "undefined" ? null : new ResizeObserver((records) => {{
+          for (const record of records) {{
+            for (const id of m.ids.get(record.target) || []) m.schedule(id);
+          }}
+          m.sendBatches();
+        }});
+        const sizeOf = (el) => {{
+          const r = el.getBoundingClientRect();
+          return `${{r.width}}x${{r.height}}`;
+        }};
+        m.poll = () => {{
+          if (document.hidden) return;
+          for (const [el, ids] of m.ids) {{
+            const size = sizeOf(el);
+            if (m.sizes.get(el) !== size) {{
+              m.sizes.set(el, size);
+              for (const id of ids) m.schedule(id);
+            }}
+          }}
+          m.sendBatches();
+        }};
+        m.add = (id, el, report, throttle, continuous = false) => {{
+          const entry = {{ el, report, throttle, timer: null, parent: null, scrollers: [], removed: false }};
+          m.entries.set(id, entry);
+          if (continuous) {{
+            m.continuous.add(id);
+            if (m.loopId === null) m.loopId = requestAnimationFrame(m.loop);
+          }}
+          if (el) {{
+            if (!m.ids.has(el)) {{
+              m.ids.set(el, new Set());
+              if (m.ro) m.ro.observe(el);
+              else m.sizes.set(el, sizeOf(el));
+            }}
+            m.ids.get(el).add(id);
+            m.attach(id, entry);
+          }} else if (m.tickers++ === 0) {{
+            window.addEventListener("scroll", m.onAnyScroll, {{ passive: true, capture: true }});
+          }}
+          if (!m.listening) {{
+            window.addEventListener("scroll", m.onScroll, {{ passive: true }});
+            window.addEventListener("resize", m.measureAll, {{ passive: true }});
+            document.addEventListener("visibilitychange", m.onVisibilityChange);
+            if (!m.ro) m.pollId = setInterval(m.poll, m.pollMs);
+            if (typeof MutationObserver !== "undefined") {{
+              m.mo = m.mo || new MutationObserver(m.onMutation);
+              m.mo.observe(document, {{ childList: true, subtree: true }});
+            }}
+            m.listening = true;
+          }}
+        }};
+        m.watch = (id, el, send) => {{
+          if (typeof IntersectionObserver === "undefined") {{
+            // Without the API the element is assumed visible
+            send(true);
+            return;
+          }}
+          if (!m.io) {{
+            m.io = new IntersectionObserver((records) => {{
+              for (const record of records) {{
+                for (const v of m.visibility.values()) {{
+                  if (v.el === record.target) v.send(record.isIntersecting);
+                }}
+              }}
+            }});
+          }}
+          m.visibility.set(id, {{ el, send }});
+          m.io.observe(el);
+        }};
+        m.unwatch = (id) => {{
+          const v = m.visibility.get(id);
+          if (!v) return;
+          m.visibility.delete(id);
+          if (![...m.visibility.values()].some((other) => other.el === v.el)) m.io.unobserve(v.el);
+        }};
+        // Stops following the loads of the iframe of entry `id`, if any
+        m.unframe = (id) => {{
+          const f = m.frames.get(id);
+          if (!f) return;
+          m.frames.delete(id);
+          f.frame.removeEventListener("load", f.onLoad);
+        }};
+        // Stops every entry and releases the shared observers and listeners; the caller frees the registry slot
+        m.dispose = () => {{
+          for (const id of [...m.frames.keys()]) m.unframe(id);
+          for (const id of [...m.entries.keys()]) m.remove(id);
+          for (const id of [...m.visibility.keys()]) m.unwatch(id);
+          if (m.ro) m.ro.disconnect();
+          if (m.io) m.io.disconnect();
+          if (m.mo) m.mo.disconnect();
+          m.hubs.clear();
+          m.roots.clear();
+        }};
+        m.remove = (id) => {{
+          m.unwatch(id);
+          const entry = m.entries.get(id);
+          if (!entry) return;
+          m.entries.delete(id);
+          m.dirty.delete(id);
+          m.continuous.delete(id);
+          if (m.continuous.size === 0 && m.loopId !== null) {{
+            cancelAnimationFrame(m.loopId);
+            m.loopId = null;
+          }}
+          if (entry.timer !== null) clearTimeout(entry.timer);
+          if (entry.el) {{
+            m.detach(id, entry);
+          }} else if (--m.tickers === 0) {{
+            window.removeEventListener("scroll", m.onAnyScroll, {{ capture: true }});
+          }}
+          const ids = entry.el && m.ids.get(entry.el);
+          if (ids) {{
+            ids.delete(id);
+            if (ids.size === 0) {{
+              m.ids.delete(entry.el);
+              if (m.ro) m.ro.unobserve(entry.el);
+              else m.sizes.delete(entry.el);
+            }}
+          }}
+          if (m.entries.size === 0 && m.listening) {{
+            window.removeEventListener("scroll", m.onScroll);
+            window.removeEventListener("resize", m.measureAll);
+            document.removeEventListener("visibilitychange", m.onVisibilityChange);
+            if (m.rafId !== null) cancelAnimationFrame(m.rafId);
+            if (m.pollId !== null) clearInterval(m.pollId);
+            if (m.mo) m.mo.disconnect();
+            m.rafId = null;
+            m.pollId = null;
+            m.listening = false;
+          }}
+        }};
+        globalThis[REG_KEY] = m;
+      }}
+      const mgr = globalThis[REG_KEY];
+"#
+    )
+}
+
+/// Generates JS code to start observation. `lookup` finds the observed element; `target_id` keys the registry
+/// entry. The element of a `Lookup::Frame` is looked up again after every load of its iframe, which replaces the
+/// iframe's document: the entry restarts on the new element, or reports it not found. With `hub_id`, rectangles are queued on that hub instead of being
+/// sent over this eval's channel. `options` sets the reported box and how often it is reported; changes where
+/// every edge moved by less than `min_delta` pixels are not sent. Failures (element not found, a throwing
+/// measurement or start) are always sent over this eval's channel as `{ error, message }` (`ObserverMessage`).
+fn js_code_of_start_observer(
+    reg_key: &str,
+    poll_ms: u64,
+    target_id: &str,
+    lookup: &Lookup,
+    hub_id: Option<&str>,
+    options: ReportOptions,
+    rect_hook: Option<&str>,
+) -> String {
+    let manager = js_code_of_manager(reg_key, poll_ms);
+    // Installed by the first observer of the registry, as the hub's channel may open after it
+    let rect_hook = match rect_hook {
+        Some(rect_hook) => format!("if (!mgr.rectHook) mgr.rectHook = ({rect_hook});"),
+        None => String::new(),
+    };
+    let hub_id = match hub_id {
+        Some(hub_id) => format!(r#""{hub_id}""#),
+        None => "null".to_string(),
+    };
+    let throttle = options.throttle.js_value();
+    let min_delta = options.min_delta;
+    let observed_box = options.observed_box.js_value();
+    let continuous = options.continuous;
+    let start = match lookup {
+        Lookup::Element(element) => format!("start({element});"),
+        Lookup::Frame { frame, element } => format!(
+            r#"
+      const frame = {frame};
+      if (!frame) {{
+        dioxus.send({{ error: "not_found" }});
+        return;
       }}
-      const reg = globalThis[REG_KEY];
-      if (reg.has(target_id)) {{
+      const onLoad = () => {{
+        try {{
+          mgr.remove(target_id);
+          start({element});
+        }} catch (e) {{
+          console.error(`start observer error: ${{e}}`);
+          dioxus.send({{ error: "script", message: `${{e}}` }});
+        }}
+      }};
+      mgr.frames.set(target_id, {{ frame, onLoad }});
+      frame.addEventListener("load", onLoad);
+      // A frame still on its initial document, before its `src` loads, reports its element once loaded
+      const doc = frame.contentDocument;
+      const pending = !doc
+        || doc.readyState !== "complete"
+        || (doc.URL === "about:blank" && frame.src && frame.src !== "about:blank");
+      if (!pending) start({element});
+"#
+        ),
+    };
+    format!(
+        r#"
+    try {{
+      {manager}
+      const target_id = "{target_id}";
+      if (mgr.entries.has(target_id) || mgr.frames.has(target_id)) {{
         // Already observing
-        // console.log("observer already started", target_id);
         return;
       }}
 
-      const el = document.getElementById(target_id);
-      if (!el) {{
-        // console.log("observer not found", target_id);
+      {rect_hook}
+      const hubId = {hub_id};
+      const minDelta = {min_delta};
+      const box = "{observed_box}";
+      const start = (el) => {{
+        if (!el) {{
+          dioxus.send({{ error: "not_found" }});
+          return;
+        }}
+        // Offset of the viewport of the (same-origin) frames the element is in, within this window
+        const frameOffset = () => {{
+          let x = 0;
+          let y = 0;
+          for (let win = el.ownerDocument.defaultView; win && win !== window && win.frameElement; ) {{
+            const frame = win.frameElement;
+            const r = frame.getBoundingClientRect();
+            const style = getComputedStyle(frame);
+            x += r.x + frame.clientLeft + (parseFloat(style.paddingLeft) || 0);
+            y += r.y + frame.clientTop + (parseFloat(style.paddingTop) || 0);
+            win = frame.ownerDocument.defaultView;
+          }}
+          return [x, y];
+        }};
+        const measure = () => {{
+          const r = el.getBoundingClientRect();
+          const [dx, dy] = el.ownerDocument === document ? [0, 0] : frameOffset();
+          if (box === "border") {{
+            return {{ width: r.width, height: r.height, x: r.x + dx, y: r.y + dy }};
+          }}
+          const style = getComputedStyle(el);
+          const px = (property) => parseFloat(style.getPropertyValue(property)) || 0;
+          const side = box === "content"
+            ? (s) => -(px(`border-${{s}}-width`) + px(`padding-${{s}}`))
+            : (s) => px(`margin-${{s}}`);
+          const [top, right, bottom, left] = ["top", "right", "bottom", "left"].map(side);
+          return {{
+            width: r.width + left + right,
+            height: r.height + top + bottom,
+            x: r.x - left + dx,
+            y: r.y - top + dy
+          }};
+        }};
+        let last = null;
+        let failed = false;
+        const sendRect = () => {{
+          let rect;
+          try {{
+            rect = measure();
+            if (mgr.rectHook) rect = mgr.rectHook(rect, el) || rect;
+          }} catch (e) {{
+            // Reported once; the entry keeps trying in case the failure is transient
+            if (!failed) {{
+              failed = true;
+              console.error(`observer measure error: ${{e}}`);
+              dioxus.send({{ error: "script", message: `${{e}}` }});
+            }}
+            return;
+          }}
+          const within = (key) => Math.abs(rect[key] - last[key]) < minDelta;
+          if (last !== null && ["width", "height", "x", "y"].every(within)) {{
+            return;
+          }}
+          last = rect;
+          rect.time = performance.now();
+          if (hubId === null) {{
+            dioxus.send(rect);
+          }} else {{
+            mgr.hub(hubId).batch.push({{ id: target_id, rect }});
+          }}
+        }};
+        mgr.add(target_id, el, sendRect, {throttle}, {continuous});
+
+        // ---- Initial send ----
+        sendRect();
+        mgr.sendBatches();
+      }};
+      {start}
+    }} catch (e) {{
+      console.error(`start observer error: ${{e}}`);
+      dioxus.send({{ error: "script", message: `${{e}}` }});
+    }}
+"#
+    )
+}
+
+/// Generates JS code that watches the visibility of the element `lookup` evaluates to, sending `true`/`false`
+/// when it changes. Registered under `target_id`, so `js_code_of_stop_observer` also stops it.
+fn js_code_of_start_visibility(
+    reg_key: &str,
+    poll_ms: u64,
+    target_id: &str,
+    lookup: &str,
+) -> String {
+    let manager = js_code_of_manager(reg_key, poll_ms);
+    format!(
+        r#"
+    try {{
+      {manager}
+      const target_id = "{target_id}";
+      if (mgr.visibility.has(target_id)) {{
         return;
       }}
 
-      // ---- rAF throttling shared logic ----
-      let rafId = null;
-      const sendRect = () => {{
-        const r = el.getBoundingClientRect();
-        const payload = {{ 
-          width: r.width,
-          height: r.height,
-          x: r.x,
-          y: r.y 
-        }};
-        // console.log("sendRect", target_id, payload);
-        dioxus.send(payload);
-      }};
-      const sendRectRaf = () => {{
-        if (rafId !== null) return; // prevent multiple schedules within the same frame
-        rafId = requestAnimationFrame(() => {{ 
-          rafId = null;
-          sendRect();
-        }});
-      }};
+      const el = {lookup};
+      if (!el) {{
+        return;
+      }}
 
-      // ---- Size change observation ----
-      const ro = new ResizeObserver(() => {{
-        sendRectRaf();
+      let last = null;
+      mgr.watch(target_id, el, (visible) => {{
+        if (visible !== last) {{
+          last = visible;
+          dioxus.send(visible);
+        }}
       }});
-      ro.observe(el);
-
-      // ---- Scroll/resize (position change) ----
-      const onScroll = () => sendRectRaf();
-      const onResize = () => sendRectRaf();
-      window.addEventListener("scroll", onScroll, {{ passive: true, capture: true }});
-      window.addEventListener("resize", onResize, {{ passive: true }});
+    }} catch (e) {{
+      console.error(`start visibility error: ${{e}}`);
+    }}
+"#
+    )
+}
 
-      // console.log("start observer", target_id);
+/// Generates JS code that sends the time (`performance.now()`) once right away and then once per frame in which
+/// anything scrolled or resized. Registered in the manager without an element, so `js_code_of_stop_observer`
+/// detaches it.
+fn js_code_of_start_ticker(reg_key: &str, poll_ms: u64, target_id: &str) -> String {
+    let manager = js_code_of_manager(reg_key, poll_ms);
+    format!(
+        r#"
+    try {{
+      {manager}
+      const target_id = "{target_id}";
+      if (mgr.entries.has(target_id)) {{
+        return;
+      }}
+      mgr.add(target_id, null, () => dioxus.send(performance.now()), "frame");
+      dioxus.send(performance.now());
+    }} catch (e) {{
+      console.error(`start ticker error: ${{e}}`);
+    }}
+"#
+    )
+}
 
-      // ---- Initial send ---- 
-      sendRect();
+/// Generates JS code to open the channel of a hub. Batches queued before it opened are sent right away.
+/// With `low_frequency`, the manager switches to `MeasureFrequency::Low` until the hub closes.
+fn js_code_of_open_hub(reg_key: &str, poll_ms: u64, hub_id: &str, low_frequency: bool) -> String {
+    let manager = js_code_of_manager(reg_key, poll_ms);
+    format!(
+        r#"
+    try {{
+      {manager}
+      const hub = mgr.hub("{hub_id}");
+      hub.lowFrequency = {low_frequency};
+      hub.send = (batch) => dioxus.send(batch);
+      mgr.updateFrequency();
+      mgr.sendBatches();
+    }} catch (e) {{
+      console.error(`open hub error: ${{e}}`);
+    }}
+"#
+    )
+}
 
-      // Store handles so we can detach later
-      reg.set(target_id, {{
-        ro,
-        onScroll,
-        onResize,
-      }});
+/// Generates JS code measuring every entry of the manager of `reg_key` once, if it exists.
+fn js_code_of_remeasure(reg_key: &str) -> String {
+    format!(
+        r#"
+    try {{
+      const mgr = globalThis[Symbol.for("{reg_key}")];
+      if (mgr) mgr.measureAll();
     }} catch (e) {{
-      console.error(`start observer error: ${{e}}`);
+      console.error(`remeasure error: ${{e}}`);
     }}
 "#
     )
 }
 
-/// Generates JS code to stop observation.
-fn js_code_of_stop_observer(target_id: &str) -> String {
+/// Generates JS code to close the channel of a hub.
+fn js_code_of_close_hub(reg_key: &str, hub_id: &str) -> String {
     format!(
         r#"
     try {{
-      const REG_KEY = Symbol.for("{REG_KEY}");
-      const target_id = "{target_id}";
+      const mgr = globalThis[Symbol.for("{reg_key}")];
+      if (mgr) {{
+        mgr.hubs.delete("{hub_id}");
+        mgr.updateFrequency();
+      }}
+    }} catch (e) {{
+      console.error(`close hub error: ${{e}}`);
+    }}
+"#
+    )
+}
 
-      const reg = globalThis[REG_KEY];
-      if (reg && reg.has(target_id)) {{
-        const rec = reg.get(target_id);
-        if (rec) {{
-          try {{ if (rec.ro) rec.ro.disconnect(); }} catch (_) {{}}
-          try {{ if (rec.onScroll) window.removeEventListener("scroll", rec.onScroll, {{ capture: true }}); }} catch (_) {{}}
-          try {{ if (rec.onResize) window.removeEventListener("resize", rec.onResize); }} catch (_) {{}}
-        }}
-        reg.delete(target_id);
+/// Generates JS code to dispose the registry of `reg_key`, so nothing of it outlives its provider.
+fn js_code_of_dispose_registry(reg_key: &str) -> String {
+    format!(
+        r#"
+    try {{
+      const key = Symbol.for("{reg_key}");
+      const mgr = globalThis[key];
+      if (mgr) {{
+        mgr.dispose();
+        delete globalThis[key];
       }}
+    }} catch (e) {{
+      console.error(`dispose registry error: ${{e}}`);
+    }}
+"#
+    )
+}
 
-      // console.log("stop observer", target_id);
+/// Generates JS code to stop observation.
+fn js_code_of_stop_observer(reg_key: &str, target_id: &str) -> String {
+    format!(
+        r#"
+    try {{
+      const mgr = globalThis[Symbol.for("{reg_key}")];
+      if (mgr) {{
+        mgr.unframe("{target_id}");
+        mgr.remove("{target_id}");
+      }}
     }} catch (e) {{
       console.error(`stop observer error: ${{e}}`);
     }}
@@ -0,0 +1,58 @@
+//! Page scroll lock for modal components.
+//!
+//! - While at least one lock is held, the root element gets `overflow: hidden`, and the width of the scrollbar it
+//!   loses is added to its `padding-right` so the page does not shift sideways. Locks are counted on the JS side,
+//!   so nested dialogs release the page only when the last one closes, and the original styles are put back then.
+//! - Everything goes through eval and is skipped without it (`PortalProvider::eval` off).
+use dioxus_lib::{core::use_drop, document, prelude::*};
+
+const SCROLL_LOCK_REG_KEY: &str = "dioxus-portal-scroll-lock";
+
+/// Holds a scroll lock while `active`, released when it turns off or the component unmounts.
+pub(crate) fn use_scroll_lock(active: bool) {
+    let mut held = use_signal(|| false);
+    use_effect(use_reactive!(|active| {
+        if active != *held.peek() {
+            set_locked(active);
+            held.set(active);
+        }
+    }));
+    use_drop(move || {
+        if *held.peek() {
+            set_locked(false);
+        }
+    });
+}
+
+fn set_locked(locked: bool) {
+    if !crate::has_eval() {
+        return;
+    }
+    document::eval(&format!(
+        r#"
+    try {{
+      const REG_KEY = Symbol.for("{SCROLL_LOCK_REG_KEY}");
+      if (!globalThis[REG_KEY]) {{
+        globalThis[REG_KEY] = {{ count: 0, saved: null }};
+      }}
+      const lock = globalThis[REG_KEY];
+      const root = document.documentElement;
+      if ({locked}) {{
+        if (lock.count++ === 0) {{
+          const scrollbar = window.innerWidth - root.clientWidth;
+          lock.saved = {{ overflow: root.style.overflow, paddingRight: root.style.paddingRight }};
+          const padding = parseFloat(getComputedStyle(root).paddingRight) || 0;
+          root.style.overflow = "hidden";
+          if (scrollbar > 0) root.style.paddingRight = `${{padding + scrollbar}}px`;
+        }}
+      }} else if (lock.count > 0 && --lock.count === 0 && lock.saved) {{
+        root.style.overflow = lock.saved.overflow;
+        root.style.paddingRight = lock.saved.paddingRight;
+        lock.saved = null;
+      }}
+    }} catch (e) {{
+      console.error(`scroll lock error: ${{e}}`);
+    }}
+"#
+    ));
+}
@@ -0,0 +1,268 @@
+//! Ready-made select built on `Portal` and `PortalMenuContent`.
+//!
+//! - `Select` renders a trigger button (`role="combobox"`) showing the label of the current value, and toggles a
+//!   listbox of `options` anchored to it. The listbox is at least as wide as the trigger and scrolls past
+//!   `max_height`. With `cover_anchor`, it is placed over the trigger instead of below it, like native macOS
+//!   selects.
+//! - The listbox is a `PortalMenuContent` with `role="listbox"` over `role="option"` items, so ArrowUp/ArrowDown,
+//!   Home/End, typeahead on the option labels, Enter, and Escape come from it. Opening focuses the selected option
+//!   (else the first enabled one) and scrolls it into view, to the top of the listbox when covering the trigger.
+//! - ArrowDown/ArrowUp on the trigger open the listbox too. Choosing an option reports it to `on_value_change`,
+//!   closes the listbox, and returns focus to the trigger. The value is internal unless `value` is set.
+use dioxus_lib::html::{GlobalAttributesExtension, Key};
+use dioxus_lib::{document, prelude::*};
+
+use crate::disclosure::use_disclosure;
+use crate::focus::use_trigger_state;
+use crate::{
+    alloc_component_id, Alignment, ContentRole, Layer, PlacementConfig, Portal, PortalAnchor,
+    PortalMenuContent, Spread,
+};
+
+/// Placement of a `Select` listbox with `cover_anchor`: over the trigger, sharing its top-left corner.
+const COVER_PLACEMENT: PlacementConfig = {
+    let mut config = PlacementConfig::bottom().align_start();
+    config.vertical.alignment = Alignment::Start;
+    config.vertical.spread = Spread::Inside;
+    config
+};
+
+/// Entry of a `Select`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SelectOption {
+    pub value: String,
+    /// Text shown in the trigger and the listbox, and matched by typeahead.
+    pub label: String,
+    pub disabled: bool,
+}
+
+impl SelectOption {
+    pub fn new(value: impl Into<String>, label: impl Into<String>) -> Self {
+        SelectOption {
+            value: value.into(),
+            label: label.into(),
+            disabled: false,
+        }
+    }
+
+    /// Makes the option unselectable. It is still shown, and skipped by the keyboard.
+    pub fn disabled(mut self) -> Self {
+        self.disabled = true;
+        self
+    }
+}
+
+/// Properties for `Select`.
+#[derive(Props, Clone, PartialEq)]
+pub struct SelectProps {
+    /// Options in display order.
+    pub options: Vec<SelectOption>,
+
+    /// Controlled value. When unset, the select keeps its own, starting from `default_value`.
+    #[props(optional, into)]
+    pub value: Option<String>,
+
+    /// Initial value of an uncontrolled select.
+    #[props(optional, into)]
+    pub default_value: Option<String>,
+
+    /// Called with the value of the chosen option.
+    #[props(default)]
+    pub on_value_change: Callback<String>,
+
+    /// Called with the requested open state of the listbox.
+    #[props(default)]
+    pub on_open_change: Callback<bool>,
+
+    /// Trigger text while no option is selected.
+    #[props(default, into)]
+    pub placeholder: String,
+
+    /// Renders an option in the listbox instead of its label.
+    #[props(optional)]
+    pub render_option: Option<Callback<SelectOption, Element>>,
+
+    /// Place the listbox over the trigger instead of using `placement`.
+    #[props(default = false)]
+    pub cover_anchor: bool,
+
+    /// Placement against the trigger.
+    #[props(default = PlacementConfig::bottom().align_start().offset(4.0).flip())]
+    pub placement: PlacementConfig,
+
+    /// Height past which the listbox scrolls, in CSS pixels.
+    #[props(default = 300.0)]
+    pub max_height: f64,
+
+    /// Stacking layer.
+    #[props(default = Layer::Dropdown.base(), into)]
+    pub layer: i32,
+
+    #[props(default = false)]
+    pub disabled: bool,
+
+    /// Declarations appended to the default style of the trigger button.
+    #[props(default)]
+    pub trigger_style: String,
+
+    /// Declarations appended to the default style of the listbox.
+    #[props(default)]
+    pub style: String,
+}
+
+/// Trigger button choosing one of `options` from an anchored listbox.
+#[component]
+pub fn Select(props: SelectProps) -> Element {
+    let listbox_id = use_hook(|| alloc_component_id("dioxus-portal-select-"));
+    let trigger_id = format!("{listbox_id}-trigger");
+    let mut disclosure = use_disclosure(None, props.on_open_change);
+    let open = disclosure.open && !props.disabled;
+    use_trigger_state(&trigger_id, &listbox_id, "listbox", open, true);
+
+    let mut internal = use_signal(|| props.default_value.clone());
+    let value = match &props.value {
+        Some(value) => Some(value.clone()),
+        None => internal(),
+    };
+    let selected = props
+        .options
+        .iter()
+        .position(|option| Some(&option.value) == value.as_ref());
+    let on_value_change = props.on_value_change;
+    let controlled = props.value.is_some();
+
+    let onmounted = {
+        let listbox_id = listbox_id.clone();
+        let cover_anchor = props.cover_anchor;
+        move |_| {
+            if crate::has_eval() {
+                document::eval(&js_code_of_show_selected(&listbox_id, cover_anchor));
+            }
+        }
+    };
+
+    let label = selected.map(|i| props.options[i].label.clone());
+    let placeholder = label.is_none();
+    let placement = if props.cover_anchor {
+        COVER_PLACEMENT
+    } else {
+        props.placement
+    };
+
+    rsx! {
+        Portal {
+            open : open,
+            layer : props.layer,
+            config : placement,
+            on_dismiss : move |_| disclosure.set(false),
+
+            PortalAnchor {
+                style : "display: inline-block;",
+                span {
+                    id : trigger_id,
+                    style : "display: contents;",
+                    button {
+                        r#type : "button",
+                        role : "combobox",
+                        disabled : props.disabled,
+                        "data-placeholder" : placeholder.then_some(""),
+                        style : "display: inline-flex; align-items: center; justify-content: space-between; gap: 8px; min-width: 160px; padding: 6px 10px; border: 1px solid #d1d5db; border-radius: 6px; background: white; font: inherit; text-align: start; {props.trigger_style}",
+                        onpointerdown : move |_| disclosure.press(),
+                        onclick : move |_| disclosure.click(),
+                        onkeydown : move |evt: KeyboardEvent| {
+                            if !open && matches!(evt.key(), Key::ArrowDown | Key::ArrowUp) {
+                                evt.prevent_default();
+                                disclosure.set(true);
+                            }
+                        },
+                        span { {label.unwrap_or_else(|| props.placeholder.clone())} }
+                        span { "aria-hidden" : "true", "▾" }
+                    }
+                }
+            }
+
+            PortalMenuContent {
+                id : listbox_id.clone(),
+                role : ContentRole::Listbox,
+                item_selector : r#"[role="option"]"#,
+                auto_focus : false,
+                match_anchor_width : true,
+                on_close : move |_| disclosure.set(false),
+                div {
+                    "data-dioxus-portal-listbox" : "",
+                    style : "position: relative; box-sizing: border-box; max-height: {props.max_height}px; overflow-y: auto; padding: 4px; border: 1px solid #e5e7eb; border-radius: 8px; background: white; box-shadow: 0 8px 24px rgba(0,0,0,.12); {props.style}",
+                    onmounted : onmounted,
+                    style { {LISTBOX_STYLE} }
+                    for (i, option) in props.options.iter().cloned().enumerate() {
+                        div {
+                            key : "{option.value}",
+                            id : "{listbox_id}-option-{i}",
+                            role : "option",
+                            tabindex : "-1",
+                            "aria-selected" : (Some(i) == selected).to_string(),
+                            "aria-disabled" : option.disabled.then_some("true"),
+                            "data-disabled" : option.disabled.then_some(""),
+                            "data-text-value" : option.label.clone(),
+                            style : "padding: 6px 8px; border-radius: 4px; cursor: default; user-select: none; outline: none;",
+                            onclick : {
+                                let option = option.clone();
+                                move |_| {
+                                    if option.disabled {
+                                        return;
+                                    }
+                                    if !controlled {
+                                        internal.set(Some(option.value.clone()));
+                                    }
+                                    on_value_change.call(option.value.clone());
+                                    disclosure.set(false);
+                                }
+                            },
+                            if let Some(render_option) = &props.render_option {
+                                {render_option.call(option.clone())}
+                            } else {
+                                {option.label.clone()}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Highlight of the focused and selected options and dimming of disabled ones.
+const LISTBOX_STYLE: &str = r#"
+[data-dioxus-portal-listbox] [role="option"]:focus { background: #f3f4f6; }
+[data-dioxus-portal-listbox] [role="option"][aria-selected="true"] { font-weight: 600; }
+[data-dioxus-portal-listbox] [role="option"][data-disabled] { opacity: 0.5; }
+"#;
+
+/// Generates JS code to focus the selected option of the listbox (else the first enabled one) and scroll the
+/// listbox so it is visible, at the top when `to_top` is set. Scrolls the listbox only, never the page.
+fn js_code_of_show_selected(listbox_id: &str, to_top: bool) -> String {
+    format!(
+        r#"
+    try {{
+      const root = document.getElementById("{listbox_id}");
+      if (!root) return;
+      const option =
+        root.querySelector(`[role="option"][aria-selected="true"]:not([aria-disabled="true"])`) ||
+        root.querySelector(`[role="option"]:not([aria-disabled="true"])`);
+      if (!option) return;
+      const list = option.offsetParent;
+      if (list && list.scrollHeight > list.clientHeight) {{
+        const top = option.offsetTop;
+        const bottom = top + option.offsetHeight;
+        if ({to_top} || top < list.scrollTop) {{
+          list.scrollTop = top;
+        }} else if (bottom > list.scrollTop + list.clientHeight) {{
+          list.scrollTop = bottom - list.clientHeight;
+        }}
+      }}
+      option.focus({{ preventScroll: true }});
+    }} catch (e) {{
+      console.error(`select error: ${{e}}`);
+    }}
+"#
+    )
+}
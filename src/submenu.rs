@@ -0,0 +1,246 @@
+//! Nested menus opened from an item of a `DropdownMenu`, `ContextMenu`, `MenubarMenu`, or another `Submenu`.
+//!
+//! - `Submenu` renders a `role="menuitem"` trigger in the parent menu and a `Portal` anchored to it, opening to the
+//!   side (flipping to the other side without room) on the parent's layer. Each menu has at most one open submenu,
+//!   recorded in its `MenuContext`.
+//! - Hovering the trigger opens the submenu after `open_delay_ms` without moving focus. Leaving it keeps the
+//!   submenu open while the pointer travels towards it (the safe polygon of `HoverCard`), so a diagonal move over
+//!   the neighboring items does not close it; otherwise it closes. Without eval, it closes after `close_delay_ms`
+//!   unless the pointer reaches it first. Leaving the submenu for the parent menu closes it after `close_delay_ms`.
+//! - ArrowRight, Enter, Space, and clicks open it and focus its first item. ArrowLeft and Escape close only the
+//!   submenu and focus its trigger. Selecting an item closes every level, and an outside click closes the whole
+//!   chain, while a press on the parent menu only closes the submenu.
+use std::rc::Rc;
+
+use dioxus_lib::core::use_drop;
+use dioxus_lib::html::Key;
+use dioxus_lib::{document, prelude::*};
+
+use crate::dropdown_menu::{MenuContext, MenuPanel, ITEM_SELECTOR};
+use crate::hover_card::js_code_of_safe_polygon;
+use crate::menu::{js_code_of_move_focus, FocusMove};
+use crate::timer::{sleep, use_delayed_toggle};
+use crate::{alloc_component_key, DismissReason, PlacementConfig, Portal, PortalAnchor};
+
+/// Properties for `Submenu`.
+#[derive(Props, Clone, PartialEq)]
+pub struct SubmenuProps {
+    /// Content of the trigger item.
+    pub label: Element,
+
+    /// Skipped by the keyboard and never opens.
+    #[props(default = false)]
+    pub disabled: bool,
+
+    /// Text matched by typeahead in the parent menu, when the label's text content does not fit.
+    #[props(optional)]
+    pub text_value: Option<String>,
+
+    /// Placement against the trigger item.
+    #[props(default = PlacementConfig::right().align_start().flip())]
+    pub placement: PlacementConfig,
+
+    /// Hover time before the submenu opens.
+    #[props(default = 100)]
+    pub open_delay_ms: u64,
+
+    /// Time the submenu stays open after the pointer left it, or the trigger when eval is unavailable.
+    #[props(default = 300)]
+    pub close_delay_ms: u64,
+
+    /// Declarations appended to the default style of the trigger item.
+    #[props(default)]
+    pub trigger_style: String,
+
+    /// Declarations appended to the default style of the submenu panel.
+    #[props(default)]
+    pub style: String,
+
+    /// `MenuItem`s, `MenuCheckboxItem`s, `MenuSeparator`s, and further `Submenu`s.
+    children: Element,
+}
+
+/// Menu item opening a nested menu to its side.
+#[component]
+pub fn Submenu(props: SubmenuProps) -> Element {
+    let mut parent = use_context::<MenuContext>();
+    let key = use_hook(alloc_component_key);
+    let content_id = format!("dioxus-portal-submenu-{key}");
+    let disabled = props.disabled;
+    let open = !disabled && *parent.open_sub.read() == Some(key);
+    let mut trigger = use_signal(|| None::<Rc<MountedData>>);
+    let mut focus_on_open = use_signal(|| false);
+
+    use_drop(move || {
+        if *parent.open_sub.peek() == Some(key) {
+            parent.open_sub.set(None);
+        }
+    });
+
+    let mut toggle = use_delayed_toggle(move |value| {
+        if value && !disabled {
+            parent.open_sub.set(Some(key));
+        } else if !value && *parent.open_sub.peek() == Some(key) {
+            parent.open_sub.set(None);
+        }
+    });
+    let open_with_focus = {
+        let content_id = content_id.clone();
+        move || {
+            if open {
+                toggle.cancel();
+                if crate::has_eval() {
+                    document::eval(&js_code_of_move_focus(
+                        &content_id,
+                        ITEM_SELECTOR,
+                        FocusMove::First,
+                        false,
+                    ));
+                }
+            } else {
+                // Read by the panel when it mounts
+                focus_on_open.set(true);
+                toggle.schedule(true, 0);
+            }
+        }
+    };
+    let mut close_to_trigger = move || {
+        toggle.schedule(false, 0);
+        if let Some(trigger) = trigger() {
+            spawn(async move {
+                let _ = trigger.set_focus(true).await;
+            });
+        }
+    };
+
+    let open_delay_ms = props.open_delay_ms;
+    let close_delay_ms = props.close_delay_ms;
+    let onpointerleave = {
+        let content_id = content_id.clone();
+        move |evt: PointerEvent| {
+            if evt.pointer_type() == "touch" {
+                return;
+            }
+            if !open {
+                // Cancels a pending open
+                toggle.cancel();
+                return;
+            }
+            if !crate::has_eval() {
+                toggle.schedule(false, close_delay_ms);
+                return;
+            }
+            let current = toggle.cancel();
+            let exit = evt.client_coordinates();
+            let mut eval = document::eval(&js_code_of_safe_polygon(&content_id, exit.x, exit.y));
+            spawn(async move {
+                let reached = eval.recv::<bool>().await.unwrap_or(false);
+                if !reached && toggle.is_current(current) {
+                    toggle.schedule(false, 0);
+                }
+            });
+        }
+    };
+
+    rsx! {
+        Portal {
+            open : open,
+            layer : *parent.layer.read(),
+            config : props.placement,
+            on_dismiss : move |reason: DismissReason| {
+                toggle.schedule(false, 0);
+                // Presses on the parent menu only close this level
+                if reason == DismissReason::OutsideClick && !*parent.pointer_inside.peek() {
+                    parent.close.call(());
+                }
+            },
+
+            PortalAnchor {
+                style : "display: block;",
+                inside_content : true,
+                div {
+                    role : "menuitem",
+                    tabindex : "-1",
+                    "aria-haspopup" : "menu",
+                    "aria-expanded" : open.to_string(),
+                    "aria-controls" : open.then(|| content_id.clone()),
+                    "aria-disabled" : disabled.then_some("true"),
+                    "data-disabled" : disabled.then_some(""),
+                    "data-state" : if open { "open" } else { "closed" },
+                    "data-text-value" : props.text_value,
+                    style : "display: flex; align-items: center; gap: 8px; padding: 6px 8px; border-radius: 4px; cursor: default; user-select: none; outline: none; {props.trigger_style}",
+                    onmounted : move |evt: MountedEvent| trigger.set(Some(evt.data())),
+                    onpointermove : move |_| {
+                        if let (false, Some(trigger)) = (disabled, trigger()) {
+                            spawn(async move {
+                                let _ = trigger.set_focus(true).await;
+                            });
+                        }
+                    },
+                    onpointerenter : move |evt: PointerEvent| {
+                        if evt.pointer_type() != "touch" {
+                            focus_on_open.set(false);
+                            toggle.schedule(true, if open { 0 } else { open_delay_ms });
+                        }
+                    },
+                    onpointerleave : onpointerleave,
+                    onclick : {
+                        let mut open_with_focus = open_with_focus.clone();
+                        move |_| open_with_focus()
+                    },
+                    onkeydown : {
+                        let mut open_with_focus = open_with_focus;
+                        move |evt: KeyboardEvent| {
+                            if evt.key() == Key::ArrowRight || evt.key() == Key::Character(" ".to_string()) {
+                                // Keeps a `MenubarMenu` from moving to the next menu
+                                evt.prevent_default();
+                                evt.stop_propagation();
+                                open_with_focus();
+                            }
+                        }
+                    },
+                    span { style : "flex: 1;", {props.label} }
+                    span { "aria-hidden" : "true", "›" }
+                }
+            }
+
+            MenuPanel {
+                id : content_id.clone(),
+                layer : *parent.layer.read(),
+                loop_focus : true,
+                auto_focus : focus_on_open(),
+                style : props.style,
+                on_close : move |_| close_to_trigger(),
+                close_all : parent.close,
+                div {
+                    onpointerenter : move |_| {
+                        // Cancels a pending close
+                        toggle.cancel();
+                    },
+                    onpointerleave : move |evt: PointerEvent| {
+                        if evt.pointer_type() == "touch" {
+                            return;
+                        }
+                        // Closes once the pointer settles on the parent menu, but not on a nested submenu or outside
+                        // (back on the trigger, its `pointerenter` takes over)
+                        let current = toggle.cancel();
+                        spawn(async move {
+                            sleep(close_delay_ms).await;
+                            if toggle.is_current(current) && *parent.pointer_inside.peek() {
+                                toggle.schedule(false, 0);
+                            }
+                        });
+                    },
+                    onkeydown : move |evt: KeyboardEvent| {
+                        if evt.key() == Key::ArrowLeft {
+                            evt.prevent_default();
+                            evt.stop_propagation();
+                            close_to_trigger();
+                        }
+                    },
+                    {props.children}
+                }
+            }
+        }
+    }
+}
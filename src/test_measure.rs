@@ -0,0 +1,207 @@
+//! Simulated measurement for component tests (`test-utils` feature).
+//!
+//! - `TestMeasure` is a `MeasureBackend` whose rectangles are set by the test instead of measured, so portal-based
+//!   components can run in a headless `VirtualDom` (or under `dioxus_ssr`) and their rendered styles be asserted.
+//! - Observers are told apart by their `measure_key`: `anchor:<PortalId>`, `content:<PortalId>:<content>` (one per
+//!   `PortalContent`, numbered in mount order), `outlet`, and `outlet:<name>` for the portal components, `root`
+//!   for a provider's `root`, and `frame` for a `PortalFrameAnchor`. Observers without a key are addressed by their observation id.
+//! - A rectangle set before its observer starts is reported when it starts, and setting it again reports it to
+//!   every observer of the key. Reports call the observers' callbacks right away; the `VirtualDom` still has to
+//!   run its effects (which start the observers) and re-render (which applies the rectangles).
+//! - `set_content_at` addresses a content by its index among the portal's contents, so the test does not need
+//!   the content's number; a rectangle set before the content is observed is reported when it starts.
+//! - Every element is reported visible.
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::rect_history::RectSample;
+use crate::rect_observer::{
+    MeasureBackend, ObserveTarget, ObserverError, Rect, ReportOptions, SharedMeasureBackend,
+};
+use crate::PortalId;
+
+/// `MeasureBackend` fed with rectangles by the test. Clones share their rectangles and observers.
+#[derive(Clone, Default)]
+pub struct TestMeasure(Rc<RefCell<TestState>>);
+
+/// Key and callback of an observation.
+type Observer = (String, Rc<dyn Fn(RectSample)>);
+
+#[derive(Default)]
+struct TestState {
+    rects: HashMap<String, Rect>,
+    /// Rectangles of contents by `content:<PortalId>` and index, for contents not observed yet.
+    contents: HashMap<(String, usize), Rect>,
+    /// Observations by id.
+    observers: HashMap<String, Observer>,
+    /// Time of the last report, advanced by one millisecond per report.
+    time_ms: f64,
+}
+
+impl TestState {
+    /// Observed keys of the contents of `portal` (`content:<PortalId>`), in mount order.
+    fn content_keys(&self, portal: &str) -> Vec<String> {
+        let mut keys = self
+            .observers
+            .values()
+            .filter_map(|(key, _)| {
+                let content = key
+                    .strip_prefix(portal)?
+                    .strip_prefix(':')?
+                    .parse::<u64>()
+                    .ok()?;
+                Some((content, key.clone()))
+            })
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys.dedup();
+        keys.into_iter().map(|(_, key)| key).collect()
+    }
+
+    fn sample(&mut self, rect: Rect) -> RectSample {
+        self.time_ms += 1.0;
+        RectSample {
+            rect,
+            time_ms: self.time_ms,
+        }
+    }
+}
+
+impl std::fmt::Debug for TestMeasure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestMeasure")
+            .field("rects", &self.0.borrow().rects)
+            .field("observed", &self.observed())
+            .finish()
+    }
+}
+
+impl TestMeasure {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Backend to pass to `PortalProvider::measure_backend`, sharing this one's rectangles.
+    pub fn backend(&self) -> SharedMeasureBackend {
+        SharedMeasureBackend::new(self.clone())
+    }
+
+    /// Sets the rectangle of the observers of `key` and reports it to those observing.
+    pub fn set_rect(&self, key: &str, rect: Rect) {
+        let reports = {
+            let mut state = self.0.borrow_mut();
+            state.rects.insert(key.to_string(), rect);
+            let callbacks = state
+                .observers
+                .values()
+                .filter(|(observed, _)| observed == key)
+                .map(|(_, on_sample)| on_sample.clone())
+                .collect::<Vec<_>>();
+            callbacks
+                .into_iter()
+                .map(|on_sample| (on_sample, state.sample(rect)))
+                .collect::<Vec<_>>()
+        };
+        // Outside the borrow, as callbacks may start or stop observers
+        for (on_sample, sample) in reports {
+            on_sample(sample);
+        }
+    }
+
+    /// Sets the rectangle of the `PortalAnchor` of portal `id`.
+    pub fn set_anchor(&self, id: PortalId, rect: Rect) {
+        self.set_rect(&format!("anchor:{id}"), rect);
+    }
+
+    /// Sets the rectangle of the first content of portal `id`, of which only the size is used.
+    pub fn set_content(&self, id: PortalId, rect: Rect) {
+        self.set_content_at(id, 0, rect);
+    }
+
+    /// Sets the rectangle of the `index`-th content of portal `id`, in the order the contents mounted.
+    pub fn set_content_at(&self, id: PortalId, index: usize, rect: Rect) {
+        let portal = format!("content:{id}");
+        let key = {
+            let mut state = self.0.borrow_mut();
+            state.contents.insert((portal.clone(), index), rect);
+            state.content_keys(&portal).into_iter().nth(index)
+        };
+        if let Some(key) = key {
+            self.set_rect(&key, rect);
+        }
+    }
+
+    /// Sets the rectangle of the provider's default outlet, or of the `PortalTarget` named `target`.
+    pub fn set_outlet(&self, target: Option<&str>, rect: Rect) {
+        match target {
+            Some(target) => self.set_rect(&format!("outlet:{target}"), rect),
+            None => self.set_rect("outlet", rect),
+        }
+    }
+
+    /// Keys currently observed, sorted, e.g. to wait until a portal's content has mounted.
+    pub fn observed(&self) -> Vec<String> {
+        let mut keys = self
+            .0
+            .borrow()
+            .observers
+            .values()
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys
+    }
+}
+
+impl MeasureBackend for TestMeasure {
+    fn observe(
+        &self,
+        id: &str,
+        target: &ObserveTarget,
+        _options: ReportOptions,
+        on_sample: Rc<dyn Fn(RectSample)>,
+        _on_error: Option<Rc<dyn Fn(ObserverError)>>,
+    ) -> bool {
+        let key = match target {
+            ObserveTarget::Element { key: Some(key), .. } => key.clone(),
+            ObserveTarget::Element { key: None, .. } => id.to_string(),
+            ObserveTarget::Root(_) => "root".to_string(),
+            ObserveTarget::Frame { .. } => "frame".to_string(),
+        };
+        let sample = {
+            let mut state = self.0.borrow_mut();
+            state
+                .observers
+                .insert(id.to_string(), (key.clone(), on_sample.clone()));
+            let rect = state.rects.get(&key).copied().or_else(|| {
+                // A content, of which the rectangle may have been set by index
+                let (portal, _) = key
+                    .rsplit_once(':')
+                    .filter(|(portal, _)| portal.starts_with("content:"))?;
+                let index = state
+                    .content_keys(portal)
+                    .iter()
+                    .position(|other| *other == key)?;
+                state.contents.get(&(portal.to_string(), index)).copied()
+            });
+            rect.map(|rect| state.sample(rect))
+        };
+        if let Some(sample) = sample {
+            on_sample(sample);
+        }
+        true
+    }
+
+    fn watch_visibility(
+        &self,
+        _id: &str,
+        _target: &ObserveTarget,
+        on_visible: Rc<dyn Fn(bool)>,
+    ) -> bool {
+        on_visible(true);
+        true
+    }
+
+    fn unobserve(&self, id: &str) {
+        self.0.borrow_mut().observers.remove(id);
+    }
+}
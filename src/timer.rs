@@ -0,0 +1,63 @@
+//! Timer utility backed by JS `setTimeout`, usable on every renderer that supports `document::eval`.
+//! Without eval (`PortalProvider::eval` off), the runtime-independent native timer of the `native` feature is used
+//! instead.
+//! The eval timer starts only on the client; on the server `sleep` never completes.
+//! `use_delayed_toggle` builds the hover delays of the ready-made components on top of it.
+use dioxus_lib::{document, prelude::*};
+
+/// Waits for `ms` milliseconds.
+pub(crate) async fn sleep(ms: u64) {
+    #[cfg(all(feature = "native", not(target_arch = "wasm32")))]
+    if !crate::has_eval() {
+        futures_timer::Delay::new(std::time::Duration::from_millis(ms)).await;
+        return;
+    }
+    crate::client::client_ready().await;
+    let mut eval = document::eval(&format!("setTimeout(() => dioxus.send(true), {ms});"));
+    let _ = eval.recv::<bool>().await;
+}
+
+/// Open state changed after a delay, unless another change is scheduled in the meantime. Obtained with
+/// `use_delayed_toggle`.
+#[derive(Clone, Copy)]
+pub(crate) struct DelayedToggle {
+    generation: Signal<u64>, // Invalidates pending changes
+    apply: Callback<bool>,
+}
+
+impl DelayedToggle {
+    /// Applies `value` after `delay_ms` (right away when 0), replacing any pending change.
+    pub fn schedule(&mut self, value: bool, delay_ms: u64) {
+        let current = self.cancel();
+        let apply = self.apply;
+        if delay_ms == 0 {
+            apply.call(value);
+            return;
+        }
+        let this = *self;
+        spawn(async move {
+            sleep(delay_ms).await;
+            if this.is_current(current) {
+                apply.call(value);
+            }
+        });
+    }
+
+    /// Drops the pending change. Returns a token that stays current until the next change or cancellation.
+    pub fn cancel(&mut self) -> u64 {
+        *self.generation.write() += 1;
+        *self.generation.peek()
+    }
+
+    /// Whether nothing was scheduled or cancelled since `token` was returned.
+    pub fn is_current(&self, token: u64) -> bool {
+        *self.generation.peek() == token
+    }
+}
+
+/// Delayed toggle calling `apply` with the scheduled values.
+pub(crate) fn use_delayed_toggle(apply: impl FnMut(bool) + 'static) -> DelayedToggle {
+    let generation = use_signal(|| 0);
+    let apply = use_callback(apply);
+    DelayedToggle { generation, apply }
+}
@@ -0,0 +1,561 @@
+//! Toast notifications rendered through the portal outlet.
+//!
+//! - `ToastProvider` (inside a `PortalProvider`) keeps the queue of toasts and renders them in one non-dismissible
+//!   portal per `ToastRegion` in use. Each region is a polite live region announcing the toasts added to it, and
+//!   the portal of the default region stays mounted while empty, so that live region exists before the first
+//!   toast is inserted and screen readers announce it.
+//! - A region shows its newest `max_visible` toasts, oldest first unless `newest_on_top`. Older ones wait
+//!   collapsed behind a "+N more" button that expands the whole stack, and their countdown only starts once shown.
+//! - `use_toast()` returns a `ToastHandle` any descendant can push toasts with (`info`, `success`, `warning`,
+//!   `error`, or `show` for a configured `Toast`). Each push returns a `ToastId` to dismiss it with later.
+//! - A toast closes itself after its duration (the provider's `duration_ms` unless set), restarted while the
+//!   pointer rests on it or focus is inside it. Closing first marks it as leaving (`data-state="closed"`) so it
+//!   fades out and collapses its height (so the toasts around it slide together), and removes it from the queue
+//!   `TOAST_EXIT_MS` later.
+use std::fmt::{self, Display};
+
+use dioxus_lib::prelude::*;
+
+use crate::timer::sleep;
+use crate::{Alignment, Layer, PlacementConfig, Portal, PortalContent, Spread};
+
+/// How long a dismissed toast stays mounted to fade out.
+const TOAST_EXIT_MS: u64 = 200;
+
+/// Corner or edge center of the outlet a toast stack sits in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ToastRegion {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    #[default]
+    BottomRight,
+}
+
+impl Display for ToastRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ToastRegion::TopLeft => "top-left",
+            ToastRegion::TopCenter => "top-center",
+            ToastRegion::TopRight => "top-right",
+            ToastRegion::BottomLeft => "bottom-left",
+            ToastRegion::BottomCenter => "bottom-center",
+            ToastRegion::BottomRight => "bottom-right",
+        })
+    }
+}
+
+impl ToastRegion {
+    const ALL: [ToastRegion; 6] = [
+        ToastRegion::TopLeft,
+        ToastRegion::TopCenter,
+        ToastRegion::TopRight,
+        ToastRegion::BottomLeft,
+        ToastRegion::BottomCenter,
+        ToastRegion::BottomRight,
+    ];
+
+    /// Placement of the stack inside the outlet.
+    pub const fn placement(self) -> PlacementConfig {
+        let mut config = PlacementConfig::bottom();
+        config.vertical.spread = Spread::Inside;
+        config.vertical.alignment = match self {
+            ToastRegion::TopLeft | ToastRegion::TopCenter | ToastRegion::TopRight => {
+                Alignment::Start
+            }
+            _ => Alignment::End,
+        };
+        config.horizontal.alignment = match self {
+            ToastRegion::TopLeft | ToastRegion::BottomLeft => Alignment::Start,
+            ToastRegion::TopCenter | ToastRegion::BottomCenter => Alignment::Center,
+            _ => Alignment::End,
+        };
+        config
+    }
+}
+
+/// Identifier of a pushed toast.
+#[derive(Copy, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct ToastId(u64);
+
+impl Display for ToastId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Toast-{}", self.0)
+    }
+}
+
+/// Severity of a toast, which sets its accent color and ARIA role (`alert` for `Error`, `status` otherwise).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ToastKind {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Display for ToastKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ToastKind::Info => "info",
+            ToastKind::Success => "success",
+            ToastKind::Warning => "warning",
+            ToastKind::Error => "error",
+        })
+    }
+}
+
+impl ToastKind {
+    fn accent(self) -> &'static str {
+        match self {
+            ToastKind::Info => "#3b82f6",
+            ToastKind::Success => "#22c55e",
+            ToastKind::Warning => "#f59e0b",
+            ToastKind::Error => "#ef4444",
+        }
+    }
+}
+
+/// Toast to push with `ToastHandle::show`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Toast {
+    pub message: String,
+    pub title: Option<String>,
+    pub kind: ToastKind,
+    /// Region to show it in. The provider's `region` when unset.
+    pub region: Option<ToastRegion>,
+    /// Time before it closes itself. The provider's `duration_ms` when unset.
+    pub duration_ms: Option<u64>,
+    /// Never close by itself, only through the close button or `ToastHandle::dismiss`.
+    pub persistent: bool,
+    /// Show a close button.
+    pub dismissible: bool,
+}
+
+impl Toast {
+    pub fn new(message: impl Into<String>) -> Self {
+        Toast {
+            message: message.into(),
+            title: None,
+            kind: ToastKind::Info,
+            region: None,
+            duration_ms: None,
+            persistent: false,
+            dismissible: true,
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn kind(mut self, kind: ToastKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn region(mut self, region: ToastRegion) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    pub fn duration_ms(mut self, duration_ms: u64) -> Self {
+        self.duration_ms = Some(duration_ms);
+        self
+    }
+
+    pub fn persistent(mut self) -> Self {
+        self.persistent = true;
+        self
+    }
+
+    /// Hides the close button.
+    pub fn not_dismissible(mut self) -> Self {
+        self.dismissible = false;
+        self
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct ToastEntry {
+    id: ToastId,
+    toast: Toast,
+    leaving: bool,
+}
+
+/// Pushes and dismisses toasts of the nearest `ToastProvider`. Cheap to copy into event handlers and tasks.
+#[derive(Clone, Copy, PartialEq)]
+pub struct ToastHandle {
+    entries: Signal<Vec<ToastEntry>>,
+    next_id: Signal<u64>,
+}
+
+impl ToastHandle {
+    /// Pushes `toast` as the newest of its region.
+    pub fn show(&self, toast: Toast) -> ToastId {
+        let mut next_id = self.next_id;
+        let id = {
+            let mut w = next_id.write();
+            *w += 1;
+            ToastId(*w)
+        };
+        let mut entries = self.entries;
+        entries.write().push(ToastEntry {
+            id,
+            toast,
+            leaving: false,
+        });
+        id
+    }
+
+    pub fn info(&self, message: impl Into<String>) -> ToastId {
+        self.show(Toast::new(message))
+    }
+
+    pub fn success(&self, message: impl Into<String>) -> ToastId {
+        self.show(Toast::new(message).kind(ToastKind::Success))
+    }
+
+    pub fn warning(&self, message: impl Into<String>) -> ToastId {
+        self.show(Toast::new(message).kind(ToastKind::Warning))
+    }
+
+    pub fn error(&self, message: impl Into<String>) -> ToastId {
+        self.show(Toast::new(message).kind(ToastKind::Error))
+    }
+
+    /// Closes a toast. Does nothing when it is already closing or gone.
+    pub fn dismiss(&self, id: ToastId) {
+        self.leave(|entry| entry.id == id);
+    }
+
+    /// Closes every toast.
+    pub fn dismiss_all(&self) {
+        self.leave(|_| true);
+    }
+
+    /// Identifiers of the toasts not closing (including the ones waiting behind `max_visible`), oldest first.
+    pub fn active(&self) -> Vec<ToastId> {
+        self.entries
+            .read()
+            .iter()
+            .filter(|entry| !entry.leaving)
+            .map(|entry| entry.id)
+            .collect()
+    }
+
+    fn leave(&self, filter: impl Fn(&ToastEntry) -> bool) {
+        let mut entries = self.entries;
+        let any = entries
+            .peek()
+            .iter()
+            .any(|entry| !entry.leaving && filter(entry));
+        if any {
+            for entry in entries.write().iter_mut() {
+                if filter(entry) {
+                    entry.leaving = true;
+                }
+            }
+        }
+    }
+
+    fn remove(&self, id: ToastId) {
+        let mut entries = self.entries;
+        entries.write().retain(|entry| entry.id != id);
+    }
+}
+
+/// Handle to the nearest `ToastProvider`. Panics outside one.
+pub fn use_toast() -> ToastHandle {
+    use_context::<ToastHandle>()
+}
+
+/// Properties for `ToastProvider`.
+#[derive(Props, Clone, PartialEq)]
+pub struct ToastProviderProps {
+    /// Time before a toast closes itself, unless it sets its own.
+    #[props(default = 5000)]
+    pub duration_ms: u64,
+
+    /// Region of the toasts that do not set one.
+    #[props(default)]
+    pub region: ToastRegion,
+
+    /// Number of toasts a region shows at once. The older ones wait behind a "+N more" button.
+    #[props(default = 3)]
+    pub max_visible: usize,
+
+    /// Stack the newest toast first instead of last.
+    #[props(default = false)]
+    pub newest_on_top: bool,
+
+    /// Accessible name of the toast regions.
+    #[props(default = "Notifications".to_string(), into)]
+    pub label: String,
+
+    /// Stacking layer.
+    #[props(default = Layer::Toast.base(), into)]
+    pub layer: i32,
+
+    /// Declarations appended to the default style of each toast.
+    #[props(default)]
+    pub toast_style: String,
+
+    children: Element,
+}
+
+/// Keeps the toast queue for `use_toast()` and renders it through the portal outlet.
+#[component]
+pub fn ToastProvider(props: ToastProviderProps) -> Element {
+    let entries = use_signal(Vec::<ToastEntry>::new);
+    let next_id = use_signal(|| 0_u64);
+    let handle = use_context_provider(|| ToastHandle { entries, next_id });
+
+    let default_region = props.region;
+    let regions = ToastRegion::ALL.into_iter().filter(|region| {
+        *region == default_region
+            || entries
+                .read()
+                .iter()
+                .any(|entry| entry.toast.region.unwrap_or(default_region) == *region)
+    });
+
+    rsx! {
+        {props.children}
+        for region in regions {
+            ToastStack {
+                key : "{region}",
+                handle : handle,
+                region : region,
+                default_region : default_region,
+                max_visible : props.max_visible,
+                newest_on_top : props.newest_on_top,
+                duration_ms : props.duration_ms,
+                label : props.label.clone(),
+                layer : props.layer,
+                toast_style : props.toast_style.clone(),
+            }
+        }
+    }
+}
+
+#[derive(Props, Clone, PartialEq)]
+struct ToastStackProps {
+    handle: ToastHandle,
+    region: ToastRegion,
+    default_region: ToastRegion,
+    max_visible: usize,
+    newest_on_top: bool,
+    duration_ms: u64,
+    label: String,
+    layer: i32,
+    toast_style: String,
+}
+
+/// Portal of one region.
+#[component]
+fn ToastStack(props: ToastStackProps) -> Element {
+    let handle = props.handle;
+    let mut expanded = use_signal(|| false);
+
+    let mut shown: Vec<ToastEntry> = handle
+        .entries
+        .read()
+        .iter()
+        .filter(|entry| entry.toast.region.unwrap_or(props.default_region) == props.region)
+        .cloned()
+        .collect();
+    let active = shown.iter().filter(|entry| !entry.leaving).count();
+    let overflow = active > props.max_visible;
+    use_effect(use_reactive!(|overflow| {
+        if !overflow {
+            expanded.set(false);
+        }
+    }));
+
+    // The oldest active toasts past `max_visible` wait unmounted, so their countdown has not started
+    let mut waiting = if expanded() {
+        0
+    } else {
+        active.saturating_sub(props.max_visible)
+    };
+    let hidden = waiting;
+    shown.retain(|entry| {
+        if !entry.leaving && waiting > 0 {
+            waiting -= 1;
+            return false;
+        }
+        true
+    });
+    if props.newest_on_top {
+        shown.reverse();
+    }
+
+    let more = overflow.then(|| {
+        rsx! {
+            div {
+                style : "display: flex; justify-content: center; padding: 4px 16px;",
+                button {
+                    r#type : "button",
+                    "data-dioxus-portal-toast-more" : "",
+                    "aria-expanded" : expanded().to_string(),
+                    style : "padding: 2px 10px; border: 1px solid #e5e7eb; border-radius: 999px; background: white; font: inherit; font-size: 0.875em; cursor: pointer; pointer-events: auto;",
+                    onclick : move |_| expanded.toggle(),
+                    if expanded() {
+                        "Show less"
+                    } else {
+                        "+{hidden} more"
+                    }
+                }
+            }
+        }
+    });
+    // Scrolls when the expanded stack outgrows the outlet
+    let scroll_style = if expanded() {
+        "max-height: 100dvh; overflow-y: auto; pointer-events: auto;"
+    } else {
+        ""
+    };
+
+    rsx! {
+        Portal {
+            open : true,
+            layer : props.layer,
+            config : props.region.placement(),
+
+            PortalContent {
+                div {
+                    role : "region",
+                    "aria-label" : props.label.clone(),
+                    // Toasts are inserted together with their text, which their own role is often not announced
+                    // for
+                    "aria-live" : "polite",
+                    "aria-relevant" : "additions",
+                    "data-dioxus-portal-toast-region" : props.region.to_string(),
+                    style : "box-sizing: border-box; display: flex; flex-direction: column; padding: 12px 0; pointer-events: none; {scroll_style}",
+                    style { {TOAST_STYLE} }
+                    if !props.newest_on_top {
+                        {more.clone()}
+                    }
+                    for entry in shown {
+                        ToastItem {
+                            key : "{entry.id.0}",
+                            handle : handle,
+                            id : entry.id,
+                            toast : entry.toast,
+                            leaving : entry.leaving,
+                            duration_ms : props.duration_ms,
+                            style : props.toast_style.clone(),
+                        }
+                    }
+                    if props.newest_on_top {
+                        {more}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Props, Clone, PartialEq)]
+struct ToastItemProps {
+    handle: ToastHandle,
+    id: ToastId,
+    toast: Toast,
+    leaving: bool,
+    duration_ms: u64,
+    style: String,
+}
+
+#[component]
+fn ToastItem(props: ToastItemProps) -> Element {
+    let handle = props.handle;
+    let id = props.id;
+    let leaving = props.leaving;
+    let duration_ms =
+        (!props.toast.persistent).then(|| props.toast.duration_ms.unwrap_or(props.duration_ms));
+    let mut generation = use_signal(|| 0_u64);
+
+    // Closes the toast after its duration, unless rescheduled or held in the meantime
+    let mut schedule = move |run: bool| {
+        *generation.write() += 1;
+        let Some(duration_ms) = duration_ms.filter(|_| run) else {
+            return;
+        };
+        let current = *generation.peek();
+        spawn(async move {
+            sleep(duration_ms).await;
+            if *generation.peek() == current {
+                handle.dismiss(id);
+            }
+        });
+    };
+    use_hook(|| schedule(true));
+
+    use_effect(use_reactive!(|leaving| {
+        if leaving {
+            spawn(async move {
+                sleep(TOAST_EXIT_MS).await;
+                handle.remove(id);
+            });
+        }
+    }));
+
+    let toast = props.toast;
+    let role = if toast.kind == ToastKind::Error {
+        "alert"
+    } else {
+        "status"
+    };
+
+    // The slot collapses its row (and the clipped padding around the card) while leaving, so the rest of the stack
+    // slides into the gap instead of jumping
+    rsx! {
+        div {
+            "data-dioxus-portal-toast-slot" : "",
+            "data-state" : if leaving { "closed" } else { "open" },
+            div {
+                div {
+                    role : role,
+                    "aria-atomic" : "true",
+                    "data-dioxus-portal-toast" : toast.kind.to_string(),
+                    style : "box-sizing: border-box; display: flex; align-items: flex-start; gap: 12px; width: min(360px, calc(100vw - 32px)); padding: 12px 16px; border-left: 4px solid {toast.kind.accent()}; border-radius: 8px; background: white; box-shadow: 0 4px 12px rgba(0,0,0,.15); pointer-events: auto; {props.style}",
+                    onpointerenter : move |_| schedule(false),
+                    onpointerleave : move |_| schedule(true),
+                    onfocusin : move |_| schedule(false),
+                    onfocusout : move |_| schedule(true),
+                    div {
+                        style : "flex: 1; min-width: 0;",
+                        if let Some(title) = &toast.title {
+                            div { style : "font-weight: 600; margin-bottom: 4px;", {title.clone()} }
+                        }
+                        div { {toast.message.clone()} }
+                    }
+                    if toast.dismissible {
+                        button {
+                            r#type : "button",
+                            "aria-label" : "Dismiss",
+                            style : "padding: 0 4px; border: none; background: none; font: inherit; font-size: 1.25em; line-height: 1; cursor: pointer;",
+                            onclick : move |_| handle.dismiss(id),
+                            "×"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Grow and fade in on entry, fade out and collapse while leaving. Collapsing animates `grid-template-rows` of the
+/// slot, whose only child clips the card and the spacing around it.
+const TOAST_STYLE: &str = r#"
+@keyframes dioxus-portal-toast-in { from { grid-template-rows: 0fr; opacity: 0; } }
+[data-dioxus-portal-toast-slot] { display: grid; grid-template-rows: 1fr; animation: dioxus-portal-toast-in 200ms ease-out; transition: grid-template-rows 200ms ease-in, opacity 200ms ease-in; }
+[data-dioxus-portal-toast-slot] > div { min-height: 0; overflow: hidden; padding: 4px 16px; transition: padding 200ms ease-in; }
+[data-dioxus-portal-toast-slot][data-state="closed"] { grid-template-rows: 0fr; opacity: 0; pointer-events: none; }
+[data-dioxus-portal-toast-slot][data-state="closed"] > div { padding-block: 0; }
+@media (prefers-reduced-motion: reduce) { [data-dioxus-portal-toast-slot], [data-dioxus-portal-toast-slot] > div { animation: none; transition: none; } }
+"#;
@@ -0,0 +1,147 @@
+//! Ready-made tooltip built on the portal primitives.
+//!
+//! - `Tooltip` wraps its children (the trigger) in a `PortalAnchor` and shows `text` (or `content`) on the
+//!   `Layer::Tooltip` layer after `delay_ms` of hover, and immediately on keyboard focus. Leaving, blurring,
+//!   pressing the trigger, and Escape (while focus is in the trigger) hide it. The tooltip stays out of the
+//!   dismissal stack, so Escape only closes the portals below once it is hidden.
+//! - The content has `role="tooltip"` and `pointer-events: none`, so it never blocks the page. While it is
+//!   shown, the trigger's first element gets `aria-describedby` pointing at it.
+//! - Tooltips inside a `PortalGroup` share its open delay and show one at a time.
+use dioxus_lib::html::{GlobalAttributesExtension, Key};
+use dioxus_lib::{document, prelude::*};
+
+use crate::timer::use_delayed_toggle;
+use crate::{
+    alloc_component_id, ContentRole, Layer, PlacementConfig, Portal, PortalAnchor, PortalArrow,
+    PortalContent,
+};
+
+/// Properties for `Tooltip`.
+#[derive(Props, Clone, PartialEq)]
+pub struct TooltipProps {
+    /// Text shown in the tooltip.
+    #[props(default, into)]
+    pub text: String,
+
+    /// Content shown instead of `text`.
+    #[props(optional)]
+    pub content: Option<Element>,
+
+    /// Hover time before the tooltip shows. Focus shows it immediately.
+    #[props(default = 300)]
+    pub delay_ms: u64,
+
+    /// Time the tooltip stays after the pointer left the trigger.
+    #[props(default = 0)]
+    pub close_delay_ms: u64,
+
+    /// Placement against the trigger.
+    #[props(default = PlacementConfig::top().offset(8.0).flip())]
+    pub placement: PlacementConfig,
+
+    /// Render a `PortalArrow` pointing at the trigger.
+    #[props(default = true)]
+    pub arrow: bool,
+
+    /// Never show the tooltip.
+    #[props(default = false)]
+    pub disabled: bool,
+
+    /// Declarations appended to the default style of the tooltip box.
+    #[props(default)]
+    pub style: String,
+
+    children: Element,
+}
+
+/// Trigger with a tooltip shown on hover and focus.
+#[component]
+pub fn Tooltip(props: TooltipProps) -> Element {
+    let tooltip_id = use_hook(|| alloc_component_id("dioxus-portal-tooltip-"));
+    let trigger_id = format!("{tooltip_id}-trigger");
+    let mut open = use_signal(|| false);
+    let mut toggle = use_delayed_toggle(move |value| open.set(value));
+    let shown = open() && !props.disabled;
+
+    // The wrapper has no box, so describe the trigger element itself
+    {
+        let tooltip_id = tooltip_id.clone();
+        let trigger_id = trigger_id.clone();
+        use_effect(use_reactive!(|shown| {
+            if crate::has_eval() {
+                document::eval(&js_code_of_describe(&trigger_id, &tooltip_id, shown));
+            }
+        }));
+    }
+
+    let delay_ms = props.delay_ms;
+    let close_delay_ms = props.close_delay_ms;
+    let content = match props.content {
+        Some(content) => content,
+        None => rsx! { {props.text} },
+    };
+
+    rsx! {
+        Portal {
+            open : shown,
+            layer : Layer::Tooltip,
+            config : props.placement,
+
+            PortalAnchor {
+                style : "display: inline-block;",
+                span {
+                    id : trigger_id,
+                    style : "display: contents;",
+                    "aria-describedby" : if shown { Some(tooltip_id.clone()) } else { None },
+                    onpointerenter : move |_| toggle.schedule(true, delay_ms),
+                    onpointerleave : move |_| toggle.schedule(false, close_delay_ms),
+                    onpointerdown : move |_| toggle.schedule(false, 0),
+                    onfocusin : move |_| toggle.schedule(true, 0),
+                    onfocusout : move |_| toggle.schedule(false, 0),
+                    // Handled here rather than through the dismissal stack, where the tooltip would shadow the
+                    // outside clicks meant for a popover below it
+                    onkeydown : move |evt: KeyboardEvent| {
+                        if shown && evt.key() == Key::Escape {
+                            evt.stop_propagation();
+                            toggle.schedule(false, 0);
+                        }
+                    },
+                    {props.children}
+                }
+            }
+
+            PortalContent {
+                id : tooltip_id,
+                role : ContentRole::Tooltip,
+                style : "pointer-events: none;",
+                div {
+                    style : "position: relative; max-width: 280px; padding: 4px 8px; border-radius: 4px; background: #111827; color: #f9fafb; font-size: 12px; line-height: 1.4; {props.style}",
+                    {content}
+                    if props.arrow {
+                        PortalArrow { size : 6.0 }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Generates JS code to point (or stop pointing) the `aria-describedby` of the trigger's first element at the
+/// tooltip.
+fn js_code_of_describe(trigger_id: &str, tooltip_id: &str, shown: bool) -> String {
+    format!(
+        r#"
+    try {{
+      const wrapper = document.getElementById("{trigger_id}");
+      const trigger = wrapper && wrapper.firstElementChild;
+      if (!trigger) return;
+      const ids = (trigger.getAttribute("aria-describedby") || "").split(" ").filter((id) => id && id !== "{tooltip_id}");
+      if ({shown}) ids.push("{tooltip_id}");
+      if (ids.length > 0) trigger.setAttribute("aria-describedby", ids.join(" "));
+      else trigger.removeAttribute("aria-describedby");
+    }} catch (e) {{
+      console.error(`tooltip describe error: ${{e}}`);
+    }}
+"#
+    )
+}
@@ -0,0 +1,414 @@
+//! Onboarding tour (spotlight) built on the portal primitives.
+//!
+//! - `Tour` walks through `steps`, each pointing at a `TourTarget` by name. Targets are measured (only while the
+//!   tour is open) and report their viewport rectangle to the enclosing `Tour`, so they must be rendered inside it.
+//! - The scrim is a `PortalOverlay` dimming the outlet around a cut-out over the current target: an element over
+//!   the target whose huge `box-shadow` darkens everything else, and which glides to the next target. A step whose
+//!   target is not mounted dims the whole outlet and centers its popover instead.
+//! - The popover is placed against the target rectangle (`anchor_rect`), and holds the step's title and text, a
+//!   progress count, and Skip, Back, and Next (Finish on the last step) buttons. ArrowLeft/ArrowRight move between
+//!   steps too. It is modal: focus is trapped inside and given back on close, and the current target is scrolled
+//!   into view.
+//! - The step is internal unless `step` is set; `on_step_change` reports every move. Finishing and skipping (the
+//!   Skip button or Escape) are reported to `on_finish` and `on_skip`, which typically turn `open` off.
+use std::collections::HashMap;
+
+use dioxus_lib::core::use_drop;
+use dioxus_lib::html::{GlobalAttributesExtension, Key};
+use dioxus_lib::{document, prelude::*};
+
+use crate::dialog::DIALOG_PLACEMENT;
+use crate::focus::{focus_first, remember_focus, return_focus, FocusTrap};
+use crate::{
+    alloc_component_id, try_use_portal_provider, ContentRole, DismissReason, Layer, OverlayClick,
+    PlacementConfig, Portal, PortalArrow, PortalContent, PortalOverlay, Rect, RectObserver,
+};
+
+/// Step of a `Tour`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TourStep {
+    /// Name of the `TourTarget` to highlight.
+    pub target: String,
+    pub title: String,
+    pub content: String,
+    /// Placement of the popover against the target. The tour's `placement` when unset.
+    pub placement: Option<PlacementConfig>,
+}
+
+impl TourStep {
+    pub fn new(
+        target: impl Into<String>,
+        title: impl Into<String>,
+        content: impl Into<String>,
+    ) -> Self {
+        TourStep {
+            target: target.into(),
+            title: title.into(),
+            content: content.into(),
+            placement: None,
+        }
+    }
+
+    pub fn placement(mut self, placement: PlacementConfig) -> Self {
+        self.placement = Some(placement);
+        self
+    }
+}
+
+/// Targets of the enclosing `Tour`.
+#[derive(Clone)]
+struct TourContext {
+    tour_id: String,
+    open: Signal<bool>,
+    targets: Signal<HashMap<String, Rect>>, // Viewport rectangles by name
+}
+
+/// Properties for `TourTarget`.
+#[derive(Props, Clone, PartialEq)]
+pub struct TourTargetProps {
+    /// Name the steps refer to.
+    #[props(into)]
+    pub name: String,
+
+    /// The highlighted element. Should be a single element; no wrapper box is added around it.
+    children: Element,
+}
+
+/// Element a `Tour` step can point at.
+#[component]
+pub fn TourTarget(props: TourTargetProps) -> Element {
+    let ctx = try_use_context::<TourContext>();
+    let name = props.name.clone();
+    {
+        let ctx = ctx.clone();
+        let name = name.clone();
+        use_drop(move || {
+            if let Some(mut ctx) = ctx {
+                ctx.targets.write().remove(&name);
+            }
+        });
+    }
+    let Some(ctx) = ctx else {
+        return rsx! {
+            {props.children}
+        };
+    };
+    let mut targets = ctx.targets;
+    let element_id = target_element_id(&ctx.tour_id, &name);
+
+    rsx! {
+        div {
+            id : element_id,
+            style : "display: contents;",
+            RectObserver {
+                wrapperless : true,
+                paused : !(ctx.open)(),
+                on_rect_changed : move |rect: Rect| {
+                    if targets.peek().get(&name) != Some(&rect) {
+                        targets.write().insert(name.clone(), rect);
+                    }
+                },
+                {props.children}
+            }
+        }
+    }
+}
+
+/// Properties for `Tour`.
+#[derive(Props, Clone, PartialEq)]
+pub struct TourProps {
+    pub steps: Vec<TourStep>,
+
+    /// Whether the tour is shown.
+    pub open: bool,
+
+    /// Controlled step index. When unset, the tour keeps its own, starting from the first step on every open.
+    #[props(optional)]
+    pub step: Option<usize>,
+
+    /// Called with the requested step index on Back and Next.
+    #[props(default)]
+    pub on_step_change: Callback<usize>,
+
+    /// Called on Finish (Next on the last step).
+    #[props(default)]
+    pub on_finish: Callback<()>,
+
+    /// Called with the current step index on Skip and Escape.
+    #[props(default)]
+    pub on_skip: Callback<usize>,
+
+    /// Placement of the popover against the target.
+    #[props(default = PlacementConfig::bottom().offset(12.0).flip())]
+    pub placement: PlacementConfig,
+
+    /// Space between the target and the edge of the cut-out, in CSS pixels.
+    #[props(default = 8.0)]
+    pub padding: f64,
+
+    /// Corner radius of the cut-out, in CSS pixels.
+    #[props(default = 8.0)]
+    pub radius: f64,
+
+    /// Color of the scrim.
+    #[props(default = "rgba(0,0,0,.5)".to_string(), into)]
+    pub scrim_color: String,
+
+    /// Skip on Escape.
+    #[props(default = true)]
+    pub close_on_escape: bool,
+
+    /// Render a `PortalArrow` pointing at the target.
+    #[props(default = true)]
+    pub arrow: bool,
+
+    #[props(default = "Skip".to_string(), into)]
+    pub skip_label: String,
+
+    #[props(default = "Back".to_string(), into)]
+    pub back_label: String,
+
+    #[props(default = "Next".to_string(), into)]
+    pub next_label: String,
+
+    #[props(default = "Finish".to_string(), into)]
+    pub finish_label: String,
+
+    /// Stacking layer.
+    #[props(default = Layer::Modal.base(), into)]
+    pub layer: i32,
+
+    /// Declarations appended to the default style of the popover box.
+    #[props(default)]
+    pub style: String,
+
+    /// Content containing the `TourTarget`s.
+    children: Element,
+}
+
+/// Guided walk through `TourTarget`s, highlighting one at a time.
+#[component]
+pub fn Tour(props: TourProps) -> Element {
+    let tour_id = use_hook(|| alloc_component_id("dioxus-portal-tour-"));
+    let title_id = format!("{tour_id}-title");
+    let count = props.steps.len();
+    let open = props.open && count > 0;
+
+    let mut open_signal = use_signal(|| open);
+    if *open_signal.peek() != open {
+        open_signal.set(open);
+    }
+    let targets = use_signal(HashMap::new);
+    use_context_provider(|| TourContext {
+        tour_id: tour_id.clone(),
+        open: open_signal,
+        targets,
+    });
+    let provider = try_use_portal_provider();
+
+    let mut internal = use_signal(|| 0_usize);
+    let controlled = props.step.is_some();
+    let step = props
+        .step
+        .unwrap_or_else(|| *internal.read())
+        .min(count.saturating_sub(1));
+    let on_step_change = props.on_step_change;
+    let mut go = move |to: usize| {
+        if !controlled {
+            internal.set(to);
+        }
+        on_step_change.call(to);
+    };
+    let on_finish = props.on_finish;
+    let on_skip = props.on_skip;
+    let last = step + 1 == count;
+    let mut next = move || {
+        if last {
+            on_finish.call(());
+        } else {
+            go(step + 1);
+        }
+    };
+    let mut back = move || {
+        if step > 0 {
+            go(step - 1);
+        }
+    };
+
+    let current = props.steps.get(step).cloned();
+    let target = current.as_ref().map(|current| current.target.clone());
+    let target_rect = target
+        .as_ref()
+        .and_then(|target| targets.read().get(target).copied());
+
+    // Restart uncontrolled tours, hand focus back on close, and bring each target into view
+    let mut was_open = use_signal(|| false);
+    {
+        let tour_id = tour_id.clone();
+        use_effect(use_reactive!(|open, target| {
+            let opened = open && !*was_open.peek();
+            if opened && !controlled {
+                internal.set(0);
+            }
+            if *was_open.peek() && !open {
+                return_focus(&tour_id, &tour_id);
+            }
+            was_open.set(open);
+            if let (true, Some(target), true) = (open, target, crate::has_eval()) {
+                document::eval(&js_code_of_scroll_into_view(&target_element_id(
+                    &tour_id, &target,
+                )));
+            }
+        }));
+    }
+    let onmounted = {
+        let tour_id = tour_id.clone();
+        move |_| {
+            remember_focus(&tour_id);
+            focus_first(&tour_id);
+        }
+    };
+
+    let placement = match (&current, target_rect) {
+        (Some(current), Some(_)) => current.placement.unwrap_or(props.placement),
+        _ => DIALOG_PLACEMENT,
+    };
+    let padding = props.padding;
+    let scrim = match target_rect {
+        Some(rect) => {
+            let origin = provider
+                .and_then(|provider| provider.viewport_to_provider(rect.origin))
+                .unwrap_or(rect.origin);
+            format!(
+                "position: absolute; left: {}px; top: {}px; width: {}px; height: {}px; border-radius: {}px; box-shadow: 0 0 0 200vmax {}; transition: left 300ms ease, top 300ms ease, width 300ms ease, height 300ms ease;",
+                origin.x - padding,
+                origin.y - padding,
+                rect.size.width + padding * 2.0,
+                rect.size.height + padding * 2.0,
+                props.radius,
+                props.scrim_color
+            )
+        }
+        None => format!(
+            "position: absolute; inset: 0; background: {};",
+            props.scrim_color
+        ),
+    };
+
+    rsx! {
+        {props.children}
+        Portal {
+            open : open,
+            layer : props.layer,
+            config : placement,
+            anchor_rect : target_rect,
+            on_dismiss : move |reason: DismissReason| {
+                if reason == DismissReason::EscapeKey {
+                    on_skip.call(step);
+                }
+            },
+            dismiss_on_escape : props.close_on_escape,
+            dismiss_on_outside_click : false,
+
+            // Blocks the page, the target included
+            PortalOverlay {
+                overlay_behavior : OverlayClick::Block,
+                div {
+                    style : "position: absolute; inset: 0; overflow: hidden;",
+                    div { "data-dioxus-portal-tour-spotlight" : "", style : scrim }
+                }
+            }
+
+            PortalContent {
+                id : tour_id.clone(),
+                role : ContentRole::Dialog,
+                aria_labelledby : Some(title_id.clone()),
+                div {
+                    style : "display: contents;",
+                    onmounted : onmounted,
+                    FocusTrap {
+                        container_id : tour_id.clone(),
+                        div {
+                            style : "position: relative; box-sizing: border-box; max-width: min(360px, calc(100vw - 32px)); padding: 16px; border-radius: 8px; background: white; box-shadow: 0 12px 32px rgba(0,0,0,.2); outline: none; {props.style}",
+                            onkeydown : move |evt: KeyboardEvent| {
+                                match evt.key() {
+                                    Key::ArrowRight => next(),
+                                    Key::ArrowLeft => back(),
+                                    _ => {}
+                                }
+                            },
+                            if let Some(current) = &current {
+                                div {
+                                    id : title_id.clone(),
+                                    style : "margin-bottom: 8px; font-weight: 600;",
+                                    {current.title.clone()}
+                                }
+                                div { {current.content.clone()} }
+                            }
+                            div {
+                                style : "display: flex; align-items: center; gap: 8px; margin-top: 16px;",
+                                span {
+                                    style : "flex: 1; color: #6b7280; font-size: 0.875em;",
+                                    "{step + 1} / {count}"
+                                }
+                                if !last {
+                                    button {
+                                        r#type : "button",
+                                        style : "padding: 4px 8px; border: none; background: none; color: #6b7280; font: inherit; cursor: pointer;",
+                                        onclick : move |_| on_skip.call(step),
+                                        {props.skip_label.clone()}
+                                    }
+                                }
+                                if step > 0 {
+                                    button {
+                                        r#type : "button",
+                                        style : "padding: 4px 12px; border: 1px solid #d1d5db; border-radius: 6px; background: white; font: inherit; cursor: pointer;",
+                                        onclick : move |_| back(),
+                                        {props.back_label.clone()}
+                                    }
+                                }
+                                button {
+                                    r#type : "button",
+                                    style : "padding: 4px 12px; border: 1px solid #2563eb; border-radius: 6px; background: #2563eb; color: white; font: inherit; cursor: pointer;",
+                                    onclick : move |_| next(),
+                                    if last {
+                                        {props.finish_label.clone()}
+                                    } else {
+                                        {props.next_label.clone()}
+                                    }
+                                }
+                            }
+                            if props.arrow && target_rect.is_some() {
+                                PortalArrow {}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn target_element_id(tour_id: &str, name: &str) -> String {
+    format!("{tour_id}-target-{name}")
+}
+
+/// Generates JS code to scroll the element wrapped by `wrapper_id` into the middle of the view, unless it is
+/// already fully visible.
+fn js_code_of_scroll_into_view(wrapper_id: &str) -> String {
+    format!(
+        r#"
+    try {{
+      let el = document.getElementById("{wrapper_id}");
+      while (el && getComputedStyle(el).display === "contents") el = el.firstElementChild;
+      if (!el) return;
+      const r = el.getBoundingClientRect();
+      if (r.top >= 0 && r.left >= 0 && r.bottom <= window.innerHeight && r.right <= window.innerWidth) return;
+      const reduce = window.matchMedia("(prefers-reduced-motion: reduce)").matches;
+      el.scrollIntoView({{ block: "center", inline: "nearest", behavior: reduce ? "auto" : "smooth" }});
+    }} catch (e) {{
+      console.error(`tour scroll error: ${{e}}`);
+    }}
+"#
+    )
+}
@@ -0,0 +1,713 @@
+//! `web-sys` backend of the rect observer (`web` feature, wasm32 only).
+//!
+//! - Mirrors the JS manager of `rect_observer`: one `ResizeObserver`, one pair of window `scroll`/`resize`
+//!   listeners, scroll listeners on the scroll containers of the tracked elements only, and one rAF flush,
+//!   but kept in Rust and driven through `web-sys`.
+//! - No JS source is generated and nothing is serialized: rectangles are read with `getBoundingClientRect`
+//!   in the flush and passed straight to the observers' callbacks.
+//! - A `MutationObserver` on the document re-walks the scroll containers of elements that were moved, and
+//!   stops measuring elements while they are out of the document.
+//! - Nothing is measured while the document is hidden; every element is measured once when it is shown again.
+//! - The manager is thread-local, so no global JS registry is involved.
+//! - Every entry point reports whether it took over the observation; when the page has no `ResizeObserver`,
+//!   the caller falls back to the eval observer.
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use euclid::{Point2D, SideOffsets2D, Size2D};
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{
+    AddEventListenerOptions, Element, Event, HtmlElement, IntersectionObserver,
+    IntersectionObserverEntry, MutationObserver, MutationObserverInit, MutationRecord, Node,
+    NodeList, ResizeObserver, ResizeObserverEntry, ShadowRoot,
+};
+
+use crate::direct_position::Placed;
+use crate::rect_history::RectSample;
+use crate::rect_observer::{
+    MeasureBackend, ObserveTarget, ObservedBox, ObserverError, Rect, ReportOptions, ThrottleMode,
+};
+use crate::PortalRoot;
+
+type Report = Rc<dyn Fn()>;
+
+/// A tracked element and the function called when it is flushed.
+struct Entry {
+    el: Element,
+    report: Report,
+    throttle: ThrottleMode,
+    timer: Option<i32>,      // Pending `ThrottleMode::IntervalMs` report
+    parent: Option<Node>,    // Parent when the ancestors were walked
+    scrollers: Vec<Element>, // Scroll containers among the ancestors
+    removed: bool, // Out of the document, without scroll listeners until it is inserted again
+}
+
+struct Manager {
+    entries: HashMap<String, Entry>,
+    dirty: HashSet<String>,
+    frame_requested: bool,
+    listening: bool,
+    // Scroll containers with a listener and the ids of the entries inside them (elements are not `Hash`)
+    containers: Vec<(Element, HashSet<String>)>,
+    resize_observer: ResizeObserver,
+    // Watches insertions and removals in the document while anything is observed
+    mutation_observer: Option<MutationObserver>,
+    // The closures stay alive for as long as the manager, which lives until the page is gone
+    _on_resize: Closure<dyn FnMut(js_sys::Array)>,
+    _on_mutation: Closure<dyn FnMut(js_sys::Array)>,
+    on_scroll: Closure<dyn FnMut()>,
+    on_visibility_change: Closure<dyn FnMut()>,
+    on_container_scroll: Closure<dyn FnMut(Event)>,
+    on_frame: Closure<dyn FnMut()>,
+}
+
+/// `IntersectionObserver` watching the visibility of one element.
+struct VisibilityWatcher {
+    observer: IntersectionObserver,
+    _on_change: Closure<dyn FnMut(js_sys::Array)>,
+}
+
+thread_local! {
+    static MANAGER: RefCell<Option<Manager>> = const { RefCell::new(None) };
+    static VISIBILITY: RefCell<HashMap<String, VisibilityWatcher>> = RefCell::new(HashMap::new());
+}
+
+/// Runs `f` on the manager, creating it on first use. Returns `None` when the page has no `ResizeObserver`.
+fn with_manager<R>(f: impl FnOnce(&mut Manager) -> R) -> Option<R> {
+    MANAGER.with(|manager| {
+        let mut manager = manager.borrow_mut();
+        if manager.is_none() {
+            *manager = Manager::new();
+        }
+        manager.as_mut().map(f)
+    })
+}
+
+impl Manager {
+    fn new() -> Option<Manager> {
+        // The callbacks run from the browser's event loop, never while the manager is borrowed
+        let on_resize = Closure::<dyn FnMut(js_sys::Array)>::new(|records: js_sys::Array| {
+            let targets = records
+                .iter()
+                .filter_map(|record| record.dyn_into::<ResizeObserverEntry>().ok())
+                .map(|record| record.target())
+                .collect::<Vec<_>>();
+            let reports = with_manager(|m| {
+                let ids = m
+                    .entries
+                    .iter()
+                    .filter(|(_, entry)| targets.contains(&entry.el))
+                    .map(|(id, _)| id.clone())
+                    .collect::<Vec<_>>();
+                m.schedule_all(ids)
+            })
+            .unwrap_or_default();
+            for report in reports {
+                report();
+            }
+        });
+        let on_mutation = Closure::<dyn FnMut(js_sys::Array)>::new(|records: js_sys::Array| {
+            let added = records
+                .iter()
+                .filter_map(|record| record.dyn_into::<MutationRecord>().ok())
+                .flat_map(|record| {
+                    let nodes = record.added_nodes();
+                    (0..nodes.length()).filter_map(move |i| nodes.item(i))
+                })
+                .collect::<Vec<_>>();
+            let reports = with_manager(|m| m.reattach(&added)).unwrap_or_default();
+            for report in reports {
+                report();
+            }
+        });
+        let on_scroll = Closure::<dyn FnMut()>::new(|| {
+            let reports = with_manager(|m| {
+                let ids = m.entries.keys().cloned().collect::<Vec<_>>();
+                m.schedule_all(ids)
+            })
+            .unwrap_or_default();
+            for report in reports {
+                report();
+            }
+        });
+        // One fresh measurement of every entry when the tab becomes visible again
+        let on_visibility_change = Closure::<dyn FnMut()>::new(|| {
+            if document_hidden() {
+                return;
+            }
+            let reports = with_manager(|m| {
+                let ids = m.entries.keys().cloned().collect::<Vec<_>>();
+                m.schedule_all(ids)
+            })
+            .unwrap_or_default();
+            for report in reports {
+                report();
+            }
+        });
+        let on_container_scroll = Closure::<dyn FnMut(Event)>::new(|event: Event| {
+            let Some(target) = event
+                .current_target()
+                .and_then(|target| target.dyn_into::<Element>().ok())
+            else {
+                return;
+            };
+            let reports = with_manager(|m| {
+                let ids = m
+                    .containers
+                    .iter()
+                    .find(|(node, _)| *node == target)
+                    .map(|(_, ids)| ids.iter().cloned().collect())
+                    .unwrap_or_default();
+                m.schedule_all(ids)
+            })
+            .unwrap_or_default();
+            for report in reports {
+                report();
+            }
+        });
+        let on_frame = Closure::<dyn FnMut()>::new(|| {
+            let reports = with_manager(|m| {
+                m.frame_requested = false;
+                std::mem::take(&mut m.dirty)
+                    .iter()
+                    .filter_map(|id| m.entries.get(id))
+                    .map(|entry| entry.report.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+            // Called after the borrow is released, as reports may start or stop observers
+            for report in reports {
+                report();
+            }
+        });
+
+        let resize_observer = ResizeObserver::new(on_resize.as_ref().unchecked_ref()).ok()?;
+        let mutation_observer = MutationObserver::new(on_mutation.as_ref().unchecked_ref()).ok();
+        Some(Manager {
+            entries: HashMap::new(),
+            dirty: HashSet::new(),
+            frame_requested: false,
+            listening: false,
+            containers: Vec::new(),
+            resize_observer,
+            mutation_observer,
+            _on_resize: on_resize,
+            _on_mutation: on_mutation,
+            on_scroll,
+            on_visibility_change,
+            on_container_scroll,
+            on_frame,
+        })
+    }
+
+    /// Marks the entries dirty according to their `ThrottleMode`. Returns the reports of `Immediate` entries,
+    /// which the caller runs once the manager is no longer borrowed.
+    fn schedule_all(&mut self, ids: Vec<String>) -> Vec<Report> {
+        ids.into_iter().filter_map(|id| self.schedule(id)).collect()
+    }
+
+    fn schedule(&mut self, id: String) -> Option<Report> {
+        let entry = self.entries.get_mut(&id)?;
+        if entry.removed || document_hidden() {
+            // Measuring a removed element would report an empty rectangle, and nothing is measured in a hidden
+            // tab, which is measured again when it becomes visible
+            return None;
+        }
+        if entry.el.parent_node() != entry.parent {
+            let scrollers = std::mem::take(&mut entry.scrollers);
+            self.detach(&id, scrollers);
+            self.attach(&id);
+        }
+        let entry = self.entries.get_mut(&id)?;
+        match entry.throttle {
+            ThrottleMode::Immediate => Some(entry.report.clone()),
+            ThrottleMode::IntervalMs(ms) => {
+                if entry.timer.is_none() {
+                    let on_timeout = Closure::once_into_js(move || {
+                        let report = with_manager(|m| {
+                            let entry = m.entries.get_mut(&id)?;
+                            entry.timer = None;
+                            (!document_hidden()).then(|| entry.report.clone())
+                        })
+                        .flatten();
+                        if let Some(report) = report {
+                            report();
+                        }
+                    });
+                    entry.timer = web_sys::window().and_then(|window| {
+                        window
+                            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                                on_timeout.unchecked_ref(),
+                                i32::try_from(ms).unwrap_or(i32::MAX),
+                            )
+                            .ok()
+                    });
+                }
+                None
+            }
+            ThrottleMode::AnimationFrame => {
+                self.dirty.insert(id);
+                if !self.frame_requested {
+                    if let Some(window) = web_sys::window() {
+                        let requested =
+                            window.request_animation_frame(self.on_frame.as_ref().unchecked_ref());
+                        self.frame_requested = requested.is_ok();
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    /// Follows DOM mutations: entries whose element left the document stop listening to their scroll
+    /// containers, and entries whose element was inserted again or moved (alone or inside a subtree in `added`)
+    /// walk their new ancestors and are measured. Returns the reports of `Immediate` entries.
+    fn reattach(&mut self, added: &[Node]) -> Vec<Report> {
+        let mut removed = Vec::new();
+        let mut moved = Vec::new();
+        for (id, entry) in &self.entries {
+            let el: &Node = &entry.el;
+            if !el.is_connected() {
+                if !entry.removed {
+                    removed.push(id.clone());
+                }
+            } else if entry.removed || added.iter().any(|node| node.contains(Some(el))) {
+                moved.push(id.clone());
+            }
+        }
+        for id in &removed {
+            let Some(entry) = self.entries.get_mut(id) else {
+                continue;
+            };
+            entry.removed = true;
+            entry.parent = None;
+            let scrollers = std::mem::take(&mut entry.scrollers);
+            self.detach(id, scrollers);
+        }
+        for id in &moved {
+            let Some(entry) = self.entries.get_mut(id) else {
+                continue;
+            };
+            entry.removed = false;
+            let scrollers = std::mem::take(&mut entry.scrollers);
+            self.detach(id, scrollers);
+            self.attach(id);
+        }
+        self.schedule_all(moved)
+    }
+
+    /// Registers the entry `id` with the scroll containers among the ancestors of its element.
+    fn attach(&mut self, id: &str) {
+        let Some(entry) = self.entries.get_mut(id) else {
+            return;
+        };
+        entry.parent = entry.el.parent_node();
+        let mut scrollers = Vec::new();
+        let mut node = parent_of(&entry.el);
+        while let Some(el) = node {
+            if is_scroll_container(&el) {
+                scrollers.push(el.clone());
+            }
+            node = parent_of(&el);
+        }
+        entry.scrollers = scrollers.clone();
+
+        for scroller in scrollers {
+            match self
+                .containers
+                .iter_mut()
+                .find(|(node, _)| *node == scroller)
+            {
+                Some((_, ids)) => {
+                    ids.insert(id.to_string());
+                }
+                None => {
+                    let _ = scroller
+                        .add_event_listener_with_callback_and_add_event_listener_options(
+                            "scroll",
+                            self.on_container_scroll.as_ref().unchecked_ref(),
+                            &passive(),
+                        );
+                    self.containers
+                        .push((scroller, HashSet::from([id.to_string()])));
+                }
+            }
+        }
+    }
+
+    fn detach(&mut self, id: &str, scrollers: Vec<Element>) {
+        for scroller in scrollers {
+            let Some(i) = self
+                .containers
+                .iter()
+                .position(|(node, _)| *node == scroller)
+            else {
+                continue;
+            };
+            self.containers[i].1.remove(id);
+            if self.containers[i].1.is_empty() {
+                let (node, _) = self.containers.swap_remove(i);
+                let _ = node.remove_event_listener_with_callback(
+                    "scroll",
+                    self.on_container_scroll.as_ref().unchecked_ref(),
+                );
+            }
+        }
+    }
+
+    fn add(&mut self, id: String, el: Element, report: Report, throttle: ThrottleMode) {
+        self.resize_observer.observe(&el);
+        self.entries.insert(
+            id.clone(),
+            Entry {
+                el,
+                report,
+                throttle,
+                timer: None,
+                parent: None,
+                scrollers: Vec::new(),
+                removed: false,
+            },
+        );
+        self.attach(&id);
+
+        if !self.listening {
+            if let Some(window) = web_sys::window() {
+                let on_scroll = self.on_scroll.as_ref().unchecked_ref();
+                let _ = window.add_event_listener_with_callback_and_add_event_listener_options(
+                    "scroll",
+                    on_scroll,
+                    &passive(),
+                );
+                let _ = window.add_event_listener_with_callback_and_add_event_listener_options(
+                    "resize",
+                    on_scroll,
+                    &passive(),
+                );
+                self.listening = true;
+            }
+            let document = web_sys::window().and_then(|window| window.document());
+            if let Some(document) = &document {
+                let _ = document.add_event_listener_with_callback(
+                    "visibilitychange",
+                    self.on_visibility_change.as_ref().unchecked_ref(),
+                );
+            }
+            if let (Some(observer), Some(document)) = (&self.mutation_observer, document) {
+                let options = MutationObserverInit::new();
+                options.set_child_list(true);
+                options.set_subtree(true);
+                let _ = observer.observe_with_options(&document, &options);
+            }
+        }
+    }
+
+    fn remove(&mut self, id: &str) {
+        let Some(entry) = self.entries.remove(id) else {
+            return;
+        };
+        self.dirty.remove(id);
+        if let (Some(timer), Some(window)) = (entry.timer, web_sys::window()) {
+            window.clear_timeout_with_handle(timer);
+        }
+        self.detach(id, entry.scrollers.clone());
+        // Several entries may observe the same element
+        if !self.entries.values().any(|other| other.el == entry.el) {
+            self.resize_observer.unobserve(&entry.el);
+        }
+
+        if self.entries.is_empty() && self.listening {
+            if let Some(window) = web_sys::window() {
+                let on_scroll = self.on_scroll.as_ref().unchecked_ref();
+                let _ = window.remove_event_listener_with_callback("scroll", on_scroll);
+                let _ = window.remove_event_listener_with_callback("resize", on_scroll);
+            }
+            if let Some(document) = web_sys::window().and_then(|window| window.document()) {
+                let _ = document.remove_event_listener_with_callback(
+                    "visibilitychange",
+                    self.on_visibility_change.as_ref().unchecked_ref(),
+                );
+            }
+            if let Some(observer) = &self.mutation_observer {
+                observer.disconnect();
+            }
+            self.listening = false;
+        }
+    }
+}
+
+/// Whether the document is hidden (e.g. a background tab).
+fn document_hidden() -> bool {
+    web_sys::window()
+        .and_then(|window| window.document())
+        .is_some_and(|document| document.hidden())
+}
+
+fn passive() -> AddEventListenerOptions {
+    let options = AddEventListenerOptions::new();
+    options.set_passive(true);
+    options
+}
+
+/// Parent of `el` in the flattened tree, crossing slots and shadow boundaries.
+fn parent_of(el: &Element) -> Option<Element> {
+    if let Some(slot) = el.assigned_slot() {
+        return Some(slot.into());
+    }
+    el.parent_element().or_else(|| {
+        el.parent_node()?
+            .dyn_into::<ShadowRoot>()
+            .ok()
+            .map(|root| root.host())
+    })
+}
+
+/// Whether `el` scrolls its overflow (`overflow` is `auto`, `scroll`, or `overlay` on either axis).
+fn is_scroll_container(el: &Element) -> bool {
+    let Some(style) =
+        web_sys::window().and_then(|window| window.get_computed_style(el).ok().flatten())
+    else {
+        return false;
+    };
+    ["overflow-x", "overflow-y"].iter().any(|property| {
+        let value = style.get_property_value(property).unwrap_or_default();
+        matches!(value.as_str(), "auto" | "scroll" | "overlay")
+    })
+}
+
+/// Current time of the page clock (`performance.now()`), in milliseconds.
+fn now_ms() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or(0.0, |performance| performance.now())
+}
+
+/// Rectangle of the `observed_box` of `el` in viewport coordinates.
+fn rect_of(el: &Element, observed_box: ObservedBox) -> Rect {
+    let r = el.get_bounding_client_rect();
+    let rect = Rect::new(
+        Point2D::new(r.x(), r.y()),
+        Size2D::new(r.width(), r.height()),
+    );
+    if observed_box == ObservedBox::BorderBox {
+        return rect;
+    }
+    let Some(style) =
+        web_sys::window().and_then(|window| window.get_computed_style(el).ok().flatten())
+    else {
+        return rect;
+    };
+    let px = |property: String| {
+        style
+            .get_property_value(&property)
+            .ok()
+            .and_then(|value| value.trim_end_matches("px").parse::<f64>().ok())
+            .unwrap_or(0.0)
+    };
+    let sides = |side: &dyn Fn(&str) -> f64| {
+        SideOffsets2D::new(side("top"), side("right"), side("bottom"), side("left"))
+    };
+    match observed_box {
+        ObservedBox::BorderBox => rect,
+        ObservedBox::ContentBox => rect.inner_rect(sides(&|s| {
+            px(format!("border-{s}-width")) + px(format!("padding-{s}"))
+        })),
+        ObservedBox::MarginBox => rect.outer_rect(sides(&|s| px(format!("margin-{s}")))),
+    }
+}
+
+/// Starts observing `el` under `id`, calling `on_rect` with its `options.observed_box` now and whenever it may have
+/// moved or resized, as often as `options.throttle` allows.
+/// Returns `false` when the page has no `ResizeObserver`, and for `options.continuous`, which only the eval
+/// observer measures every frame.
+pub(crate) fn observe(
+    id: &str,
+    el: Element,
+    options: ReportOptions,
+    on_rect: impl Fn(RectSample) + 'static,
+) -> bool {
+    if options.continuous {
+        return false;
+    }
+    let report_el = el.clone();
+    let report: Report = Rc::new(move || {
+        on_rect(RectSample {
+            rect: rect_of(&report_el, options.observed_box),
+            time_ms: now_ms(),
+        })
+    });
+    let added = with_manager(|m| {
+        if m.entries.contains_key(id) {
+            return false;
+        }
+        m.add(id.to_string(), el, report.clone(), options.throttle);
+        true
+    });
+    if added == Some(true) {
+        report();
+    }
+    added.is_some()
+}
+
+/// Starts observing the element of `root` under `id`. Returns `false` when it cannot be observed natively
+/// (no `ResizeObserver`, or `MountedData` not backed by a `web_sys::Element`).
+pub(crate) fn observe_root(
+    id: &str,
+    root: &PortalRoot,
+    options: ReportOptions,
+    on_sample: impl Fn(RectSample) + 'static,
+) -> bool {
+    let el = match root {
+        PortalRoot::Selector(selector) => query_selector(selector),
+        PortalRoot::Mounted(mounted) => mounted.downcast::<Element>().cloned(),
+    };
+    el.is_some_and(|el| observe(id, el, options, on_sample))
+}
+
+/// Calls `on_visible` with whether `el` is visible in the viewport and its scroll containers, once after the
+/// start and then on every change. Returns `false` when the page has no `IntersectionObserver`.
+pub(crate) fn watch_visibility(
+    id: &str,
+    el: &Element,
+    on_visible: impl Fn(bool) + 'static,
+) -> bool {
+    let on_change = Closure::<dyn FnMut(js_sys::Array)>::new(move |records: js_sys::Array| {
+        // Only the latest record of a batch matters
+        let latest = records
+            .iter()
+            .filter_map(|record| record.dyn_into::<IntersectionObserverEntry>().ok())
+            .last();
+        if let Some(record) = latest {
+            on_visible(record.is_intersecting());
+        }
+    });
+    let Ok(observer) = IntersectionObserver::new(on_change.as_ref().unchecked_ref()) else {
+        return false;
+    };
+    observer.observe(el);
+    let watcher = VisibilityWatcher {
+        observer,
+        _on_change: on_change,
+    };
+    let replaced =
+        VISIBILITY.with(|watchers| watchers.borrow_mut().insert(id.to_string(), watcher));
+    if let Some(replaced) = replaced {
+        replaced.observer.disconnect();
+    }
+    true
+}
+
+/// Stops the observation registered under `id`, if any, including its visibility watcher.
+pub(crate) fn unobserve(id: &str) {
+    with_manager(|m| m.remove(id));
+    let watcher = VISIBILITY.with(|watchers| watchers.borrow_mut().remove(id));
+    if let Some(watcher) = watcher {
+        watcher.observer.disconnect();
+    }
+}
+
+/// Sets `top`/`left` and the custom properties of the elements whose `attribute` matches each key. Returns `false`
+/// when there is no document, so the caller writes them with eval instead.
+pub(crate) fn set_positions(attribute: &str, positions: &[(String, Placed)]) -> bool {
+    if web_sys::window()
+        .and_then(|window| window.document())
+        .is_none()
+    {
+        return false;
+    }
+    for (key, placed) in positions {
+        let Some(el) = query_selector(&format!(r#"[{attribute}="{key}"]"#)) else {
+            continue;
+        };
+        if let Some(el) = el.dyn_ref::<HtmlElement>() {
+            let style = el.style();
+            let _ = style.set_property("top", &format!("{}px", placed.top));
+            let _ = style.set_property("left", &format!("{}px", placed.left));
+            for (name, value) in &placed.properties {
+                let _ = style.set_property(name, &format!("{value}px"));
+            }
+        }
+    }
+    true
+}
+
+/// `MeasureBackend` of this module.
+pub(crate) struct WebSysBackend;
+
+impl MeasureBackend for WebSysBackend {
+    fn observe(
+        &self,
+        id: &str,
+        target: &ObserveTarget,
+        options: ReportOptions,
+        on_sample: Rc<dyn Fn(RectSample)>,
+        _on_error: Option<Rc<dyn Fn(ObserverError)>>,
+    ) -> bool {
+        match target {
+            ObserveTarget::Element { wrapperless, .. } => observed_element(id, *wrapperless)
+                .is_some_and(|el| observe(id, el, options, move |sample| on_sample(sample))),
+            ObserveTarget::Root(root) => {
+                observe_root(id, root, options, move |sample| on_sample(sample))
+            }
+            // Left to the eval observer, which translates frame coordinates
+            ObserveTarget::Frame { .. } => false,
+        }
+    }
+
+    fn watch_visibility(
+        &self,
+        id: &str,
+        target: &ObserveTarget,
+        on_visible: Rc<dyn Fn(bool)>,
+    ) -> bool {
+        match target {
+            ObserveTarget::Element { wrapperless, .. } => observed_element(id, *wrapperless)
+                .is_some_and(|el| watch_visibility(id, &el, move |visible| on_visible(visible))),
+            ObserveTarget::Root(_) | ObserveTarget::Frame { .. } => false,
+        }
+    }
+
+    fn unobserve(&self, id: &str) {
+        unobserve(id);
+    }
+}
+
+/// Element observed by the `RectObserver` element `id`: the element itself, or its first child element when
+/// `wrapperless`.
+pub(crate) fn observed_element(id: &str, wrapperless: bool) -> Option<Element> {
+    let el = element_by_id(id)?;
+    if wrapperless {
+        el.first_element_child()
+    } else {
+        Some(el)
+    }
+}
+
+/// Element with the given id, in the document or in any open shadow root.
+fn element_by_id(id: &str) -> Option<Element> {
+    let document = web_sys::window()?.document()?;
+    document
+        .get_element_by_id(id)
+        .or_else(|| find_in_shadow_roots(document.query_selector_all("*").ok()?, id))
+}
+
+/// Searches the open shadow roots of `nodes`, and those nested in them, for the element `id`.
+fn find_in_shadow_roots(nodes: NodeList, id: &str) -> Option<Element> {
+    (0..nodes.length())
+        .filter_map(|i| nodes.item(i)?.dyn_into::<Element>().ok()?.shadow_root())
+        .find_map(|root| {
+            root.get_element_by_id(id)
+                .or_else(|| find_in_shadow_roots(root.query_selector_all("*").ok()?, id))
+        })
+}
+
+/// First element matching `selector`.
+fn query_selector(selector: &str) -> Option<Element> {
+    web_sys::window()?
+        .document()?
+        .query_selector(selector)
+        .ok()?
+}
@@ -0,0 +1,60 @@
+//! An anchored portal opened in the browser is placed where the layout says, against the measured anchor. Run
+//! with `wasm-pack test --headless --chrome --features e2e`.
+#![cfg(all(target_arch = "wasm32", feature = "e2e"))]
+
+use dioxus_lib::prelude::*;
+use dioxus_portal::e2e::{viewport, E2eHarness};
+use dioxus_portal::*;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn app() -> Element {
+    let mut open = use_signal(|| false);
+    rsx! {
+        PortalProvider {
+            div {
+                style: "position: absolute; top: 120px; left: 200px;",
+                Portal {
+                    open: open(),
+                    vertical_offset: 8.0,
+                    PortalAnchor {
+                        button {
+                            id: "trigger",
+                            style: "width: 100px; height: 30px;",
+                            onclick: move |_| open.set(true),
+                            "open"
+                        }
+                    }
+                    PortalContent {
+                        div {
+                            "data-testid": "menu",
+                            style: "width: 160px; height: 80px;",
+                            "menu"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+async fn opens_below_the_trigger() {
+    let harness = E2eHarness::mount(app).await;
+    assert!(harness.query("[data-testid=menu]").is_none());
+
+    harness.click("#trigger").await;
+
+    harness.assert_placed(
+        "#trigger",
+        "[data-testid=menu]",
+        AxisParam {
+            offset: 8.0,
+            ..AxisParam::VERTICAL
+        },
+        AxisParam::HORIZONTAL,
+        viewport(),
+        0.5,
+    );
+}
@@ -0,0 +1,70 @@
+//! Portals rendered in the outlet's shadow root (`PortalProviderProps::shadow_root`) still dispatch events to the
+//! handlers inside them. Run with `wasm-pack test --headless --chrome --features e2e`.
+#![cfg(all(target_arch = "wasm32", feature = "e2e"))]
+
+use std::cell::Cell;
+
+use dioxus_lib::prelude::*;
+use dioxus_portal::e2e::E2eHarness;
+use dioxus_portal::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+thread_local! {
+    static CLICKS: Cell<u32> = const { Cell::new(0) };
+}
+
+fn app() -> Element {
+    rsx! {
+        PortalProvider {
+            shadow_root: true,
+            Portal {
+                open: true,
+                PortalAnchor { div { "anchor" } }
+                PortalContent {
+                    button {
+                        id: "inside",
+                        onclick: move |_| CLICKS.with(|clicks| clicks.set(clicks.get() + 1)),
+                        "inside"
+                    }
+                }
+            }
+        }
+    }
+}
+
+// The button in the shadow root of an outlet, which `document.querySelector` does not reach
+fn shadow_button() -> Option<HtmlElement> {
+    let document = web_sys::window()?.document()?;
+    let hosts = document
+        .query_selector_all("[data-dioxus-portal-shadow-host]")
+        .ok()?;
+    (0..hosts.length())
+        .filter_map(|i| {
+            hosts
+                .item(i)?
+                .dyn_into::<web_sys::Element>()
+                .ok()?
+                .shadow_root()
+        })
+        .find_map(|root| root.query_selector("#inside").ok().flatten())
+        .and_then(|button| button.dyn_into::<HtmlElement>().ok())
+}
+
+#[wasm_bindgen_test]
+async fn click_in_shadow_root_reaches_handler() {
+    let harness = E2eHarness::mount(app).await;
+    assert!(
+        harness.query("#inside").is_none(),
+        "the content should be in the shadow root"
+    );
+    let button = shadow_button().expect("no button in an outlet's shadow root");
+
+    button.click();
+    harness.settle().await;
+
+    assert_eq!(CLICKS.with(Cell::get), 1);
+}
@@ -0,0 +1,215 @@
+//! Portals placed against rectangles fed by `TestMeasure`, rendered in a headless `VirtualDom`.
+use std::cell::Cell;
+
+use dioxus_lib::core::{use_drop, Mutation, Mutations};
+use dioxus_lib::prelude::*;
+use dioxus_portal::*;
+
+thread_local! {
+    static MEASURE: TestMeasure = TestMeasure::new();
+}
+
+fn app() -> Element {
+    let backend = MEASURE.with(|measure| measure.backend());
+    rsx! {
+        PortalProvider {
+            measure_backend: backend,
+            Portal {
+                open: true,
+                PortalAnchor { div { "anchor" } }
+                PortalContent { div { "content" } }
+            }
+        }
+    }
+}
+
+// Runs the effects (which start the observers) and the renders (which apply the rectangles), returning the
+// style attributes written on the way
+fn settle(dom: &mut VirtualDom) -> Vec<String> {
+    let mut styles = Vec::new();
+    for _ in 0..5 {
+        dom.process_events();
+        let mutations: Mutations = dom.render_immediate_to_vec();
+        styles.extend(mutations.edits.into_iter().filter_map(|edit| match edit {
+            Mutation::SetAttribute {
+                name: "style",
+                value,
+                ..
+            } => Some(format!("{value:?}")),
+            _ => None,
+        }));
+    }
+    styles
+}
+
+fn observed_key(measure: &TestMeasure, prefix: &str) -> String {
+    measure
+        .observed()
+        .into_iter()
+        .find(|key| key.starts_with(prefix))
+        .unwrap_or_else(|| panic!("no `{prefix}` observer in {measure:?}"))
+}
+
+#[test]
+fn content_is_placed_against_the_measured_anchor() {
+    let measure = MEASURE.with(|measure| measure.clone());
+    let mut dom = VirtualDom::new(app);
+    dom.rebuild_in_place();
+    settle(&mut dom);
+
+    measure.set_outlet(None, Rect::from_xywh(0.0, 0.0, 800.0, 600.0));
+    settle(&mut dom);
+    let anchor = observed_key(&measure, "anchor:");
+    measure.set_rect(&anchor, Rect::from_xywh(100.0, 100.0, 50.0, 20.0));
+    // The content is observed once there is an anchor to place it against
+    settle(&mut dom);
+    let content = observed_key(&measure, "content:");
+    measure.set_rect(&content, Rect::from_xywh(0.0, 0.0, 80.0, 30.0));
+    let styles = settle(&mut dom);
+
+    // Below the anchor (the default placement), centered on it
+    let placed = styles.last().expect("the content was not placed");
+    assert!(placed.contains("top: 120px; left: 85px;"), "{placed}");
+    assert!(placed.contains("opacity: 1;"), "{placed}");
+}
+
+static SECOND: GlobalSignal<bool> = Signal::global(|| false);
+
+thread_local! {
+    static HANDLE: Cell<Option<PortalProviderHandle>> = const { Cell::new(None) };
+    static SCRIMS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn keyed_app() -> Element {
+    let backend = MEASURE.with(|measure| measure.backend());
+    rsx! {
+        PortalProvider {
+            measure_backend: backend,
+            Probe {}
+            // Two instances in turn, the second one mounted before the first one unmounts
+            if SECOND() {
+                Portal {
+                    key: "{SECOND()}",
+                    portal_key: "keyed",
+                    open: true,
+                    anchor_rect: Rect::from_xywh(0.0, 0.0, 10.0, 10.0),
+                    Body {}
+                }
+            } else {
+                Portal {
+                    key: "{SECOND()}",
+                    portal_key: "keyed",
+                    open: true,
+                    anchor_rect: Rect::from_xywh(0.0, 0.0, 10.0, 10.0),
+                    Body {}
+                }
+            }
+        }
+    }
+}
+
+// Content and overlay in a component of its own, which does not re-render with the portal
+#[component]
+fn Body() -> Element {
+    rsx! {
+        PortalContent { div { "content" } }
+        PortalOverlay { Scrim {} }
+    }
+}
+
+// Counts the rendered overlays
+#[component]
+fn Scrim() -> Element {
+    use_hook(|| SCRIMS.with(|scrims| scrims.set(scrims.get() + 1)));
+    use_drop(|| SCRIMS.with(|scrims| scrims.set(scrims.get() - 1)));
+    rsx! {}
+}
+
+// Hands the provider's handle to the test
+#[component]
+fn Probe() -> Element {
+    let handle = use_portal_provider();
+    HANDLE.with(|cell| cell.set(Some(handle)));
+    rsx! {}
+}
+
+#[test]
+fn replacing_a_keyed_portal_keeps_the_new_one_registered() {
+    let mut dom = VirtualDom::new(keyed_app);
+    dom.rebuild_in_place();
+    MEASURE.with(|measure| measure.set_outlet(None, Rect::from_xywh(0.0, 0.0, 800.0, 600.0)));
+    settle(&mut dom);
+    assert_eq!(SCRIMS.with(Cell::get), 1);
+
+    // The second portal registers under the same id before the first one unmounts
+    dom.in_runtime(|| *SECOND.write() = true);
+    settle(&mut dom);
+
+    let handle = HANDLE
+        .with(|cell| cell.take())
+        .expect("the provider did not render");
+    let snapshot = dom.in_runtime(|| handle.debug_snapshot());
+    assert_eq!(snapshot.portals.len(), 1, "{snapshot:#?}");
+    assert_eq!(snapshot.portals[0].contents.len(), 1, "{snapshot:#?}");
+    assert_eq!(SCRIMS.with(Cell::get), 1);
+}
+
+thread_local! {
+    static PORTAL_ID: Cell<Option<PortalId>> = const { Cell::new(None) };
+}
+
+fn two_contents_app() -> Element {
+    let backend = MEASURE.with(|measure| measure.backend());
+    rsx! {
+        PortalProvider {
+            measure_backend: backend,
+            Portal {
+                open: true,
+                anchor_rect: Rect::from_xywh(100.0, 100.0, 50.0, 20.0),
+                IdProbe {}
+                PortalContent { div { "first" } }
+                PortalContent { div { "second" } }
+            }
+        }
+    }
+}
+
+// Hands the portal's id to the test
+#[component]
+fn IdProbe() -> Element {
+    let id = use_portal_id();
+    PORTAL_ID.with(|cell| cell.set(Some(id)));
+    rsx! {}
+}
+
+#[test]
+fn each_content_is_placed_with_its_own_size() {
+    let measure = MEASURE.with(|measure| measure.clone());
+    let mut dom = VirtualDom::new(two_contents_app);
+    dom.rebuild_in_place();
+    let id = PORTAL_ID
+        .with(Cell::get)
+        .expect("the portal did not render");
+
+    // Set before the contents are observed
+    measure.set_outlet(None, Rect::from_xywh(0.0, 0.0, 800.0, 600.0));
+    measure.set_content_at(id, 0, Rect::from_xywh(0.0, 0.0, 80.0, 30.0));
+    measure.set_content_at(id, 1, Rect::from_xywh(0.0, 0.0, 40.0, 10.0));
+    // The contents register and start their observers over several renders
+    let mut styles = settle(&mut dom);
+    styles.extend(settle(&mut dom));
+
+    // Both below the anchor, each centered on it with its own width
+    assert!(
+        styles
+            .iter()
+            .any(|style| style.contains("top: 120px; left: 85px;")),
+        "{styles:#?}"
+    );
+    assert!(
+        styles
+            .iter()
+            .any(|style| style.contains("top: 120px; left: 105px;")),
+        "{styles:#?}"
+    );
+}